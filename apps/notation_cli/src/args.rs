@@ -0,0 +1,125 @@
+use clap::{Parser, Subcommand, ValueEnum};
+
+#[derive(Parser, Debug)]
+#[clap(name = "notation", author, version, about, long_about = None)]
+pub struct NotationCli {
+    #[clap(subcommand)]
+    pub command: NotationCommand,
+}
+
+/// The input can be a `.ron` tab, a `.rs` file with `tab!`, a plain DSL file,
+/// or any of the imported formats, e.g. `.mid`, `.musicxml`, `.gp5` or `.cho`.
+#[derive(Subcommand, Debug)]
+pub enum NotationCommand {
+    /// Parse a tab and write it as validated RON
+    Parse {
+        input: String,
+        /// Defaults to the input with `.ron` extension
+        #[clap(short, long)]
+        output: Option<String>,
+    },
+    /// Print the tracks, sections, bar count and duration of a tab
+    Info { input: String },
+    /// Move the key, notes and fretted tracks of a tab by semitones
+    #[clap(allow_negative_numbers = true)]
+    Transpose {
+        input: String,
+        semitones: i8,
+        /// Move the capo of fretted tracks instead of rewriting the frets
+        #[clap(long)]
+        capo: bool,
+        #[clap(short, long)]
+        output: String,
+    },
+    /// Add a guitar track picking the melody of a vocal or piano track
+    Finger {
+        input: String,
+        /// Id of the melody track
+        #[clap(short, long)]
+        track: String,
+        /// Id of the added guitar track
+        #[clap(long, default_value = "guitar")]
+        id: String,
+        /// Name of the guitar tuning, e.g. `DropD` or `OpenG`
+        #[clap(long, default_value = "Standard")]
+        tuning: String,
+        #[clap(long, default_value_t = 0)]
+        capo: u8,
+        /// Frets between the index finger and the highest fretted note
+        #[clap(long, default_value_t = 3)]
+        max_stretch: u8,
+        /// Play the notes on open strings when possible
+        #[clap(long)]
+        prefer_open: bool,
+        #[clap(short, long)]
+        output: String,
+    },
+    /// Add a guitar track playing the chords of a chord track
+    Voice {
+        input: String,
+        /// Id of the chord track
+        #[clap(short, long)]
+        track: String,
+        /// Id of the added guitar track
+        #[clap(long, default_value = "guitar")]
+        id: String,
+        /// Name of the guitar tuning, e.g. `DropD` or `OpenG`
+        #[clap(long, default_value = "Standard")]
+        tuning: String,
+        #[clap(long, default_value_t = 0)]
+        capo: u8,
+        /// Frets between the lowest and the highest fretted notes
+        #[clap(long, default_value_t = 3)]
+        max_span: u8,
+        /// Fingers can be used, a barre takes one
+        #[clap(long, default_value_t = 4)]
+        max_fingers: u8,
+        #[clap(short, long)]
+        output: String,
+    },
+    /// Export a tab to another format
+    Export {
+        input: String,
+        #[clap(short, long, value_enum)]
+        format: ExportFormat,
+        /// Defaults to the input with the extension of the format
+        #[clap(short, long)]
+        output: Option<String>,
+        /// Frequency of A4 in Hz, for midi
+        #[clap(long, default_value_t = 440.0)]
+        a4: f32,
+        /// `equal`, `just`, `pythagorean` or a Scala `.scl` file, for midi
+        #[clap(long, default_value = "equal")]
+        temperament: String,
+        /// Scala keyboard mapping `.kbm` file for the `.scl` temperament
+        #[clap(long)]
+        kbm: Option<String>,
+        /// Tune with pitch bends instead of MIDI Tuning Standard messages
+        #[clap(long)]
+        pitch_bend: bool,
+    },
+    /// Check tabs for unknown tracks, missing marks, bar lengths and frets
+    Validate {
+        #[clap(required = true)]
+        inputs: Vec<String>,
+    },
+}
+
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    Midi,
+    Ascii,
+    Musicxml,
+    Chordpro,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Midi => "mid",
+            Self::Ascii => "txt",
+            Self::Musicxml => "musicxml",
+            Self::Chordpro => "cho",
+        }
+    }
+}
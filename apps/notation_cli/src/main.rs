@@ -0,0 +1,329 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use std::path::Path;
+use std::sync::Arc;
+
+use notation_formats::prelude::*;
+use notation_midi_core::prelude::{MidiFile, MidiSettings, MidiTuningMode};
+use notation_model::prelude::*;
+use notation_tab::prelude::{parse_get_tab_file, parse_tab_file, try_write_tab};
+
+mod args;
+
+use args::{ExportFormat, NotationCli, NotationCommand};
+
+fn load_tab(input: &str) -> Result<ProtoTab> {
+    let extension = Path::new(input)
+        .extension()
+        .map(|x| x.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let tab = match extension.as_str() {
+        "ron" => ron::de::from_str::<ProtoTab>(&std::fs::read_to_string(input)?)?,
+        "rs" => parse_get_tab_file(input)?,
+        "mid" | "midi" => MidiImporter::default().import_file(input)?,
+        "xml" | "musicxml" => MusicXmlImporter::default().import_file(input)?,
+        "gp3" | "gp4" | "gp5" => GuitarProImporter::default().import_file(input)?,
+        "cho" | "crd" | "chopro" | "chordpro" => ChordProImporter::default().import_file(input)?,
+        "txt" | "tab" => AsciiTabImporter::default().import_file(input)?,
+        _ => parse_tab_file(input)?,
+    };
+    Ok(tab)
+}
+
+fn parse_model(proto: &ProtoTab) -> Result<Arc<Tab>> {
+    Ok(Tab::try_parse_arc(proto.clone(), false, None)?)
+}
+
+fn output_path(input: &str, output: Option<String>, extension: &str) -> Result<String> {
+    let path = match output {
+        Some(output) => output,
+        None => Path::new(input)
+            .with_extension(extension)
+            .to_string_lossy()
+            .to_string(),
+    };
+    if path == input {
+        bail!("output would overwrite the input: {}", input);
+    }
+    Ok(path)
+}
+
+/// Prints the diagnostics of the tab, fails if there are any.
+fn check_tab(input: &str, proto: &ProtoTab) -> Result<()> {
+    let diagnostics = Tab::validate(proto);
+    for diagnostic in diagnostics.iter() {
+        println!("{} - {}: {}", input, diagnostic.location, diagnostic.kind);
+    }
+    if !diagnostics.is_empty() {
+        bail!("{} diagnostics", diagnostics.len());
+    }
+    Ok(())
+}
+
+fn parse(input: &str, output: Option<String>) -> Result<()> {
+    let proto = load_tab(input)?;
+    check_tab(input, &proto)?;
+    parse_model(&proto)?;
+    try_write_tab(&proto, output_path(input, output, "ron")?)?;
+    Ok(())
+}
+
+fn info(input: &str) -> Result<()> {
+    let proto = load_tab(input)?;
+    let tab = parse_model(&proto)?;
+    println!("{}", tab);
+    println!("Meta: {}", tab.meta);
+    println!("Tracks:");
+    for track in tab.tracks.iter() {
+        println!("  {} {} [{}]", track.id, track.kind, track.entries.len());
+    }
+    println!("Sections:");
+    for section in tab.sections.iter() {
+        println!("  {} {} [{}]", section.id, section.kind, section.bars.len());
+    }
+    if let Some(estimate) = tab.detect_key() {
+        let mismatch = if estimate.matches(&tab.meta.key, &tab.meta.scale) {
+            ""
+        } else {
+            " (differs from meta)"
+        };
+        println!(
+            "Detected: {} {} [{:.2}]{}",
+            estimate.key, estimate.scale, estimate.confidence, mismatch
+        );
+        for section in tab.detect_section_keys().iter().filter(|x| x.modulates) {
+            println!(
+                "  {} modulates to {} {}",
+                section.section_id, section.estimate.key, section.estimate.scale
+            );
+        }
+    }
+    let form: Vec<String> = tab.form.sections.iter().map(|x| x.id.clone()).collect();
+    println!("Form: {}", form.join(" "));
+    let seconds: f32 = tab
+        .bars
+        .iter()
+        .map(|x| x.bar_units().0 * PlaySpeed::new(&x.meta).seconds_per_unit)
+        .sum();
+    let seconds = seconds.round() as u32;
+    println!(
+        "Bars: {}, Duration: {}:{:02}",
+        tab.bars.len(),
+        seconds / 60,
+        seconds % 60
+    );
+    Ok(())
+}
+
+fn transpose(input: &str, semitones: i8, capo: bool, output: &str) -> Result<()> {
+    let fretted = if capo {
+        FrettedTranspose::Capo
+    } else {
+        FrettedTranspose::Frets
+    };
+    let proto = match load_tab(input)?.transpose(semitones, fretted) {
+        Some(proto) => proto,
+        None => bail!(
+            "can not transpose by {} with {}, missing or out of the fretboard",
+            semitones,
+            fretted
+        ),
+    };
+    parse_model(&proto)?;
+    try_write_tab(&proto, output_path(input, Some(output.to_string()), "ron")?)?;
+    Ok(())
+}
+
+fn finger(
+    input: &str,
+    track: &str,
+    id: &str,
+    tuning: &str,
+    capo: u8,
+    settings: FingeringSettings,
+    output: &str,
+) -> Result<()> {
+    let tuning = match GuitarTuning::from_ident(tuning) {
+        Some(tuning) => tuning,
+        None => bail!("unknown tuning: {}", tuning),
+    };
+    let fretboard = GuitarUtil::new_acoustic_guitar_fretboard(Some(tuning)).with_capo(capo);
+    let proto = match load_tab(input)?.add_fingered_track(track, id, fretboard, &settings) {
+        Some(proto) => proto,
+        None => bail!("track {} not found, or {} is already used", track, id),
+    };
+    parse_model(&proto)?;
+    try_write_tab(&proto, output_path(input, Some(output.to_string()), "ron")?)?;
+    Ok(())
+}
+
+fn voice(
+    input: &str,
+    track: &str,
+    id: &str,
+    tuning: &str,
+    capo: u8,
+    settings: VoicingSettings,
+    output: &str,
+) -> Result<()> {
+    let tuning = match GuitarTuning::from_ident(tuning) {
+        Some(tuning) => tuning,
+        None => bail!("unknown tuning: {}", tuning),
+    };
+    let fretboard = GuitarUtil::new_acoustic_guitar_fretboard(Some(tuning)).with_capo(capo);
+    let proto = match load_tab(input)?.add_voiced_track(track, id, fretboard, &settings) {
+        Some(proto) => proto,
+        None => bail!("track {} not found, or {} is already used", track, id),
+    };
+    parse_model(&proto)?;
+    try_write_tab(&proto, output_path(input, Some(output.to_string()), "ron")?)?;
+    Ok(())
+}
+
+fn load_intonation(a4: f32, temperament: &str, kbm: Option<String>) -> Result<Intonation> {
+    let temperament = match temperament {
+        "equal" => Temperament::Equal,
+        "just" => Temperament::Just,
+        "pythagorean" => Temperament::Pythagorean,
+        scl => Temperament::Scala(ScalaImporter::default().import_file(scl, kbm.as_deref())?),
+    };
+    if kbm.is_some() && !matches!(temperament, Temperament::Scala(_)) {
+        bail!("--kbm needs a `.scl` temperament");
+    }
+    if !a4.is_finite() || a4 <= 0.0 {
+        bail!("invalid A4 frequency: {}", a4);
+    }
+    Ok(Intonation::new(a4, temperament))
+}
+
+fn export_midi(proto: &ProtoTab, intonation: Intonation, pitch_bend: bool) -> Result<Vec<u8>> {
+    let tab = parse_model(proto)?;
+    let settings = MidiSettings {
+        intonation,
+        tuning_mode: if pitch_bend {
+            MidiTuningMode::PitchBend
+        } else {
+            MidiTuningMode::Mts
+        },
+        ..MidiSettings::default()
+    };
+    let file = MidiFile::from_tab(&settings, &tab, MidiFile::DEFAULT_TICKS_PER_QUARTER);
+    Ok(file.to_bytes())
+}
+
+fn export(
+    input: &str,
+    format: ExportFormat,
+    output: Option<String>,
+    intonation: Intonation,
+    pitch_bend: bool,
+) -> Result<()> {
+    let proto = load_tab(input)?;
+    let bytes = match format {
+        ExportFormat::Midi => export_midi(&proto, intonation, pitch_bend)?,
+        ExportFormat::Ascii => AsciiTabExporter::default().export(&proto)?.into_bytes(),
+        ExportFormat::Musicxml => MusicXmlExporter::default().export(&proto)?.into_bytes(),
+        ExportFormat::Chordpro => ChordProExporter::default().export(&proto)?.into_bytes(),
+    };
+    let path = output_path(input, output, format.extension())?;
+    std::fs::write(&path, &bytes)?;
+    println!("Tab exported to: `{}` [{}]", path, bytes.len());
+    Ok(())
+}
+
+fn validate(inputs: &[String]) -> Result<()> {
+    let mut failed = 0;
+    for input in inputs.iter() {
+        let result = load_tab(input).and_then(|proto| {
+            check_tab(input, &proto)?;
+            let tab = parse_model(&proto)?;
+            if tab.bars.is_empty() {
+                bail!("no bars");
+            }
+            Ok(tab)
+        });
+        match result {
+            Ok(tab) => println!("OK: {} - {}", input, tab),
+            Err(err) => {
+                failed += 1;
+                println!("FAILED: {} - {}", input, err);
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} tabs failed", failed, inputs.len());
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = NotationCli::parse();
+    match cli.command {
+        NotationCommand::Parse { input, output } => parse(&input, output),
+        NotationCommand::Info { input } => info(&input),
+        NotationCommand::Transpose {
+            input,
+            semitones,
+            capo,
+            output,
+        } => transpose(&input, semitones, capo, &output),
+        NotationCommand::Finger {
+            input,
+            track,
+            id,
+            tuning,
+            capo,
+            max_stretch,
+            prefer_open,
+            output,
+        } => finger(
+            &input,
+            &track,
+            &id,
+            &tuning,
+            capo,
+            FingeringSettings {
+                max_stretch,
+                prefer_open,
+            },
+            &output,
+        ),
+        NotationCommand::Voice {
+            input,
+            track,
+            id,
+            tuning,
+            capo,
+            max_span,
+            max_fingers,
+            output,
+        } => voice(
+            &input,
+            &track,
+            &id,
+            &tuning,
+            capo,
+            VoicingSettings {
+                max_span,
+                max_fingers,
+            },
+            &output,
+        ),
+        NotationCommand::Export {
+            input,
+            format,
+            output,
+            a4,
+            temperament,
+            kbm,
+            pitch_bend,
+        } => export(
+            &input,
+            format,
+            output,
+            load_intonation(a4, &temperament, kbm)?,
+            pitch_bend,
+        ),
+        NotationCommand::Validate { inputs } => validate(&inputs),
+    }
+}
@@ -116,22 +116,18 @@ impl ScalePage {
         for syllable in syllables.iter() {
             add_note(syllable, false);
         }
+        let notes = entries.len();
         let new_bar = |index: usize| {
             ProtoBar::new(
                 vec![
                     ProtoBarLayer::new("notes".to_owned(), vec![
-                        Slice::new(SliceBegin::Index(index), SliceEnd::Count(4), None),
+                        Slice::new(SliceBegin::Index(index), SliceEnd::Count(usize::min(4, notes - index)), None),
                     ])
                 ],
             )
         };
         let track = ProtoTrack::new("notes".to_owned(), TrackKind::Vocal, entries);
-        let bars = vec![
-            new_bar(0),
-            new_bar(4),
-            new_bar(8),
-            new_bar(12),
-        ];
+        let bars = (0..notes).step_by(4).map(new_bar).collect();
         let section = ProtoSection::new("notes".to_owned(), SectionKind::Verse, bars);
         ProtoTab::new(
             ProtoTab::new_uuid().as_str(),
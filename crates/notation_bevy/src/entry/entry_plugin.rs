@@ -11,7 +11,9 @@ use crate::prelude::{
     BevyUtil, ChordBundle, EntryBundle, LyricsPlugin, NotationAssets, NotationAssetsStates,
     NotationSettings, NotationTheme, ShapesPlugin, StringsPlugin, ToneBundle,
 };
-use crate::shapes::shape_diagram::{ShapeDiagramData4, ShapeDiagramData6};
+use crate::shapes::shape_diagram::{
+    ShapeDiagramData4, ShapeDiagramData5, ShapeDiagramData6, ShapeDiagramData7, ShapeDiagramData8,
+};
 use crate::strings::pick_note::PickNoteData;
 use crate::strings::single_string::SingleStringData;
 use crate::tab::tab_events::TabBarsResizedEvent;
@@ -112,6 +114,69 @@ fn insert_entry_extra(
                 fretted_entry,
             );
         }
+        ProtoEntry::Fretted5(fretted_entry) => {
+            ShapesPlugin::insert_entry_extra5(
+                commands,
+                assets,
+                theme,
+                settings,
+                entry_entity,
+                entry,
+                fretted_entry,
+            );
+            StringsPlugin::insert_entry_extra5(
+                commands,
+                assets,
+                theme,
+                settings,
+                lane_layout.lane_kind,
+                entry_entity,
+                entry,
+                fretted_entry,
+            );
+        }
+        ProtoEntry::Fretted7(fretted_entry) => {
+            ShapesPlugin::insert_entry_extra7(
+                commands,
+                assets,
+                theme,
+                settings,
+                entry_entity,
+                entry,
+                fretted_entry,
+            );
+            StringsPlugin::insert_entry_extra7(
+                commands,
+                assets,
+                theme,
+                settings,
+                lane_layout.lane_kind,
+                entry_entity,
+                entry,
+                fretted_entry,
+            );
+        }
+        ProtoEntry::Fretted8(fretted_entry) => {
+            ShapesPlugin::insert_entry_extra8(
+                commands,
+                assets,
+                theme,
+                settings,
+                entry_entity,
+                entry,
+                fretted_entry,
+            );
+            StringsPlugin::insert_entry_extra8(
+                commands,
+                assets,
+                theme,
+                settings,
+                lane_layout.lane_kind,
+                entry_entity,
+                entry,
+                fretted_entry,
+            );
+        }
         _ => {}
     }
 }
@@ -157,6 +222,9 @@ fn on_tab_bars_resized(
     mut word_text_query: Query<(Entity, &mut WordTextData), With<WordTextData>>,
     mut shape_diagram_6_query: Query<(Entity, &mut ShapeDiagramData6), With<ShapeDiagramData6>>,
     mut shape_diagram_4_query: Query<(Entity, &mut ShapeDiagramData4), With<ShapeDiagramData4>>,
+    mut shape_diagram_5_query: Query<(Entity, &mut ShapeDiagramData5), With<ShapeDiagramData5>>,
+    mut shape_diagram_7_query: Query<(Entity, &mut ShapeDiagramData7), With<ShapeDiagramData7>>,
+    mut shape_diagram_8_query: Query<(Entity, &mut ShapeDiagramData8), With<ShapeDiagramData8>>,
 ) {
     if theme._bypass_systems {
         return;
@@ -204,6 +272,30 @@ fn on_tab_bars_resized(
                     }
                 }
             }
+            for (entity, mut data) in shape_diagram_5_query.iter_mut() {
+                for (view, layout) in bars.iter() {
+                    if data.bar_props.bar_ordinal == view.bar_props.bar_ordinal {
+                        data.value.bar_size = layout.size.width;
+                        data.update(&mut commands, &theme, entity);
+                    }
+                }
+            }
+            for (entity, mut data) in shape_diagram_7_query.iter_mut() {
+                for (view, layout) in bars.iter() {
+                    if data.bar_props.bar_ordinal == view.bar_props.bar_ordinal {
+                        data.value.bar_size = layout.size.width;
+                        data.update(&mut commands, &theme, entity);
+                    }
+                }
+            }
+            for (entity, mut data) in shape_diagram_8_query.iter_mut() {
+                for (view, layout) in bars.iter() {
+                    if data.bar_props.bar_ordinal == view.bar_props.bar_ordinal {
+                        data.value.bar_size = layout.size.width;
+                        data.update(&mut commands, &theme, entity);
+                    }
+                }
+            }
         }
         if !settings.hide_strings_lane {
             for (entity, mut data) in single_string_query.iter_mut() {
@@ -1,14 +1,15 @@
 use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use notation_bevy_utils::prelude::{BevyUtil, FillRectangle, LayoutSize, ShapeOp};
-use notation_model::prelude::{HandShape6, Pick};
+use notation_model::prelude::Pick;
 
 use crate::prelude::NotationTheme;
 
 #[derive(Clone, Debug, Component)]
 pub struct GuitarBarreData {
     pub capo: u8,
-    pub shape: Option<HandShape6>,
+    /// Barre and max fret of the current hand shape.
+    pub shape: Option<(u8, u8)>,
     pub pick: Option<Pick>,
     pub view_size: LayoutSize,
     pub guitar_size: LayoutSize,
@@ -32,10 +33,10 @@ impl GuitarBarreData {
         }
     }
     pub fn barre(&self) -> u8 {
-        self.shape.and_then(|x| x.barre).unwrap_or(0)
+        self.shape.map(|x| x.0).unwrap_or(0)
     }
     pub fn max_fret(&self) -> u8 {
-        let mut max = self.shape.map(|x| x.max_fret_with_barre()).unwrap_or(0);
+        let mut max = self.shape.map(|x| x.1).unwrap_or(0);
         if let Some(pick) = self.pick {
             let pick_max = pick.max_fret();
             if pick_max > max {
@@ -4,7 +4,8 @@ use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use notation_bevy_utils::prelude::{BevyUtil, LayoutSize, OutlineRectangle, ShapeOp};
 use notation_model::prelude::{
-    Duration, Fretboard6, HandShape6, Pick, PlaySpeed, PlayingState, Note, TabMeta, Units,
+    Duration, Fretboard6, Fretboard7, Fretboard8, HandShape6, HandShape7, HandShape8, Pick,
+    PlaySpeed, PlayingState, Note, TabMeta, Units,
 };
 
 use crate::prelude::NotationTheme;
@@ -12,6 +13,7 @@ use crate::prelude::NotationTheme;
 #[derive(Clone, Debug, Component)]
 pub struct GuitarStringData {
     pub string: u8,
+    pub string_num: u8,
     pub upper: bool,
     pub fret: Option<u8>,
     pub pick_fret: Option<u8>,
@@ -26,10 +28,10 @@ pub struct GuitarStringData {
 }
 
 impl GuitarStringData {
-    pub fn new(string: u8, upper: bool, fretboard: Option<Fretboard6>) -> Self {
-        let capo = fretboard.map(|x| x.capo).unwrap_or(0);
+    pub fn new(string: u8, string_num: u8, upper: bool, capo: u8) -> Self {
         Self {
             string,
+            string_num,
             upper,
             fret: Some(0),
             pick_fret: None,
@@ -99,43 +101,54 @@ impl GuitarStringData {
         };
         self.hit_expired_seconds = time.seconds_since_startup() + self.hit_seconds as f64;
     }
-    fn set_note(&mut self, fretboard: Option<Fretboard6>, meta: Option<Arc<TabMeta>>) {
-        self.note = None;
-        if let (Some(meta), Some(fretboard)) = (meta, fretboard) {
-            if let Some(note) = fretboard.fretted_note(&meta.scale, &meta.key, self.string, self.fret()) {
-                self.note = Some(note);
+}
+
+macro_rules! impl_guitar_string_data {
+    ($set_note:ident, $update_pick:ident, $update_value:ident, $fretboard:ident, $hand_shape:ident) => {
+        impl GuitarStringData {
+            fn $set_note(&mut self, fretboard: Option<$fretboard>, meta: Option<Arc<TabMeta>>) {
+                self.note = None;
+                if let (Some(meta), Some(fretboard)) = (meta, fretboard) {
+                    if let Some(note) = fretboard.fretted_note(&meta.scale, &meta.key, self.string, self.fret()) {
+                        self.note = Some(note);
+                    }
+                }
+            }
+            pub fn $update_pick(
+                &mut self,
+                fretboard: Option<$fretboard>,
+                pick: Pick,
+                meta: Option<Arc<TabMeta>>,
+            ) {
+                let pick_note = pick.get_pick_note(self.string);
+                self.pick_fret = pick_note.and_then(|x| x.fret);
+                self.$set_note(fretboard, meta);
+            }
+            pub fn $update_value(
+                &mut self,
+                shape: &$hand_shape,
+                fretboard: Option<$fretboard>,
+                pick: Option<Pick>,
+                meta: Option<Arc<TabMeta>>,
+            ) {
+                let pick_note = pick.and_then(|x| x.get_pick_note(self.string));
+                self.pick_fret = pick_note.and_then(|x| x.fret);
+                self.fret = shape.string_fret_with_barre(self.string);
+                if let Some(fretboard) = fretboard {
+                    self.capo = fretboard.capo;
+                } else {
+                    self.capo = 0;
+                }
+                self.$set_note(fretboard, meta);
             }
         }
-    }
-    pub fn update_pick(
-        &mut self,
-        fretboard: Option<Fretboard6>,
-        pick: Pick,
-        meta: Option<Arc<TabMeta>>,
-    ) {
-        let pick_note = pick.get_pick_note(self.string);
-        self.pick_fret = pick_note.and_then(|x| x.fret);
-        self.set_note(fretboard, meta);
-    }
-    pub fn update_value(
-        &mut self,
-        shape: &HandShape6,
-        fretboard: Option<Fretboard6>,
-        pick: Option<Pick>,
-        meta: Option<Arc<TabMeta>>,
-    ) {
-        let pick_note = pick.and_then(|x| x.get_pick_note(self.string));
-        self.pick_fret = pick_note.and_then(|x| x.fret);
-        self.fret = shape.string_fret_with_barre(self.string);
-        if let Some(fretboard) = fretboard {
-            self.capo = fretboard.capo;
-        } else {
-            self.capo = 0;
-        }
-        self.set_note(fretboard, meta);
-    }
+    };
 }
 
+impl_guitar_string_data!(set_note6, update_pick6, update_value6, Fretboard6, HandShape6);
+impl_guitar_string_data!(set_note7, update_pick7, update_value7, Fretboard7, HandShape7);
+impl_guitar_string_data!(set_note8, update_pick8, update_value8, Fretboard8, HandShape8);
+
 impl ShapeOp<NotationTheme, OutlineRectangle> for GuitarStringData {
     fn get_shape(&self, theme: &NotationTheme) -> OutlineRectangle {
         let fret_y = theme
@@ -174,7 +187,7 @@ impl ShapeOp<NotationTheme, OutlineRectangle> for GuitarStringData {
         let outline_width = self.outline(theme);
         let x = theme
             .guitar
-            .calc_string_x(self.string, self.guitar_size.width);
+            .calc_string_x(self.string, self.string_num, self.guitar_size.width);
         let fret_y = theme
             .guitar
             .calc_fret_y(self.fret() + self.capo, self.guitar_size.height);
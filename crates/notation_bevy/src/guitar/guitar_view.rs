@@ -7,8 +7,8 @@ use notation_bevy_utils::prelude::{
     BevyUtil, LayoutAnchor, LayoutChangedQuery, LayoutSize, ShapeOp, View, ViewBundle, SingleData,
 };
 use notation_model::prelude::{
-    Duration, Entry, HandShape6, Interval, LaneEntry, LaneKind, ModelEntryProps, Pick, Syllable,
-    Tab, TrackKind, Units,
+    Duration, Entry, HandShape6, HandShape7, HandShape8, Interval, LaneEntry, LaneKind,
+    ModelEntryProps, Pick, Syllable, Tab, TrackKind, Units, GUITAR_STRING_NUM,
 };
 
 use crate::chord::interval_dot::DotQuery;
@@ -70,14 +70,17 @@ impl GuitarView {
             texture: assets.fretboard.clone(),
             ..Default::default()
         };
-        let fretboard = tab
-            .get_track_of_kind(TrackKind::Guitar)
-            .and_then(|x| x.get_fretboard6());
+        let track = tab.get_track_of_kind(TrackKind::Guitar);
+        let string_num = track
+            .as_ref()
+            .and_then(|x| x.fretted_string_num())
+            .unwrap_or(GUITAR_STRING_NUM) as u8;
+        let capo = track.and_then(|x| x.get_fretboard_capo()).unwrap_or(0);
 
         BevyUtil::spawn_child_bundle(commands, guitar_entity, sprite_bundle);
-        for string in 1..=6 {
+        for string in 1..=string_num {
             for upper in [true, false] {
-                let string_data = GuitarStringData::new(string as u8, upper, fretboard);
+                let string_data = GuitarStringData::new(string, string_num, upper, capo);
                 string_data.create(commands, theme, guitar_entity);
             }
         }
@@ -97,6 +100,7 @@ impl GuitarView {
                     Syllable::Do,
                     Interval::Unison,
                     string as u8,
+                    string_num,
                     false,
                     Some(fret as u8),
                     None,
@@ -104,13 +108,13 @@ impl GuitarView {
                 finger_data.value.extra.visible = true;
                 finger_data.spawn(commands, theme, guitar_entity);
                 string = string + 1;
-                if string > 6 {
+                if string > string_num {
                     string = 1;
                 }
                 fret = fret + 1;
             }
         } else {
-            for index in 1..=6 {
+            for index in 1..=string_num {
                 for pick in [true, false] {
                     let finger_data = FretFingerData::new_data(
                         ModelEntryProps {
@@ -119,7 +123,8 @@ impl GuitarView {
                         },
                         Syllable::Do,
                         Interval::Unison,
-                        index as u8,
+                        index,
+                        string_num,
                         pick,
                         None,
                         None,
@@ -182,197 +187,11 @@ impl GuitarView {
             }
         }
     }
-    pub fn update_string_state(
-        mut commands: Commands,
-        assets: Res<NotationAssets>,
-        settings: Res<NotationSettings>,
-        #[cfg(feature = "midi")]
-        midi_state: Res<MidiState>,
-        time: Res<Time>,
-        theme: Res<NotationTheme>,
-        query: Query<(&SingleData<LaneEntry>, &SingleData<Pick>, &EntryPlaying), Changed<EntryPlaying>>,
-        mut string_query: Query<(Entity, &mut GuitarStringData), With<GuitarStringData>>,
-        mut finger_query: Query<(Entity, &mut FretFingerData), With<FretFingerData>>,
-        mut barre_query: Query<(Entity, &mut GuitarBarreData), With<GuitarBarreData>>,
-        mut dot_query: DotQuery,
-        text_query: Query<(&Parent, Entity), With<Text>>,
-    ) {
-        if Self::CHECKING_FRETS {
-            return;
-        }
-        if theme._bypass_systems {
-            return;
-        }
-        if settings.hide_guitar_view {
-            return;
-        }
-        let mut current_entry_pick = None;
-        let mut string_states = [None; 6];
-        let mut hit_strings = [(false, Duration::Zero); 6];
-        for (entry, pick, playing) in query.iter() {
-            if playing.value.is_current() {
-                current_entry_pick = Some((entry.0.clone(), pick.0));
-            }
-            for pick_note in pick.0.get_notes() {
-                if pick_note.string >= 1 && pick_note.string <= 6 {
-                    string_states[(pick_note.string - 1) as usize] = Some(playing.value);
-                    hit_strings[(pick_note.string - 1) as usize] =
-                        (playing.value.is_current(), entry.0.duration());
-                }
-            }
-        }
-        let fretboard = current_entry_pick.clone()
-            .and_then(|(entry, _)| entry.track().and_then(|x| x.get_fretboard6()));
-        let meta = current_entry_pick.clone().and_then(|(entry, _)| entry.bar().map(|x| x.tab_meta()));
-
-        for (string_entity, mut string_data) in string_query.iter_mut() {
-            if string_data.string >= 1 && string_data.string <= 6 {
-                let (hit, hit_duration) = hit_strings[(string_data.string - 1) as usize];
-                #[cfg(feature = "midi")]
-                string_data.set_hit(
-                    hit,
-                    hit_duration,
-                    &time,
-                    theme.guitar.hit_string_seconds_range,
-                    midi_state.play_control.play_speed,
-                );
-                if let Some(state) = string_states[(string_data.string - 1) as usize] {
-                    string_data.state = state;
-                }
-                if let Some((_, pick)) = current_entry_pick {
-                    string_data.update_pick(fretboard, pick, meta.clone());
-                }
-                string_data.update(&mut commands, &theme, string_entity);
-            }
-        }
-        if let Some((entry, pick)) = current_entry_pick {
-            let chord = entry.bar().and_then(|x| x.get_chord_of_entry(&entry));
-            for (finger_entity, mut finger_data) in finger_query.iter_mut() {
-                let changed = finger_data.update_pick(fretboard, chord, pick, meta.clone());
-                if changed {
-                    if finger_data.value.extra.pick {
-                        finger_data.respawn_dots(
-                            &mut commands,
-                            &theme,
-                            Some(&mut dot_query),
-                            finger_entity,
-                        );
-                    }
-                    finger_data.update_with_syllable(
-                        &mut commands,
-                        &assets,
-                        &theme,
-                        &settings,
-                        &text_query,
-                        finger_entity,
-                        &meta.clone().unwrap_or_default(),
-                    );
-                }
-            }
-            for (_barre_entity, mut barre_data) in barre_query.iter_mut() {
-                barre_data.update_pick(&pick);
-            }
-        }
-    }
-    pub fn update_hand_shape6(
-        mut commands: Commands,
-        assets: Res<NotationAssets>,
-        theme: Res<NotationTheme>,
-        settings: Res<NotationSettings>,
-        query: Query<(&SingleData<LaneEntry>, &SingleData<HandShape6>, &EntryPlaying), Changed<EntryPlaying>>,
-        mut finger_query: Query<(Entity, &mut FretFingerData), With<FretFingerData>>,
-        mut string_query: Query<(Entity, &mut GuitarStringData), With<GuitarStringData>>,
-        mut capo_query: Query<(Entity, &mut GuitarCapoData), With<GuitarCapoData>>,
-        mut barre_query: Query<(Entity, &mut GuitarBarreData), With<GuitarBarreData>>,
-        mut dot_query: DotQuery,
-        text_query: Query<(&Parent, Entity), With<Text>>,
-        tab_state_query: Query<(Entity, &TabState), With<TabState>>,
-    ) {
-        if Self::CHECKING_FRETS {
-            return;
-        }
-        if theme._bypass_systems {
-            return;
-        }
-        if settings.hide_guitar_view {
-            return;
-        }
-        let mut current_shape = None;
-        for (entry, shape, playing) in query.iter() {
-            if playing.value.is_current() {
-                //println!("GuitarView::update_hand_shape6(): found changed playing shape: {}", shape);
-                current_shape = Some((entry.0.clone(), shape.0));
-            }
-        }
-        if let Some((entry, shape)) = current_shape {
-            let fretboard = entry.track().and_then(|x| x.get_fretboard6());
-            let chord = entry.bar().and_then(|x| x.get_chord_of_entry(&entry));
-            let pick = entry.bar().and_then(|x| {
-                x.get_entry_in_other_lane(
-                    LaneKind::Strings,
-                    entry.track_index(),
-                    Some(entry.in_bar_pos()),
-                    &|x: &LaneEntry| {
-                        x.proto()
-                            .as_fretted6()
-                            .and_then(|y| y.as_pick())
-                            .map(|z| z.to_owned())
-                    },
-                )
-            });
-            let meta = entry.bar().map(|x| x.tab_meta());
-            //println!("GuitarView::update_hand_shape6(): {}, {:#?}, {:#?}", shape, fretboard, chord);
-            for (finger_entity, mut finger_data) in finger_query.iter_mut() {
-                finger_data.update_value(&shape, fretboard, chord, pick, meta.clone());
-                finger_data.respawn_dots(&mut commands, &theme, Some(&mut dot_query), finger_entity);
-                finger_data.update_with_syllable(
-                    &mut commands,
-                    &assets,
-                    &theme,
-                    &settings,
-                    &text_query,
-                    finger_entity,
-                    &meta.clone().unwrap_or_default(),
-                );
-            }
-            for (string_entity, mut string_data) in string_query.iter_mut() {
-                string_data.update_value(&shape, fretboard, pick, meta.clone());
-                string_data.update(&mut commands, &theme, string_entity);
-            }
-            if let Some(fretboard) = fretboard {
-                for (capo_entity, mut capo_data) in capo_query.iter_mut() {
-                    if fretboard.capo != capo_data.capo {
-                        capo_data.capo = fretboard.capo;
-                        capo_data.update(&mut commands, &theme, capo_entity);
-                    }
-                }
-                for (barre_entity, mut barre_data) in barre_query.iter_mut() {
-                    barre_data.capo = fretboard.capo;
-                    barre_data.shape = Some(shape.clone());
-                    barre_data.pick = None;
-                    barre_data.update(&mut commands, &theme, barre_entity);
-                }
-            }
-        } else {
-            let position = TabState::get_position(&tab_state_query, None);
-            if settings.add_ready_section && position.is_some() && position.unwrap().bar.bar_ordinal == 0 {
-                for (finger_entity, mut finger_data) in finger_query.iter_mut() {
-                    finger_data.reset();
-                    finger_data.update(&mut commands, &theme, finger_entity);
-                }
-                for (string_entity, mut string_data) in string_query.iter_mut() {
-                    string_data.reset();
-                    string_data.update(&mut commands, &theme, string_entity);
-                }
-                for (barre_entity, mut barre_data) in barre_query.iter_mut() {
-                    if barre_data.shape.is_some() {
-                        barre_data.shape = None;
-                        barre_data.pick = None;
-                        barre_data.update(&mut commands, &theme, barre_entity);
-                    }
-                }
-            }
-        }
+    fn is_guitar_entry(entry: &LaneEntry, string_num: usize) -> bool {
+        entry
+            .track()
+            .map(|x| x.kind == TrackKind::Guitar && x.fretted_string_num() == Some(string_num))
+            .unwrap_or(false)
     }
     pub fn update_y(guitar_view_query: &mut Query<&mut Transform, With<GuitarView>>, y: f32) {
         if let Ok(mut transform) = guitar_view_query.get_single_mut() {
@@ -441,3 +260,250 @@ impl GuitarView {
         }
     }
 }
+
+macro_rules! impl_guitar_view_systems {
+    ($strings:literal,
+        $update_string_state:ident,
+        $update_hand_shape:ident,
+        $hand_shape:ident,
+        $get_fretboard:ident,
+        $as_fretted:ident,
+        $update_pick:ident,
+        $update_value:ident
+    ) => {
+        impl GuitarView {
+            pub fn $update_string_state(
+                mut commands: Commands,
+                assets: Res<NotationAssets>,
+                settings: Res<NotationSettings>,
+                #[cfg(feature = "midi")]
+                midi_state: Res<MidiState>,
+                time: Res<Time>,
+                theme: Res<NotationTheme>,
+                query: Query<(&SingleData<LaneEntry>, &SingleData<Pick>, &EntryPlaying), Changed<EntryPlaying>>,
+                mut string_query: Query<(Entity, &mut GuitarStringData), With<GuitarStringData>>,
+                mut finger_query: Query<(Entity, &mut FretFingerData), With<FretFingerData>>,
+                mut barre_query: Query<(Entity, &mut GuitarBarreData), With<GuitarBarreData>>,
+                mut dot_query: DotQuery,
+                text_query: Query<(&Parent, Entity), With<Text>>,
+            ) {
+                if Self::CHECKING_FRETS {
+                    return;
+                }
+                if theme._bypass_systems {
+                    return;
+                }
+                if settings.hide_guitar_view {
+                    return;
+                }
+                let mut current_entry_pick = None;
+                let mut string_states = [None; $strings];
+                let mut hit_strings = [(false, Duration::Zero); $strings];
+                for (entry, pick, playing) in query.iter() {
+                    if !Self::is_guitar_entry(&entry.0, $strings) {
+                        continue;
+                    }
+                    if playing.value.is_current() {
+                        current_entry_pick = Some((entry.0.clone(), pick.0));
+                    }
+                    for pick_note in pick.0.get_notes() {
+                        if pick_note.string >= 1 && pick_note.string <= $strings {
+                            string_states[(pick_note.string - 1) as usize] = Some(playing.value);
+                            hit_strings[(pick_note.string - 1) as usize] =
+                                (playing.value.is_current(), entry.0.duration());
+                        }
+                    }
+                }
+                let fretboard = current_entry_pick.clone()
+                    .and_then(|(entry, _)| entry.track().and_then(|x| x.$get_fretboard()));
+                let meta = current_entry_pick.clone().and_then(|(entry, _)| entry.bar().map(|x| x.tab_meta()));
+
+                for (string_entity, mut string_data) in string_query.iter_mut() {
+                    if string_data.string_num != $strings {
+                        continue;
+                    }
+                    if string_data.string >= 1 && string_data.string <= $strings {
+                        let (hit, hit_duration) = hit_strings[(string_data.string - 1) as usize];
+                        #[cfg(feature = "midi")]
+                        string_data.set_hit(
+                            hit,
+                            hit_duration,
+                            &time,
+                            theme.guitar.hit_string_seconds_range,
+                            midi_state.play_control.play_speed,
+                        );
+                        if let Some(state) = string_states[(string_data.string - 1) as usize] {
+                            string_data.state = state;
+                        }
+                        if let Some((_, pick)) = current_entry_pick {
+                            string_data.$update_pick(fretboard, pick, meta.clone());
+                        }
+                        string_data.update(&mut commands, &theme, string_entity);
+                    }
+                }
+                if let Some((entry, pick)) = current_entry_pick {
+                    let chord = entry.bar().and_then(|x| x.get_chord_of_entry(&entry));
+                    for (finger_entity, mut finger_data) in finger_query.iter_mut() {
+                        let changed = finger_data.$update_pick(fretboard, chord, pick, meta.clone());
+                        if changed {
+                            if finger_data.value.extra.pick {
+                                finger_data.respawn_dots(
+                                    &mut commands,
+                                    &theme,
+                                    Some(&mut dot_query),
+                                    finger_entity,
+                                );
+                            }
+                            finger_data.update_with_syllable(
+                                &mut commands,
+                                &assets,
+                                &theme,
+                                &settings,
+                                &text_query,
+                                finger_entity,
+                                &meta.clone().unwrap_or_default(),
+                            );
+                        }
+                    }
+                    for (_barre_entity, mut barre_data) in barre_query.iter_mut() {
+                        barre_data.update_pick(&pick);
+                    }
+                }
+            }
+            pub fn $update_hand_shape(
+                mut commands: Commands,
+                assets: Res<NotationAssets>,
+                theme: Res<NotationTheme>,
+                settings: Res<NotationSettings>,
+                query: Query<(&SingleData<LaneEntry>, &SingleData<$hand_shape>, &EntryPlaying), Changed<EntryPlaying>>,
+                mut finger_query: Query<(Entity, &mut FretFingerData), With<FretFingerData>>,
+                mut string_query: Query<(Entity, &mut GuitarStringData), With<GuitarStringData>>,
+                mut capo_query: Query<(Entity, &mut GuitarCapoData), With<GuitarCapoData>>,
+                mut barre_query: Query<(Entity, &mut GuitarBarreData), With<GuitarBarreData>>,
+                mut dot_query: DotQuery,
+                text_query: Query<(&Parent, Entity), With<Text>>,
+                tab_state_query: Query<(Entity, &TabState), With<TabState>>,
+            ) {
+                if Self::CHECKING_FRETS {
+                    return;
+                }
+                if theme._bypass_systems {
+                    return;
+                }
+                if settings.hide_guitar_view {
+                    return;
+                }
+                let mut current_shape = None;
+                for (entry, shape, playing) in query.iter() {
+                    if !Self::is_guitar_entry(&entry.0, $strings) {
+                        continue;
+                    }
+                    if playing.value.is_current() {
+                        //println!("GuitarView::update_hand_shape(): found changed playing shape: {}", shape);
+                        current_shape = Some((entry.0.clone(), shape.0));
+                    }
+                }
+                if let Some((entry, shape)) = current_shape {
+                    let fretboard = entry.track().and_then(|x| x.$get_fretboard());
+                    let chord = entry.bar().and_then(|x| x.get_chord_of_entry(&entry));
+                    let pick = entry.bar().and_then(|x| {
+                        x.get_entry_in_other_lane(
+                            LaneKind::Strings,
+                            entry.track_index(),
+                            Some(entry.in_bar_pos()),
+                            &|x: &LaneEntry| {
+                                x.proto()
+                                    .$as_fretted()
+                                    .and_then(|y| y.as_pick())
+                                    .map(|z| z.to_owned())
+                            },
+                        )
+                    });
+                    let meta = entry.bar().map(|x| x.tab_meta());
+                    //println!("GuitarView::update_hand_shape(): {}, {:#?}, {:#?}", shape, fretboard, chord);
+                    for (finger_entity, mut finger_data) in finger_query.iter_mut() {
+                        finger_data.$update_value(&shape, fretboard, chord, pick, meta.clone());
+                        finger_data.respawn_dots(&mut commands, &theme, Some(&mut dot_query), finger_entity);
+                        finger_data.update_with_syllable(
+                            &mut commands,
+                            &assets,
+                            &theme,
+                            &settings,
+                            &text_query,
+                            finger_entity,
+                            &meta.clone().unwrap_or_default(),
+                        );
+                    }
+                    for (string_entity, mut string_data) in string_query.iter_mut() {
+                        string_data.$update_value(&shape, fretboard, pick, meta.clone());
+                        string_data.update(&mut commands, &theme, string_entity);
+                    }
+                    if let Some(fretboard) = fretboard {
+                        for (capo_entity, mut capo_data) in capo_query.iter_mut() {
+                            if fretboard.capo != capo_data.capo {
+                                capo_data.capo = fretboard.capo;
+                                capo_data.update(&mut commands, &theme, capo_entity);
+                            }
+                        }
+                        for (barre_entity, mut barre_data) in barre_query.iter_mut() {
+                            barre_data.capo = fretboard.capo;
+                            barre_data.shape = Some((shape.barre.unwrap_or(0), shape.max_fret_with_barre()));
+                            barre_data.pick = None;
+                            barre_data.update(&mut commands, &theme, barre_entity);
+                        }
+                    }
+                } else {
+                    let position = TabState::get_position(&tab_state_query, None);
+                    if settings.add_ready_section && position.is_some() && position.unwrap().bar.bar_ordinal == 0 {
+                        for (finger_entity, mut finger_data) in finger_query.iter_mut() {
+                            finger_data.reset();
+                            finger_data.update(&mut commands, &theme, finger_entity);
+                        }
+                        for (string_entity, mut string_data) in string_query.iter_mut() {
+                            string_data.reset();
+                            string_data.update(&mut commands, &theme, string_entity);
+                        }
+                        for (barre_entity, mut barre_data) in barre_query.iter_mut() {
+                            if barre_data.shape.is_some() {
+                                barre_data.shape = None;
+                                barre_data.pick = None;
+                                barre_data.update(&mut commands, &theme, barre_entity);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_guitar_view_systems!(
+    6,
+    update_string_state6,
+    update_hand_shape6,
+    HandShape6,
+    get_fretboard6,
+    as_fretted6,
+    update_pick6,
+    update_value6
+);
+impl_guitar_view_systems!(
+    7,
+    update_string_state7,
+    update_hand_shape7,
+    HandShape7,
+    get_fretboard7,
+    as_fretted7,
+    update_pick7,
+    update_value7
+);
+impl_guitar_view_systems!(
+    8,
+    update_string_state8,
+    update_hand_shape8,
+    HandShape8,
+    get_fretboard8,
+    as_fretted8,
+    update_pick8,
+    update_value8
+);
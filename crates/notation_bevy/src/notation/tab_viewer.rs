@@ -47,10 +47,28 @@ impl Plugin for TabViewerPlugin {
                         .label("GuitarView::update_hand_shape6"),
                 )
                 .with_system(
-                    GuitarView::update_string_state
-                        .label("GuitarView::update_string_state")
+                    GuitarView::update_string_state6
+                        .label("GuitarView::update_string_state6")
                         .after("GuitarView::update_hand_shape6"),
                 )
+                .with_system(
+                    GuitarView::update_hand_shape7
+                        .label("GuitarView::update_hand_shape7"),
+                )
+                .with_system(
+                    GuitarView::update_string_state7
+                        .label("GuitarView::update_string_state7")
+                        .after("GuitarView::update_hand_shape7"),
+                )
+                .with_system(
+                    GuitarView::update_hand_shape8
+                        .label("GuitarView::update_hand_shape8"),
+                )
+                .with_system(
+                    GuitarView::update_string_state8
+                        .label("GuitarView::update_string_state8")
+                        .after("GuitarView::update_hand_shape8"),
+                )
                 .with_system(GuitarView::adjust_y_by_barre)
         );
     }
@@ -1,5 +1,5 @@
 use crate::prelude::{SingleData, SingleBundle};
-use notation_model::prelude::{HandShape4, HandShape6};
+use notation_model::prelude::{HandShape4, HandShape5, HandShape6, HandShape7, HandShape8};
 
 macro_rules! impl_hand_shape_bundle {
     ($type:ident, $hand_shape:ident) => {
@@ -9,3 +9,6 @@ macro_rules! impl_hand_shape_bundle {
 
 impl_hand_shape_bundle!(HandShapeBundle6, HandShape6);
 impl_hand_shape_bundle!(HandShapeBundle4, HandShape4);
+impl_hand_shape_bundle!(HandShapeBundle5, HandShape5);
+impl_hand_shape_bundle!(HandShapeBundle7, HandShape7);
+impl_hand_shape_bundle!(HandShapeBundle8, HandShape8);
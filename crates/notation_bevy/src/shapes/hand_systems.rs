@@ -5,10 +5,12 @@ use notation_model::prelude::LaneEntry;
 use notation_bevy_utils::prelude::ShapeOp;
 
 use super::shape_barre::ShapeBarreData;
-use super::shape_diagram::{ShapeDiagramData4, ShapeDiagramData6};
+use super::shape_diagram::{
+    ShapeDiagramData4, ShapeDiagramData5, ShapeDiagramData6, ShapeDiagramData7, ShapeDiagramData8,
+};
 use super::shape_finger::ShapeFingerData;
 use crate::prelude::{NotationAssets, NotationSettings, NotationTheme};
-use notation_model::prelude::{HandShape4, HandShape6};
+use notation_model::prelude::{HandShape4, HandShape5, HandShape6, HandShape7, HandShape8};
 
 macro_rules! impl_hand_system {
     ($type:ident, $hand_shape:ident, $diagram_data:ident) => {
@@ -48,3 +50,6 @@ macro_rules! impl_hand_system {
 
 impl_hand_system!(create_hand_shape6, HandShape6, ShapeDiagramData6);
 impl_hand_system!(create_hand_shape4, HandShape4, ShapeDiagramData4);
+impl_hand_system!(create_hand_shape5, HandShape5, ShapeDiagramData5);
+impl_hand_system!(create_hand_shape7, HandShape7, ShapeDiagramData7);
+impl_hand_system!(create_hand_shape8, HandShape8, ShapeDiagramData8);
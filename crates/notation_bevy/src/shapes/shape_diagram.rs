@@ -2,7 +2,9 @@ use bevy::prelude::*;
 
 use crate::prelude::{EntryData, NotationTheme};
 use notation_bevy_utils::prelude::{BevyUtil, ShapeOp, StrokePath};
-use notation_model::prelude::{HandShape4, HandShape6, LaneEntry};
+use notation_model::prelude::{
+    HandShape4, HandShape5, HandShape6, HandShape7, HandShape8, LaneEntry,
+};
 
 macro_rules! impl_shape_diagram {
     ($hand_shape:ident, $diagram_data:ident, $diagram_value:ident) => {
@@ -44,3 +46,6 @@ macro_rules! impl_shape_diagram {
 
 impl_shape_diagram!(HandShape6, ShapeDiagramData6, ShapeDiagramValue6);
 impl_shape_diagram!(HandShape4, ShapeDiagramData4, ShapeDiagramValue4);
+impl_shape_diagram!(HandShape5, ShapeDiagramData5, ShapeDiagramValue5);
+impl_shape_diagram!(HandShape7, ShapeDiagramData7, ShapeDiagramValue7);
+impl_shape_diagram!(HandShape8, ShapeDiagramData8, ShapeDiagramValue8);
@@ -3,9 +3,13 @@ use bevy::prelude::*;
 
 use crate::prelude::{NotationAssets, NotationSettings, NotationTheme};
 
-use super::hand_bundles::{HandShapeBundle4, HandShapeBundle6};
+use super::hand_bundles::{
+    HandShapeBundle4, HandShapeBundle5, HandShapeBundle6, HandShapeBundle7, HandShapeBundle8,
+};
 
-use notation_model::prelude::{BarLane, FrettedEntry4, FrettedEntry6, LaneEntry};
+use notation_model::prelude::{
+    BarLane, FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8, LaneEntry,
+};
 
 pub struct ShapesPlugin;
 
@@ -74,3 +78,33 @@ impl_shapes_plugin!(
     ShapeDiagramData4,
     HandShapeBundle4
 );
+impl_shapes_plugin!(
+    insert_entry_extra5,
+    create_hand_shape5,
+    FrettedEntry5,
+    Fretboard5,
+    HandShape5,
+    ShapeDiagram5,
+    ShapeDiagramData5,
+    HandShapeBundle5
+);
+impl_shapes_plugin!(
+    insert_entry_extra7,
+    create_hand_shape7,
+    FrettedEntry7,
+    Fretboard7,
+    HandShape7,
+    ShapeDiagram7,
+    ShapeDiagramData7,
+    HandShapeBundle7
+);
+impl_shapes_plugin!(
+    insert_entry_extra8,
+    create_hand_shape8,
+    FrettedEntry8,
+    Fretboard8,
+    HandShape8,
+    ShapeDiagram8,
+    ShapeDiagramData8,
+    HandShapeBundle8
+);
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use notation_bevy_utils::prelude::{BevyUtil, OutlineRectangle, ShapeOp};
-use notation_model::prelude::{PickNote, PlayingState, Syllable};
+use notation_model::prelude::{Harmonic, PickNote, PlayingState, Syllable};
 
 use crate::prelude::{EntryData, NotationTheme};
 
@@ -24,6 +24,49 @@ impl PickNoteValue {
             bar_size: 0.0,
         }
     }
+    /// Fret with the techniques in the usual tab notation, e.g. `h7` for a
+    /// hammer-on, `7b9r` for a whole step bend and release, `<12>` for a
+    /// natural harmonic.
+    pub fn calc_fret_text(&self, fret: u8) -> String {
+        let techniques = &self.pick_note.techniques;
+        let mut text = String::new();
+        if techniques.slide_in {
+            text.push('/');
+        }
+        if let Some(attack) = techniques.attack {
+            text.push_str(&attack.to_string());
+        }
+        if techniques.dead_note {
+            text.push('x');
+        } else {
+            match techniques.harmonic {
+                Some(Harmonic::Natural) => text.push_str(&format!("<{}>", fret)),
+                Some(Harmonic::Artificial(node)) => {
+                    text.push_str(&format!("{}<{}>", fret, fret as u16 + node as u16))
+                }
+                None => text.push_str(&fret.to_string()),
+            }
+        }
+        if let Some(bend) = techniques.bend {
+            text.push_str(&format!("b{}", fret as u16 + bend.semitones as u16));
+            if bend.release {
+                text.push('r');
+            }
+        }
+        if techniques.vibrato {
+            text.push('~');
+        }
+        if techniques.slide_out {
+            text.push('\\');
+        }
+        if techniques.palm_mute {
+            text.push_str(" pm");
+        }
+        if techniques.let_ring {
+            text.push_str(" lr");
+        }
+        text
+    }
 }
 
 impl PickNoteData {
@@ -38,6 +81,9 @@ impl PickNoteData {
         let width =
             self.value.bar_size / self.bar_props.bar_units.0 * self.entry_props.tied_units.0;
         let mut height = theme.sizes.strings.note_height;
+        if self.value.pick_note.techniques.dead_note {
+            height *= 0.5;
+        }
         let outline = self.calc_outline(theme);
         if self.value.playing_state.is_current() {
             height += outline * 2.0;
@@ -72,13 +72,15 @@ macro_rules! impl_pick_system {
                                 PickNoteData::new(entry, PickNoteValue::new(pick_note, syllable));
                             let note_entity = data.create(commands, theme, entity);
                             if !settings.hide_strings_lane
-                                && (settings.always_show_fret || pick_note.fret.is_some())
+                                && (settings.always_show_fret
+                                    || pick_note.fret.is_some()
+                                    || !pick_note.techniques.is_empty())
                             {
                                 theme.texts.strings.spawn_fret_text(
                                     commands,
                                     note_entity,
                                     &assets,
-                                    fret,
+                                    data.value.calc_fret_text(fret).as_str(),
                                 );
                             }
                         }
@@ -130,3 +132,24 @@ impl_pick_system!(
     HandShape4,
     get_fretted_shape4
 );
+impl_pick_system!(
+    create_pick_notes5,
+    create_pick_tones5,
+    Fretboard5,
+    HandShape5,
+    get_fretted_shape5
+);
+impl_pick_system!(
+    create_pick_notes7,
+    create_pick_tones7,
+    Fretboard7,
+    HandShape7,
+    get_fretted_shape7
+);
+impl_pick_system!(
+    create_pick_notes8,
+    create_pick_tones8,
+    Fretboard8,
+    HandShape8,
+    get_fretted_shape8
+);
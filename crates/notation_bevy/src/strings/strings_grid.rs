@@ -33,3 +33,6 @@ macro_rules! impl_strings_grid {
 
 impl_strings_grid!(StringsGrid6, 6);
 impl_strings_grid!(StringsGrid4, 4);
+impl_strings_grid!(StringsGrid5, 5);
+impl_strings_grid!(StringsGrid7, 7);
+impl_strings_grid!(StringsGrid8, 8);
@@ -3,9 +3,12 @@ use bevy::prelude::*;
 
 use super::pick_bundle::PickBundle;
 
-use super::strings_grid::{StringsGrid4, StringsGrid6};
+use super::strings_grid::{StringsGrid4, StringsGrid5, StringsGrid6, StringsGrid7, StringsGrid8};
 use crate::prelude::{NotationAssets, NotationAssetsStates, NotationSettings, NotationTheme, SingleData};
-use notation_model::prelude::{LaneKind, BarLane, FrettedEntry4, FrettedEntry6, LaneEntry, TrackKind};
+use notation_model::prelude::{
+    LaneKind, BarLane, FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8,
+    LaneEntry,
+};
 
 pub struct StringsPlugin;
 
@@ -15,6 +18,9 @@ impl Plugin for StringsPlugin {
             SystemSet::on_update(NotationAssetsStates::Loaded)
                 .with_system(on_add_fretted_grid6)
                 .with_system(on_add_fretted_grid4)
+                .with_system(on_add_fretted_grid5)
+                .with_system(on_add_fretted_grid7)
+                .with_system(on_add_fretted_grid8)
                 .with_system(super::pick_systems::on_entry_playing_changed),
         );
     }
@@ -22,8 +28,12 @@ impl Plugin for StringsPlugin {
 
 impl StringsPlugin {
     pub fn insert_lane_extra(commands: &mut EntityCommands, lane: &BarLane) {
-        match lane.track.kind {
-            TrackKind::Guitar => Self::insert_lane_extra6(commands, lane),
+        match lane.track.fretted_string_num() {
+            Some(4) => Self::insert_lane_extra4(commands, lane),
+            Some(5) => Self::insert_lane_extra5(commands, lane),
+            Some(6) => Self::insert_lane_extra6(commands, lane),
+            Some(7) => Self::insert_lane_extra7(commands, lane),
+            Some(8) => Self::insert_lane_extra8(commands, lane),
             _ => (),
         }
     }
@@ -106,3 +116,30 @@ impl_strings_plugin!(
     FrettedEntry4,
     StringsGrid4
 );
+impl_strings_plugin!(
+    on_add_fretted_grid5,
+    insert_lane_extra5,
+    insert_entry_extra5,
+    create_pick_notes5,
+    create_pick_tones5,
+    FrettedEntry5,
+    StringsGrid5
+);
+impl_strings_plugin!(
+    on_add_fretted_grid7,
+    insert_lane_extra7,
+    insert_entry_extra7,
+    create_pick_notes7,
+    create_pick_tones7,
+    FrettedEntry7,
+    StringsGrid7
+);
+impl_strings_plugin!(
+    on_add_fretted_grid8,
+    insert_lane_extra8,
+    insert_entry_extra8,
+    create_pick_notes8,
+    create_pick_tones8,
+    FrettedEntry8,
+    StringsGrid8
+);
@@ -204,13 +204,13 @@ impl TabBars {
             for ((_k, _i), lane) in bar.lanes.iter() {
                 let lane_id = lane.id();
                 if !lane_layouts.contains_key(&lane_id) {
-                    let height = theme.sizes.calc_lane_height(settings, lane.kind);
+                    let height = theme.sizes.calc_lane_height(settings, lane.kind, &lane.track);
                     let margin = theme.sizes.layout.lane_margin;
                     lane_layouts.insert(lane_id, (lane.kind, LaneLayoutData::new(&lane, height, margin)));
                     if lane.kind == LaneKind::Strings && !settings.hide_harmony_lane {
                         let lane_id = lane.kind_id(LaneKind::Harmony);
                         if !lane_layouts.contains_key(&lane_id) {
-                            let height = theme.sizes.calc_lane_height(settings, LaneKind::Harmony);
+                            let height = theme.sizes.calc_lane_height(settings, LaneKind::Harmony, &lane.track);
                             let margin = theme.sizes.layout.lane_margin;
                             lane_layouts.insert(lane_id, (lane.kind, LaneLayoutData::new_virtual(&lane, LaneKind::Harmony, height, margin)));
                         }
@@ -75,8 +75,17 @@ impl GuitarTheme {
     pub fn calc_scale(&self, guitar_width: f32) -> f32 {
         guitar_width / self.syllable_base_width
     }
-    pub fn calc_string_x(&self, string: u8, guitar_width: f32) -> f32 {
-        -1.0 * (string as f32 - 3.5) * guitar_width * self.string_x_factor
+    /// The strings are squeezed into the width of the 6 strings in the image.
+    pub fn calc_string_x_factor(&self, string_num: u8) -> f32 {
+        if string_num <= GUITAR_STRING_NUM as u8 {
+            self.string_x_factor
+        } else {
+            self.string_x_factor * (GUITAR_STRING_NUM - 1) as f32 / (string_num - 1) as f32
+        }
+    }
+    pub fn calc_string_x(&self, string: u8, string_num: u8, guitar_width: f32) -> f32 {
+        let center = (string_num as f32 + 1.0) / 2.0;
+        -1.0 * (string as f32 - center) * guitar_width * self.calc_string_x_factor(string_num)
     }
     pub fn calc_fret_y(&self, fret: u8, guitar_height: f32) -> f32 {
         let index = if fret as usize >= self.fret_y_factors.len() {
@@ -1,5 +1,7 @@
 use notation_bevy_utils::prelude::LayoutSize;
-use notation_model::prelude::{LaneKind, Note, PlayingState, Semitones, Tab, TrackKind};
+use notation_model::prelude::{
+    LaneKind, Note, PlayingState, Semitones, Tab, Track, TrackKind, GUITAR_STRING_NUM,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::prelude::NotationSettings;
@@ -193,19 +195,14 @@ impl NotesSizes {
         for bar in tab.bars.iter() {
             if let Some(lane) = bar.get_lane_of_kind(LaneKind::Strings, track_index) {
                 for entry in lane.entries.iter() {
-                    if let Some(fretted_entry) = entry.model.proto.as_fretted6() {
-                        if let Some(pick) = fretted_entry.as_pick() {
-                            if let Some((fretboard, shape)) = bar.get_fretted_shape6(entry) {
-                                let tone = fretboard.pick_tone(&tab.meta.scale, &tab.meta.key, &shape, pick);
-                                for note in tone.get_notes() {
-                                    let v = Semitones::from(note);
-                                    if v < self.lowest {
-                                        self.lowest = v
-                                    }
-                                    if v > self.highest {
-                                        self.highest = v
-                                    }
-                                }
+                    if let Some(tone) = Tab::get_entry_tone(bar, entry) {
+                        for note in tone.get_notes() {
+                            let v = Semitones::from(note);
+                            if v < self.lowest {
+                                self.lowest = v
+                            }
+                            if v > self.highest {
+                                self.highest = v
                             }
                         }
                     }
@@ -267,8 +264,8 @@ impl Default for StringsSizes {
     }
 }
 impl StringsSizes {
-    pub fn layout_height(&self) -> f32 {
-        self.string_space * 6.0
+    pub fn layout_height(&self, string_num: usize) -> f32 {
+        self.string_space * string_num as f32
     }
     pub fn calc_string_y(&self, string: u8) -> f32 {
         -1.0 * self.string_space * (string as f32 - 0.5)
@@ -364,7 +361,12 @@ impl ThemeSizes {
         };
         LayoutSize::new(0.0, height)
     }
-    pub fn calc_lane_height(&self, settings: &NotationSettings, lane_kind: LaneKind) -> f32 {
+    pub fn calc_lane_height(
+        &self,
+        settings: &NotationSettings,
+        lane_kind: LaneKind,
+        track: &Track,
+    ) -> f32 {
         match lane_kind {
             LaneKind::Lyrics => {
                 if settings.hide_lyrics_lane {
@@ -391,7 +393,8 @@ impl ThemeSizes {
                 if settings.hide_strings_lane {
                     0.0
                 } else {
-                    self.strings.layout_height()
+                    let string_num = track.fretted_string_num().unwrap_or(GUITAR_STRING_NUM);
+                    self.strings.layout_height(string_num)
                 }
             }
             LaneKind::Shapes => {
@@ -1,5 +1,5 @@
 use notation_bevy_utils::prelude::{BevyUtil, LayoutData};
-use notation_model::prelude::{Syllable, Scale, Key};
+use notation_model::prelude::{Syllable, Scale, Key, Note, Pitch};
 use serde::{Deserialize, Serialize};
 
 use bevy::prelude::*;
@@ -240,11 +240,9 @@ impl NoteTexts {
         entity: Entity,
         assets: &NotationAssets,
         settings: &NotationSettings,
-        scale: &Scale,
-        key: &Key,
-        syllable: &Syllable,
+        note: &Note,
     ) {
-        self.spawn_scaled_note_text(commands, entity, assets, settings, scale, key, syllable, 1.0);
+        self.spawn_pitch_text(commands, entity, assets, settings, &note.pitch, &note.syllable, 1.0);
     }
     pub fn spawn_scaled_note_text(
         &self,
@@ -257,7 +255,20 @@ impl NoteTexts {
         syllable: &Syllable,
         size_scale: f32,
     ) {
-        let text = Self::calc_text(settings, scale, key, syllable);
+        let pitch = scale.calc_pitch(key, syllable);
+        self.spawn_pitch_text(commands, entity, assets, settings, &pitch, syllable, size_scale);
+    }
+    pub fn spawn_pitch_text(
+        &self,
+        commands: &mut Commands,
+        entity: Entity,
+        assets: &NotationAssets,
+        settings: &NotationSettings,
+        pitch: &Pitch,
+        syllable: &Syllable,
+        size_scale: f32,
+    ) {
+        let text = Self::calc_text(settings, pitch, syllable);
         BevyUtil::spawn_text(
             commands,
             entity,
@@ -278,12 +289,11 @@ impl NoteTexts {
     }
     pub fn calc_text(
         settings: &NotationSettings,
-        scale: &Scale,
-        key: &Key,
+        pitch: &Pitch,
         syllable: &Syllable,
     ) -> String {
         let pitch_text = if settings.show_note_pitch {
-            Some(scale.calc_pitch(key, syllable).to_text())
+            Some(pitch.to_text())
         } else {
             None
         };
@@ -329,13 +339,12 @@ impl StringsTexts {
         commands: &mut Commands,
         entity: Entity,
         assets: &NotationAssets,
-        fret: u8,
+        text: &str,
     ) {
-        let text = format!("{}", fret);
         BevyUtil::spawn_text(
             commands,
             entity,
-            text.as_str(),
+            text,
             assets.fret_font.clone(),
             self.fret_font_size,
             self.fret_font_color,
@@ -22,7 +22,7 @@ pub struct ToneNoteValue {
 impl ToneNoteValue {
     pub fn new(tab_bar: &TabBar, note: Note, mode: ToneMode) -> Self {
         Self {
-            note,
+            note: tab_bar.rekey_note(&note),
             mode,
             playing_state: PlayingState::Idle,
             bar_size: 0.0,
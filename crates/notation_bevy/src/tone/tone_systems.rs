@@ -26,7 +26,6 @@ pub fn create_tone_notes(
     if let Some(lane) = entry.lane() {
         let mode: ToneMode = lane.kind.into();
         let bar = lane.bar().unwrap();
-        let meta = bar.tab_meta();
         for note in tone.get_notes() {
             let data = ToneNoteData::new(entry, ToneNoteValue::new(&bar, note, mode));
             let note_entity = data.create(commands, theme, entity);
@@ -41,8 +40,7 @@ pub fn create_tone_notes(
                         note_entity,
                         assets,
                         settings,
-                        &meta.scale, &meta.key,
-                        &data.value.syllable(),
+                        &data.value.note,
                     )
                 }
             }
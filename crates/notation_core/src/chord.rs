@@ -4,7 +4,7 @@ use std::hash::Hash;
 use serde::{Deserialize, Serialize};
 
 use crate::interval::Interval;
-use crate::prelude::{Intervals, Semitones, Syllable};
+use crate::prelude::{ChordQuality, Intervals, Note, Semitones, Syllable};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
 pub struct Chord {
@@ -46,6 +46,98 @@ impl Chord {
         None
     }
 }
+impl Chord {
+    /// Ranked chord candidates for the notes, best first, each distinct
+    /// syllable is tried as root, with the lowest note as bass if it's not
+    /// the root, e.g. `Tone::get_notes()` or the notes of a hand shape, root
+    /// positions and triads or seventh chords are preferred.
+    pub fn recognize(notes: &[Note]) -> Vec<Self> {
+        let pitch_class = |x: &Syllable| Semitones::from(*x).0.rem_euclid(12);
+        let bass = match notes.iter().min_by_key(|x| Semitones::from(**x)) {
+            Some(note) => pitch_class(&note.syllable),
+            None => return vec![],
+        };
+        let mut roots: Vec<Syllable> = Vec::new();
+        for note in notes.iter() {
+            if !roots
+                .iter()
+                .any(|x| pitch_class(x) == pitch_class(&note.syllable))
+            {
+                roots.push(note.syllable);
+            }
+        }
+        let mut candidates: Vec<(i32, Self)> = roots
+            .iter()
+            .map(|root| {
+                let mut semitones: Vec<i8> = roots
+                    .iter()
+                    .map(|x| (pitch_class(x) - pitch_class(root)).rem_euclid(12))
+                    .filter(|x| *x != 0)
+                    .collect();
+                semitones.sort_unstable();
+                let simple: Vec<Interval> = semitones
+                    .iter()
+                    .map(|x| Interval::from(Semitones(*x)))
+                    .collect();
+                let quality = ChordQuality::closest(&simple);
+                let quality_intervals = quality.get_intervals();
+                let has_fifth = semitones.contains(&7);
+                let mut extras = 0;
+                let intervals: Vec<Interval> = semitones
+                    .iter()
+                    .map(|x| {
+                        match quality_intervals
+                            .iter()
+                            .find(|y| Semitones::from(**y).0 % 12 == *x)
+                        {
+                            Some(interval) => *interval,
+                            None => {
+                                extras += 1;
+                                Self::extra_interval(*x, has_fifth)
+                            }
+                        }
+                    })
+                    .collect();
+                let bass = if pitch_class(root) == bass {
+                    None
+                } else {
+                    Some(Interval::from(Semitones(
+                        (bass - pitch_class(root)).rem_euclid(12),
+                    )))
+                };
+                // extra notes cost more than an inversion, so B-D-F-G is G7/B, not Bdimb13
+                let score = quality.match_score(&simple).0 - extras * 2
+                    + if bass.is_none() { 2 } else { 0 }
+                    + if quality.is_common() { 1 } else { 0 };
+                let intervals = if intervals.is_empty() {
+                    Intervals::Monad
+                } else {
+                    intervals.into()
+                };
+                (score, Self::new(*root, intervals, bass))
+            })
+            .collect();
+        candidates.sort_by_key(|x| -x.0);
+        candidates.into_iter().map(|x| x.1).collect()
+    }
+    /// Intervals not in the quality are written as extensions, unless the
+    /// fifth is missing, e.g. `C7b5`.
+    fn extra_interval(semitones: i8, has_fifth: bool) -> Interval {
+        match semitones {
+            1 => Interval::Minor9th,
+            2 => Interval::Major9th,
+            3 => Interval::Augmented9th,
+            5 => Interval::Perfect11th,
+            6 if !has_fifth => Interval::Diminished5th,
+            6 => Interval::Augmented11th,
+            8 if !has_fifth => Interval::Augmented5th,
+            8 => Interval::Minor13th,
+            9 => Interval::Major13th,
+            x => Interval::from(Semitones(x)),
+        }
+    }
+}
+
 impl Hash for Chord {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         let string = self.to_string();
@@ -76,3 +168,38 @@ impl From<(Syllable, Vec<Interval>, Interval)> for Chord {
         Self::new(v.0, v.1.into(), Some(v.2))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Key, Scale};
+
+    fn recognize(semitones: &[i8]) -> String {
+        let notes: Vec<Note> = semitones
+            .iter()
+            .map(|x| Scale::Ionian.calc_note_from_semitones(&Key::C, Semitones(*x)))
+            .collect();
+        Chord::recognize(&notes)[0].to_text(&Scale::Ionian, &Key::C)
+    }
+
+    #[test]
+    fn test_recognize_root_position() {
+        assert_eq!(recognize(&[48, 52, 55]), "C");
+        assert_eq!(recognize(&[45, 48, 52]), "Am");
+        assert_eq!(recognize(&[48, 52, 55, 57]), "C6");
+        assert_eq!(recognize(&[53, 57, 60, 62]), "F6");
+        assert_eq!(recognize(&[45, 48, 52, 55]), "Am7");
+        assert_eq!(recognize(&[50, 53, 57, 60]), "Dm7");
+        assert_eq!(recognize(&[47, 50, 53, 57]), "Bm7b5");
+    }
+
+    #[test]
+    fn test_recognize_inversions() {
+        assert_eq!(recognize(&[52, 55, 60]), "C/E");
+        assert_eq!(recognize(&[55, 60, 64]), "C/G");
+        assert_eq!(recognize(&[47, 50, 53, 55]), "G7/B");
+        assert_eq!(recognize(&[50, 53, 55, 59]), "G7/D");
+        assert_eq!(recognize(&[53, 55, 59, 62]), "G7/F");
+        assert_eq!(recognize(&[52, 55, 59, 60]), "Cmaj7/E");
+    }
+}
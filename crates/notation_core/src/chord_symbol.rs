@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{Pitch, Syllable};
+use crate::prelude::{Chord, Interval, Intervals, Key, Pitch, Scale, Semitones, Syllable};
 
 // https://hellomusictheory.com/learn/chord-inversions/
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -35,14 +35,250 @@ pub enum ChordQuality {
     Dominant7th,
     Diminished7th,
     HaldDiminished7th,
+    Power,
+    Major6th,
+    Minor6th,
+    SixNine,
+    Add9,
+    MinorAdd9,
+    Major9th,
+    Minor9th,
+    Dominant9th,
+    Dominant11th,
+    Minor11th,
+    Major13th,
+    Minor13th,
+    Dominant13th,
+    Dominant7thFlat9,
+    Dominant7thSharp9,
+    Dominant7thSharp11,
+    Dominant7thFlat13,
 }
-//Extended, Altered, Quartal and Quintal not supported
+//Quartal and Quintal not supported
 impl Display for ChordQuality {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
+impl ChordQuality {
+    /// Intervals above the root, the 11th is left out of the major and
+    /// dominant 13th chords, as it's usually not played.
+    pub fn get_intervals(&self) -> Vec<Interval> {
+        use Interval::*;
+        match self {
+            Self::Major => vec![Major3nd, Perfect5th],
+            Self::Minor => vec![Minor3nd, Perfect5th],
+            Self::Diminished => vec![Minor3nd, Diminished5th],
+            Self::Augmented => vec![Major3nd, Augmented5th],
+            Self::Suspended2th => vec![Major2nd, Perfect5th],
+            Self::Suspended4th => vec![Perfect4th, Perfect5th],
+            Self::Major7th => vec![Major3nd, Perfect5th, Major7th],
+            Self::Minor7th => vec![Minor3nd, Perfect5th, Minor7th],
+            Self::Dominant7th => vec![Major3nd, Perfect5th, Minor7th],
+            Self::Diminished7th => vec![Minor3nd, Diminished5th, Diminished7th],
+            Self::HaldDiminished7th => vec![Minor3nd, Diminished5th, Minor7th],
+            Self::Power => vec![Perfect5th],
+            Self::Major6th => vec![Major3nd, Perfect5th, Major6th],
+            Self::Minor6th => vec![Minor3nd, Perfect5th, Major6th],
+            Self::SixNine => vec![Major3nd, Perfect5th, Major6th, Major9th],
+            Self::Add9 => vec![Major3nd, Perfect5th, Major9th],
+            Self::MinorAdd9 => vec![Minor3nd, Perfect5th, Major9th],
+            Self::Major9th => vec![Major3nd, Perfect5th, Major7th, Major9th],
+            Self::Minor9th => vec![Minor3nd, Perfect5th, Minor7th, Major9th],
+            Self::Dominant9th => vec![Major3nd, Perfect5th, Minor7th, Major9th],
+            Self::Dominant11th => vec![Major3nd, Perfect5th, Minor7th, Major9th, Perfect11th],
+            Self::Minor11th => vec![Minor3nd, Perfect5th, Minor7th, Major9th, Perfect11th],
+            Self::Major13th => vec![Major3nd, Perfect5th, Major7th, Major9th, Major13th],
+            Self::Minor13th => vec![
+                Minor3nd,
+                Perfect5th,
+                Minor7th,
+                Major9th,
+                Perfect11th,
+                Major13th,
+            ],
+            Self::Dominant13th => vec![Major3nd, Perfect5th, Minor7th, Major9th, Major13th],
+            Self::Dominant7thFlat9 => vec![Major3nd, Perfect5th, Minor7th, Minor9th],
+            Self::Dominant7thSharp9 => vec![Major3nd, Perfect5th, Minor7th, Augmented9th],
+            Self::Dominant7thSharp11 => vec![Major3nd, Perfect5th, Minor7th, Augmented11th],
+            Self::Dominant7thFlat13 => vec![Major3nd, Perfect5th, Minor7th, Minor13th],
+        }
+    }
+}
+
+impl ChordQuality {
+    pub const ALL: [ChordQuality; 29] = [
+        Self::Major,
+        Self::Minor,
+        Self::Diminished,
+        Self::Augmented,
+        Self::Suspended2th,
+        Self::Suspended4th,
+        Self::Major7th,
+        Self::Minor7th,
+        Self::Dominant7th,
+        Self::Diminished7th,
+        Self::HaldDiminished7th,
+        Self::Power,
+        Self::Major6th,
+        Self::Minor6th,
+        Self::SixNine,
+        Self::Add9,
+        Self::MinorAdd9,
+        Self::Major9th,
+        Self::Minor9th,
+        Self::Dominant9th,
+        Self::Dominant11th,
+        Self::Minor11th,
+        Self::Major13th,
+        Self::Minor13th,
+        Self::Dominant13th,
+        Self::Dominant7thFlat9,
+        Self::Dominant7thSharp9,
+        Self::Dominant7thSharp11,
+        Self::Dominant7thFlat13,
+    ];
+    /// Other common spellings, only used for parsing.
+    const ALIASES: [(&'static str, ChordQuality); 31] = [
+        ("min", Self::Minor),
+        ("mi", Self::Minor),
+        ("-", Self::Minor),
+        ("maj", Self::Major),
+        ("M", Self::Major),
+        ("o", Self::Diminished),
+        ("°", Self::Diminished),
+        ("+", Self::Augmented),
+        ("sus", Self::Suspended4th),
+        ("M7", Self::Major7th),
+        ("ma7", Self::Major7th),
+        ("Δ", Self::Major7th),
+        ("Δ7", Self::Major7th),
+        ("min7", Self::Minor7th),
+        ("-7", Self::Minor7th),
+        ("o7", Self::Diminished7th),
+        ("°7", Self::Diminished7th),
+        ("ø", Self::HaldDiminished7th),
+        ("ø7", Self::HaldDiminished7th),
+        ("m7-5", Self::HaldDiminished7th),
+        ("-7b5", Self::HaldDiminished7th),
+        ("min6", Self::Minor6th),
+        ("-6", Self::Minor6th),
+        ("69", Self::SixNine),
+        ("add2", Self::Add9),
+        ("M9", Self::Major9th),
+        ("Δ9", Self::Major9th),
+        ("min9", Self::Minor9th),
+        ("-9", Self::Minor9th),
+        ("min11", Self::Minor11th),
+        ("M13", Self::Major13th),
+    ];
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Major => "",
+            Self::Minor => "m",
+            Self::Diminished => "dim",
+            Self::Augmented => "aug",
+            Self::Suspended2th => "sus2",
+            Self::Suspended4th => "sus4",
+            Self::Major7th => "maj7",
+            Self::Minor7th => "m7",
+            Self::Dominant7th => "7",
+            Self::Diminished7th => "dim7",
+            Self::HaldDiminished7th => "m7b5",
+            Self::Power => "5",
+            Self::Major6th => "6",
+            Self::Minor6th => "m6",
+            Self::SixNine => "6/9",
+            Self::Add9 => "add9",
+            Self::MinorAdd9 => "madd9",
+            Self::Major9th => "maj9",
+            Self::Minor9th => "m9",
+            Self::Dominant9th => "9",
+            Self::Dominant11th => "11",
+            Self::Minor11th => "m11",
+            Self::Major13th => "maj13",
+            Self::Minor13th => "m13",
+            Self::Dominant13th => "13",
+            Self::Dominant7thFlat9 => "7b9",
+            Self::Dominant7thSharp9 => "7#9",
+            Self::Dominant7thSharp11 => "7#11",
+            Self::Dominant7thFlat13 => "7b13",
+        }
+        .into()
+    }
+    /// Returns the longest quality suffix at the beginning of the text, and
+    /// the rest of it, major is returned if nothing else matches.
+    pub fn parse_prefix(text: &str) -> (Self, &str) {
+        let (suffix, quality) = Self::ALL
+            .iter()
+            .map(|x| (x.to_text(), *x))
+            .chain(Self::ALIASES.iter().map(|(x, y)| (x.to_string(), *y)))
+            .filter(|(suffix, _)| text.starts_with(suffix.as_str()))
+            .max_by_key(|(suffix, _)| suffix.len())
+            .unwrap_or((String::new(), Self::Major));
+        (quality, &text[suffix.len()..])
+    }
+    pub fn from_text(text: &str) -> Option<Self> {
+        match Self::parse_prefix(text) {
+            (quality, "") => Some(quality),
+            _ => None,
+        }
+    }
+}
+
+impl ChordQuality {
+    /// Triads and seventh chords, preferred over extended readings of the same notes.
+    pub fn is_common(&self) -> bool {
+        matches!(
+            self,
+            Self::Major
+                | Self::Minor
+                | Self::Diminished
+                | Self::Augmented
+                | Self::Suspended2th
+                | Self::Suspended4th
+                | Self::Major7th
+                | Self::Minor7th
+                | Self::Dominant7th
+                | Self::Diminished7th
+                | Self::HaldDiminished7th
+        )
+    }
+    /// Matched intervals minus missing ones, compared within an octave,
+    /// missing intervals cost twice, so C9sus4 is not Cm11no3.
+    pub fn match_score(&self, intervals: &[Interval]) -> (i32, usize) {
+        let quality_intervals = self.get_intervals();
+        let matched = quality_intervals
+            .iter()
+            .filter(|x| {
+                intervals
+                    .iter()
+                    .any(|y| simple_semitones(x) == simple_semitones(y))
+            })
+            .count();
+        let missing = quality_intervals.len() - matched;
+        (matched as i32 - missing as i32 * 2, missing)
+    }
+    /// The quality with the best score, fewer missing intervals first.
+    pub fn closest(intervals: &[Interval]) -> Self {
+        Self::ALL
+            .iter()
+            .max_by_key(|x| {
+                let (score, missing) = x.match_score(intervals);
+                (score, -(missing as i32))
+            })
+            .copied()
+            .unwrap_or(Self::Major)
+    }
+}
+
+impl From<ChordQuality> for Intervals {
+    fn from(v: ChordQuality) -> Self {
+        v.get_intervals().into()
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub struct ChordSymbol {
     pub pitch: Pitch,
@@ -70,4 +306,323 @@ impl ChordSymbol {
             syllable,
         }
     }
+    /// Splits `F#m7b5/C` into `F#m7b5` and `C`, the slash of `6/9` is kept.
+    fn split_bass(text: &str) -> (&str, Option<&str>) {
+        match text.rsplit_once('/') {
+            Some((name, bass)) if bass.starts_with(|x: char| ('A'..='G').contains(&x)) => {
+                (name, Some(bass))
+            }
+            _ => (text, None),
+        }
+    }
+    fn parse_bass(bass: Option<&str>) -> Option<Option<Pitch>> {
+        match bass {
+            Some(bass) => match Pitch::parse_prefix(bass)? {
+                (pitch, "") => Some(Some(pitch)),
+                _ => None,
+            },
+            None => Some(None),
+        }
+    }
+    /// Parses lead sheet symbols like `F#m7b5/C`, `Gsus4` or `Bbmaj9`, only
+    /// the ones written as a single quality, use [`Chord::from_text`] for
+    /// the others, e.g. `C7#9b13`.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let (name, bass) = Self::split_bass(text.trim());
+        let (pitch, rest) = Pitch::parse_prefix(name)?;
+        let quality = ChordQuality::from_text(rest)?;
+        let inversion = match Self::parse_bass(bass)? {
+            Some(bass) => ChordInversion::SlashBass(bass),
+            None => ChordInversion::RootPosition,
+        };
+        Some(Self::new(pitch, quality, inversion, None))
+    }
+    pub fn to_text(&self) -> String {
+        let bass = match self.inversion {
+            ChordInversion::RootPosition => None,
+            ChordInversion::FirstInversion => self.quality.get_intervals().first().copied(),
+            ChordInversion::SecondInversion => self.quality.get_intervals().get(1).copied(),
+            ChordInversion::ThirdInversion => self.quality.get_intervals().get(2).copied(),
+            ChordInversion::SlashBass(pitch) => {
+                return format!("{}{}/{}", self.pitch, self.quality.to_text(), pitch)
+            }
+        }
+        .map(|x| {
+            let semitones = Semitones::from(self.pitch) + Semitones::from(x);
+            let name = self
+                .pitch
+                .name
+                .shift(interval_degree(&x).unwrap_or(1) as i8 - 1);
+            Pitch::from_name_semitones(name, semitones).unwrap_or_else(|| semitones.into())
+        });
+        match bass {
+            Some(bass) => format!("{}{}/{}", self.pitch, self.quality.to_text(), bass),
+            None => format!("{}{}", self.pitch, self.quality.to_text()),
+        }
+    }
+    pub fn with_key(&self, scale: &Scale, key: &Key) -> Self {
+        Self {
+            syllable: Some(scale.calc_syllable(key, &self.pitch)),
+            ..*self
+        }
+    }
+    pub fn to_chord(&self, scale: &Scale, key: &Key) -> Chord {
+        let intervals = self.quality.get_intervals();
+        let bass = match self.inversion {
+            ChordInversion::RootPosition => None,
+            ChordInversion::FirstInversion => intervals.first().copied(),
+            ChordInversion::SecondInversion => intervals.get(1).copied(),
+            ChordInversion::ThirdInversion => intervals.get(2).copied(),
+            ChordInversion::SlashBass(pitch) => Some(Interval::from(Semitones(
+                (Semitones::from(pitch) - Semitones::from(self.pitch))
+                    .0
+                    .rem_euclid(12),
+            ))),
+        };
+        let root = scale.calc_syllable(key, &self.pitch);
+        Chord::new(root, intervals.into(), bass)
+    }
+}
+
+/// The number in chord symbols, e.g. 9 for both `Major9th` and `Minor9th`.
+fn interval_degree(interval: &Interval) -> Option<u8> {
+    let text = interval.to_text();
+    let digits = text.chars().take_while(|x| x.is_ascii_digit()).count();
+    text[..digits].parse().ok()
+}
+
+fn degree_interval(degree: u8, alter: i8) -> Option<Interval> {
+    let interval = match (degree, alter) {
+        (2, 0) => Interval::Major2nd,
+        (2, -1) => Interval::Minor2nd,
+        (3, 0) => Interval::Major3nd,
+        (3, -1) => Interval::Minor3nd,
+        (4, 0) => Interval::Perfect4th,
+        (4, 1) => Interval::Augmented4th,
+        (5, 0) => Interval::Perfect5th,
+        (5, -1) => Interval::Diminished5th,
+        (5, 1) => Interval::Augmented5th,
+        (6, 0) => Interval::Major6th,
+        (6, -1) => Interval::Minor6th,
+        (7, 0) => Interval::Minor7th,
+        (9, 0) => Interval::Major9th,
+        (9, -1) => Interval::Minor9th,
+        (9, 1) => Interval::Augmented9th,
+        (11, 0) => Interval::Perfect11th,
+        (11, 1) => Interval::Augmented11th,
+        (13, 0) => Interval::Major13th,
+        (13, -1) => Interval::Minor13th,
+        _ => return None,
+    };
+    Some(interval)
+}
+
+fn simple_semitones(interval: &Interval) -> i8 {
+    Semitones::from(*interval).0 % 12
+}
+
+impl Chord {
+    /// Parses any lead sheet symbol, e.g. `C7#9b13`, `Dm(maj7)`, `E7sus4`
+    /// or `Gadd11no3/B`, the root and bass are relative to the key.
+    pub fn from_text(scale: &Scale, key: &Key, text: &str) -> Option<Self> {
+        let (name, bass) = ChordSymbol::split_bass(text.trim());
+        let (pitch, mut rest) = Pitch::parse_prefix(name)?;
+        let mut intervals = Vec::new();
+        // 7sus4 and 9sus4 instead of sus4add7
+        if rest.starts_with("7sus") {
+            intervals.push(Interval::Minor7th);
+            rest = &rest[1..];
+        } else if rest.starts_with("9sus") {
+            intervals.push(Interval::Minor7th);
+            intervals.push(Interval::Major9th);
+            rest = &rest[1..];
+        }
+        let (quality, mut rest) = ChordQuality::parse_prefix(rest);
+        intervals.extend(quality.get_intervals());
+        loop {
+            rest = rest.trim_start_matches(['(', ')', ',', ' ']);
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(x) = rest.strip_prefix("maj7") {
+                intervals.push(Interval::Major7th);
+                rest = x;
+                continue;
+            }
+            let (remove, alter, body) = if let Some(x) = rest.strip_prefix("add") {
+                (false, 0, x)
+            } else if let Some(x) = rest.strip_prefix("no") {
+                (true, 0, x)
+            } else if let Some(x) = rest.strip_prefix(['b', '-']) {
+                (false, -1, x)
+            } else if let Some(x) = rest.strip_prefix(['#', '+']) {
+                (false, 1, x)
+            } else {
+                (false, 0, rest)
+            };
+            let digits = body.chars().take_while(|x| x.is_ascii_digit()).count();
+            let degree: u8 = body[..digits].parse().ok()?;
+            rest = &body[digits..];
+            if remove || alter != 0 {
+                intervals.retain(|x| interval_degree(x) != Some(degree));
+            }
+            if !remove {
+                intervals.push(degree_interval(degree, alter)?);
+            }
+        }
+        intervals.sort_by_key(|x| (Semitones::from(*x), *x));
+        intervals.dedup_by_key(|x| Semitones::from(*x));
+        let bass = ChordSymbol::parse_bass(bass)?.map(|bass| {
+            Interval::from(Semitones(
+                (Semitones::from(bass) - Semitones::from(pitch))
+                    .0
+                    .rem_euclid(12),
+            ))
+        });
+        let intervals = if intervals.is_empty() {
+            Intervals::Monad
+        } else {
+            intervals.into()
+        };
+        Some(Self::new(scale.calc_syllable(key, &pitch), intervals, bass))
+    }
+    /// The closest quality, with extra intervals as `add9`, `b9` or `#11`,
+    /// and missing ones as `no3`, a monad is written as the root only.
+    pub fn to_text(&self, scale: &Scale, key: &Key) -> String {
+        let mut text = scale.calc_pitch(key, &self.root).to_string();
+        let mut intervals = self.intervals.get_intervals();
+        if !intervals.is_empty() {
+            let contains = |list: &[Interval], semitones: i8| {
+                list.iter().any(|x| simple_semitones(x) == semitones)
+            };
+            // C7b5 instead of C7#11no5
+            let flat_fifth =
+                contains(&intervals, 4) && contains(&intervals, 6) && !contains(&intervals, 7);
+            if flat_fifth {
+                intervals.retain(|x| simple_semitones(x) != 6);
+                intervals.push(Interval::Perfect5th);
+            }
+            let quality = ChordQuality::closest(&intervals);
+            // C7#9b13 instead of C7b13#9
+            let quality = match quality {
+                ChordQuality::Dominant7thFlat9
+                | ChordQuality::Dominant7thSharp9
+                | ChordQuality::Dominant7thSharp11
+                | ChordQuality::Dominant7thFlat13
+                    if intervals.len() > quality.get_intervals().len() =>
+                {
+                    ChordQuality::Dominant7th
+                }
+                _ => quality,
+            };
+            let quality_intervals = quality.get_intervals();
+            let mut extras: Vec<(u8, String)> = intervals
+                .iter()
+                .map(simple_semitones)
+                .filter(|x| !contains(&quality_intervals, *x))
+                .filter_map(|x| match x {
+                    1 => Some((9, "b9")),
+                    2 => Some((9, "add9")),
+                    3 => Some((9, "#9")),
+                    4 => Some((3, "add3")),
+                    5 => Some((11, "add11")),
+                    6 => Some((11, "#11")),
+                    7 => Some((5, "add5")),
+                    8 => Some((13, "b13")),
+                    9 => Some((13, "add13")),
+                    10 if quality == ChordQuality::Augmented => Some((7, "7")),
+                    10 => Some((7, "add7")),
+                    11 => Some((7, "maj7")),
+                    _ => None,
+                })
+                .map(|(degree, x)| (degree, x.to_string()))
+                .collect();
+            extras.extend(
+                quality_intervals
+                    .iter()
+                    .filter(|x| !contains(&intervals, simple_semitones(x)))
+                    .filter_map(interval_degree)
+                    .map(|degree| (degree, format!("no{}", degree))),
+            );
+            if flat_fifth {
+                extras.push((5, "b5".to_string()));
+            }
+            extras.sort();
+            // 7sus4 and 9sus4 instead of sus4add7
+            if matches!(
+                quality,
+                ChordQuality::Suspended2th | ChordQuality::Suspended4th
+            ) && extras.iter().any(|x| x.1 == "add7")
+            {
+                extras.retain(|x| x.1 != "add7");
+                if let Some(index) = extras.iter().position(|x| x.1 == "add9") {
+                    extras.remove(index);
+                    text.push('9');
+                } else {
+                    text.push('7');
+                }
+            }
+            text.push_str(&quality.to_text());
+            // Cm(maj7) instead of Cmmaj7
+            if quality == ChordQuality::Minor {
+                if let Some(index) = extras.iter().position(|x| x.1 == "maj7") {
+                    extras.remove(index);
+                    text.push_str("(maj7)");
+                }
+            }
+            for (_, extra) in extras {
+                text.push_str(&extra);
+            }
+        }
+        if let Some(bass) = self.bass {
+            text.push('/');
+            text.push_str(
+                &scale
+                    .calc_pitch(key, &bass.syllable_on_root(&self.root))
+                    .to_string(),
+            );
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(text: &str) -> String {
+        Chord::from_text(&Scale::Ionian, &Key::C, text)
+            .map(|x| x.to_text(&Scale::Ionian, &Key::C))
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_chord_symbol() {
+        for text in ["F#m7b5/C", "Gsus4", "Bbmaj9", "C6/9", "Am7/G"] {
+            let symbol = ChordSymbol::from_text(text).unwrap();
+            assert_eq!(symbol.to_text(), text);
+        }
+        let symbol = ChordSymbol::from_text("F#m7b5/C").unwrap();
+        assert_eq!(symbol.pitch, Pitch::F_SHARP);
+        assert_eq!(symbol.quality, ChordQuality::HaldDiminished7th);
+        assert_eq!(symbol.inversion, ChordInversion::SlashBass(Pitch::C));
+        assert_eq!(
+            ChordSymbol::from_text("C6/9").unwrap().quality,
+            ChordQuality::SixNine
+        );
+        assert!(ChordSymbol::from_text("H7").is_none());
+    }
+
+    #[test]
+    fn test_chord_text() {
+        for text in [
+            "F#m7b5/C", "Gsus4", "Bbmaj9", "C6/9", "Cm(maj7)", "C7#9b13", "E7sus4", "Ab", "Abmaj7",
+            "Db", "G#", "Bb7/D",
+        ] {
+            assert_eq!(round_trip(text), text);
+        }
+        assert_eq!(round_trip("Cmmaj7"), "Cm(maj7)");
+        assert_eq!(round_trip("CM7"), "Cmaj7");
+        assert_eq!(round_trip("C-7"), "Cm7");
+    }
 }
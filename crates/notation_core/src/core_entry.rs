@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{Chord, Duration, Entry, EntryPassMode, Tone};
+use crate::prelude::{Chord, Duration, Entry, EntryPassMode, Key, Scale, Semitones, Tone};
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub enum CoreEntry {
@@ -37,6 +37,15 @@ impl CoreEntry {
             EntryPassMode::Immediate
         }
     }
+    /// Chords are based on syllables, so only tones need to be re-spelled.
+    pub fn transpose(&self, scale: &Scale, key: &Key, semitones: Semitones) -> Self {
+        match self {
+            CoreEntry::Tone(tone, duration) => {
+                CoreEntry::Tone(tone.transpose(scale, key, semitones), *duration)
+            }
+            _ => *self,
+        }
+    }
 }
 
 impl Entry for CoreEntry {
@@ -4,6 +4,8 @@ use std::ops::{Add, Sub};
 
 use serde::{Deserialize, Serialize};
 
+use crate::prelude::Rational;
+
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub enum Unit {
     Whole,
@@ -12,6 +14,7 @@ pub enum Unit {
     Eighth,
     Sixteenth,
     ThirtySecondth,
+    SixtyFourth,
 }
 
 impl Default for Unit {
@@ -29,6 +32,7 @@ impl Display for Unit {
             Unit::Eighth => "_1_8",
             Unit::Sixteenth => "_1_16",
             Unit::ThirtySecondth => "_1_32",
+            Unit::SixtyFourth => "_1_64",
         })
     }
 }
@@ -41,7 +45,8 @@ impl Unit {
             Self::Quarter => Self::Eighth,
             Self::Eighth => Self::Sixteenth,
             Self::Sixteenth => Self::ThirtySecondth,
-            Self::ThirtySecondth => Self::ThirtySecondth,
+            Self::ThirtySecondth => Self::SixtyFourth,
+            Self::SixtyFourth => Self::SixtyFourth,
         }
     }
     pub fn doubled(&self) -> Self {
@@ -52,6 +57,7 @@ impl Unit {
             Self::Eighth => Self::Quarter,
             Self::Sixteenth => Self::Eighth,
             Self::ThirtySecondth => Self::Sixteenth,
+            Self::SixtyFourth => Self::ThirtySecondth,
         }
     }
 }
@@ -63,6 +69,40 @@ impl Unit {
     pub const _1_8: Self = Unit::Eighth;
     pub const _1_16: Self = Unit::Sixteenth;
     pub const _1_32: Self = Unit::ThirtySecondth;
+    pub const _1_64: Self = Unit::SixtyFourth;
+    pub const ALL: [Self; 7] = [
+        Self::Whole,
+        Self::Half,
+        Self::Quarter,
+        Self::Eighth,
+        Self::Sixteenth,
+        Self::ThirtySecondth,
+        Self::SixtyFourth,
+    ];
+    /// 1 for whole, 2 for half, up to 64.
+    pub fn to_denom(&self) -> u8 {
+        match self {
+            Self::Whole => 1,
+            Self::Half => 2,
+            Self::Quarter => 4,
+            Self::Eighth => 8,
+            Self::Sixteenth => 16,
+            Self::ThirtySecondth => 32,
+            Self::SixtyFourth => 64,
+        }
+    }
+    pub fn from_denom(denom: u32) -> Option<Self> {
+        match denom {
+            1 => Some(Self::Whole),
+            2 => Some(Self::Half),
+            4 => Some(Self::Quarter),
+            8 => Some(Self::Eighth),
+            16 => Some(Self::Sixteenth),
+            32 => Some(Self::ThirtySecondth),
+            64 => Some(Self::SixtyFourth),
+            _ => None,
+        }
+    }
     pub fn to_ident(&self) -> String {
         format!("{}", self)
     }
@@ -74,6 +114,7 @@ impl Unit {
             "_1_8" => Self::_1_8,
             "_1_16" => Self::_1_16,
             "_1_32" => Self::_1_32,
+            "_1_64" => Self::_1_64,
             _ => Self::_1_4,
         }
     }
@@ -138,6 +179,9 @@ pub enum Duration {
     Dotted(Unit),
     Triplet(Unit),
     DottedTriplet(Unit),
+    DoubleDotted(Unit),
+    /// N notes in the time of M, e.g. `Tuplet(Eighth, 5, 4)` for quintuplet eighths.
+    Tuplet(Unit, u8, u8),
 }
 
 impl Display for Duration {
@@ -157,6 +201,14 @@ impl Display for Duration {
                 write!(f, "DT")?;
                 d.fmt(f)
             }
+            Duration::DoubleDotted(d) => {
+                write!(f, "DD")?;
+                d.fmt(f)
+            }
+            Duration::Tuplet(d, actual, normal) => {
+                write!(f, "T{}_{}", actual, normal)?;
+                d.fmt(f)
+            }
         }
     }
 }
@@ -169,6 +221,7 @@ impl Duration {
     pub const _1_8: Self = Duration::Simple(Unit::Eighth);
     pub const _1_16: Self = Duration::Simple(Unit::Sixteenth);
     pub const _1_32: Self = Duration::Simple(Unit::ThirtySecondth);
+    pub const _1_64: Self = Duration::Simple(Unit::SixtyFourth);
     pub const D_1: Self = Duration::Dotted(Unit::Whole);
     pub const D_1_2: Self = Duration::Dotted(Unit::Half);
     pub const D_1_4: Self = Duration::Dotted(Unit::Quarter);
@@ -200,40 +253,70 @@ impl Duration {
             None
         }
     }
+
+    pub fn unit(&self) -> Option<Unit> {
+        match self {
+            Self::Zero => None,
+            Self::Simple(v)
+            | Self::Dotted(v)
+            | Self::Triplet(v)
+            | Self::DottedTriplet(v)
+            | Self::DoubleDotted(v)
+            | Self::Tuplet(v, _, _) => Some(*v),
+        }
+    }
+    pub fn dots(&self) -> u8 {
+        match self {
+            Self::Dotted(_) | Self::DottedTriplet(_) => 1,
+            Self::DoubleDotted(_) => 2,
+            _ => 0,
+        }
+    }
+    /// The actual and normal notes, e.g. `(3, 2)` for triplets.
+    pub fn tuplet(&self) -> Option<(u8, u8)> {
+        match self {
+            Self::Triplet(_) | Self::DottedTriplet(_) => Some((3, 2)),
+            Self::Tuplet(_, actual, normal) => Some((*actual, *normal)),
+            _ => None,
+        }
+    }
 }
 
 impl Duration {
     pub fn to_ident(&self) -> String {
         format!("{}", self)
     }
+    /// e.g. `_1_4`, `D_1_8`, `DD_1_4`, `T_1_8`, `DT_1_4` or `T5_4_1_16`.
     pub fn from_ident(ident: &str) -> Self {
-        match ident {
-            "_0" => Self::_0,
-            "_1" => Self::_1,
-            "_1_2" => Self::_1_2,
-            "_1_4" => Self::_1_4,
-            "_1_8" => Self::_1_8,
-            "_1_16" => Self::_1_16,
-            "_1_32" => Self::_1_32,
-            "D_1" => Self::D_1,
-            "D_1_2" => Self::D_1_2,
-            "D_1_4" => Self::D_1_4,
-            "D_1_8" => Self::D_1_8,
-            "D_1_16" => Self::D_1_16,
-            "D_1_32" => Self::D_1_32,
-            "T_1" => Self::T_1,
-            "T_1_2" => Self::T_1_2,
-            "T_1_4" => Self::T_1_4,
-            "T_1_8" => Self::T_1_8,
-            "T_1_16" => Self::T_1_16,
-            "T_1_32" => Self::T_1_32,
-            "DT_1" => Self::DT_1,
-            "DT_1_2" => Self::DT_1_2,
-            "DT_1_4" => Self::DT_1_4,
-            "DT_1_8" => Self::DT_1_8,
-            "DT_1_16" => Self::DT_1_16,
-            "DT_1_32" => Self::DT_1_32,
-            _ => Self::_0,
+        if ident == "_0" {
+            return Self::_0;
+        }
+        let unit = Unit::ALL
+            .iter()
+            .filter(|x| ident.ends_with(x.to_ident().as_str()))
+            .max_by_key(|x| x.to_ident().len());
+        let unit = match unit {
+            Some(unit) => *unit,
+            None => return Self::_0,
+        };
+        match &ident[..ident.len() - unit.to_ident().len()] {
+            "" => Self::Simple(unit),
+            "D" => Self::Dotted(unit),
+            "DD" => Self::DoubleDotted(unit),
+            "T" => Self::Triplet(unit),
+            "DT" => Self::DottedTriplet(unit),
+            prefix => {
+                let tuplet = prefix
+                    .strip_prefix('T')
+                    .and_then(|x| x.split_once('_'))
+                    .and_then(|(actual, normal)| actual.parse().ok().zip(normal.parse().ok()));
+                match tuplet {
+                    Some((actual, normal)) if actual > 0 && normal > 0 => {
+                        Self::Tuplet(unit, actual, normal)
+                    }
+                    _ => Self::_0,
+                }
+            }
         }
     }
 }
@@ -256,29 +339,92 @@ impl From<f32> for Units {
     }
 }
 
+impl From<Unit> for Rational {
+    fn from(v: Unit) -> Self {
+        Rational::new(1, v.to_denom() as i64)
+    }
+}
+
 impl From<Unit> for Units {
     fn from(v: Unit) -> Self {
-        match v {
-            Unit::Whole => 1.0,
-            Unit::Half => 1.0 / 2.0,
-            Unit::Quarter => 1.0 / 4.0,
-            Unit::Eighth => 1.0 / 8.0,
-            Unit::Sixteenth => 1.0 / 16.0,
-            Unit::ThirtySecondth => 1.0 / 32.0,
-        }
-        .into()
+        Rational::from(v).into()
+    }
+}
+
+impl From<Duration> for Rational {
+    fn from(v: Duration) -> Self {
+        let (unit, ratio) = match v {
+            Duration::Zero => return Rational::ZERO,
+            Duration::Simple(v) => (v, Rational::ONE),
+            Duration::Dotted(v) => (v, Rational::new(3, 2)),
+            Duration::Triplet(v) => (v, Rational::new(2, 3)),
+            Duration::DottedTriplet(v) => (v, Rational::new(4, 3)),
+            Duration::DoubleDotted(v) => (v, Rational::new(7, 4)),
+            Duration::Tuplet(v, actual, normal) => (v, Rational::new(normal as i64, actual as i64)),
+        };
+        Rational::from(unit) * ratio
     }
 }
 
 impl From<Duration> for Units {
     fn from(v: Duration) -> Self {
-        match v {
-            Duration::Zero => 0.0,
-            Duration::Simple(v) => Units::from(v).0,
-            Duration::Dotted(v) => Units::from(v).0 * 1.5,
-            Duration::Triplet(v) => Units::from(v).0 * 2.0 / 3.0,
-            Duration::DottedTriplet(v) => Units::from(v).0 * 4.0 / 3.0,
-        }
-        .into()
+        Rational::from(v).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn length(duration: Duration) -> Rational {
+        Rational::from(duration)
+    }
+
+    #[test]
+    fn test_simple_lengths() {
+        assert_eq!(length(Duration::Zero), Rational::ZERO);
+        assert_eq!(length(Duration::Simple(Unit::Quarter)), Rational::new(1, 4));
+        assert_eq!(length(Duration::Dotted(Unit::Quarter)), Rational::new(3, 8));
+        assert_eq!(length(Duration::Triplet(Unit::Eighth)), Rational::new(1, 12));
+        assert_eq!(
+            length(Duration::DottedTriplet(Unit::Eighth)),
+            Rational::new(1, 6)
+        );
+    }
+
+    #[test]
+    fn test_double_dotted_lengths() {
+        assert_eq!(
+            length(Duration::DoubleDotted(Unit::Quarter)),
+            Rational::new(7, 16)
+        );
+        assert_eq!(
+            length(Duration::DoubleDotted(Unit::Half)),
+            Rational::new(7, 8)
+        );
+    }
+
+    #[test]
+    fn test_tuplet_lengths() {
+        assert_eq!(
+            length(Duration::Tuplet(Unit::Eighth, 5, 4)),
+            Rational::new(1, 10)
+        );
+        assert_eq!(
+            length(Duration::Tuplet(Unit::Eighth, 3, 2)),
+            length(Duration::Triplet(Unit::Eighth))
+        );
+        let quintuplets: Rational = (0..5)
+            .map(|_| length(Duration::Tuplet(Unit::Sixteenth, 5, 4)))
+            .sum();
+        assert_eq!(quintuplets, Rational::new(1, 4));
+    }
+
+    #[test]
+    fn test_tuplet_with_zero_count() {
+        assert_eq!(length(Duration::Tuplet(Unit::Eighth, 0, 4)), Rational::ZERO);
+        assert_eq!(length(Duration::Tuplet(Unit::Eighth, 3, 0)), Rational::ZERO);
+        assert_eq!(length(Duration::Tuplet(Unit::Eighth, 0, 0)), Rational::ZERO);
+        assert_eq!(Units::from(Duration::Tuplet(Unit::Eighth, 0, 4)), Units(0.0));
     }
 }
@@ -39,6 +39,13 @@ pub enum Interval {
     Minor7th,
     Major7th,
     Perfect8ve,
+    Minor9th,
+    Major9th,
+    Augmented9th,
+    Perfect11th,
+    Augmented11th,
+    Minor13th,
+    Major13th,
 }
 impl Display for Interval {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -66,6 +73,13 @@ impl From<Interval> for IntervalQuality {
             Interval::Minor7th => Self::Minor,
             Interval::Major7th => Self::Major,
             Interval::Perfect8ve => Self::Perfect,
+            Interval::Minor9th => Self::Minor,
+            Interval::Major9th => Self::Major,
+            Interval::Augmented9th => Self::Augmented,
+            Interval::Perfect11th => Self::Perfect,
+            Interval::Augmented11th => Self::Augmented,
+            Interval::Minor13th => Self::Minor,
+            Interval::Major13th => Self::Major,
         }
     }
 }
@@ -90,6 +104,13 @@ impl From<Interval> for Syllable {
             Interval::Minor7th => Self::Te,
             Interval::Major7th => Self::Ti,
             Interval::Perfect8ve => Self::Do,
+            Interval::Minor9th => Self::Ra,
+            Interval::Major9th => Self::Re,
+            Interval::Augmented9th => Self::Ri,
+            Interval::Perfect11th => Self::Fa,
+            Interval::Augmented11th => Self::Fi,
+            Interval::Minor13th => Self::Le,
+            Interval::Major13th => Self::La,
         }
     }
 }
@@ -114,6 +135,13 @@ impl From<Interval> for Semitones {
             Interval::Minor7th => 10,
             Interval::Major7th => 11,
             Interval::Perfect8ve => 12,
+            Interval::Minor9th => 13,
+            Interval::Major9th => 14,
+            Interval::Augmented9th => 15,
+            Interval::Perfect11th => 17,
+            Interval::Augmented11th => 18,
+            Interval::Minor13th => 20,
+            Interval::Major13th => 21,
         }
         .into()
     }
@@ -125,6 +153,8 @@ impl From<(Syllable, Interval)> for Syllable {
     }
 }
 
+/// Always returns a simple interval, compound ones can't be told apart from
+/// semitones only.
 impl From<Semitones> for Interval {
     fn from(v: Semitones) -> Self {
         if v.0 == 0 {
@@ -160,7 +190,29 @@ impl From<(Syllable, Syllable)> for Interval {
 
 impl Interval {
     pub fn is_matched(&self, root: Syllable, syllable: Syllable) -> bool {
-        Semitones::from(Interval::from((root, syllable))) == Semitones::from(*self)
+        Semitones::from(Interval::from((root, syllable))).0 % 12 == Semitones::from(*self).0 % 12
+    }
+    /// Returns `true` for the intervals larger than an octave, e.g. [`Major9th`].
+    ///
+    /// [`Major9th`]: Interval::Major9th
+    pub fn is_compound(&self) -> bool {
+        *self > Interval::Perfect8ve
+    }
+    /// The interval within an octave, e.g. [`Major2nd`] for [`Major9th`].
+    ///
+    /// [`Major2nd`]: Interval::Major2nd
+    /// [`Major9th`]: Interval::Major9th
+    pub fn to_simple(&self) -> Self {
+        match self {
+            Interval::Minor9th => Interval::Minor2nd,
+            Interval::Major9th => Interval::Major2nd,
+            Interval::Augmented9th => Interval::Minor3nd,
+            Interval::Perfect11th => Interval::Perfect4th,
+            Interval::Augmented11th => Interval::Augmented4th,
+            Interval::Minor13th => Interval::Minor6th,
+            Interval::Major13th => Interval::Major6th,
+            _ => *self,
+        }
     }
     pub fn dot_count(&self) -> usize {
         match self {
@@ -181,6 +233,13 @@ impl Interval {
             Interval::Minor7th => 7,
             Interval::Major7th => 7,
             Interval::Perfect8ve => 1,
+            Interval::Minor9th => 2,
+            Interval::Major9th => 2,
+            Interval::Augmented9th => 2,
+            Interval::Perfect11th => 4,
+            Interval::Augmented11th => 4,
+            Interval::Minor13th => 6,
+            Interval::Major13th => 6,
         }
     }
     pub fn to_text(&self) -> String {
@@ -202,6 +261,13 @@ impl Interval {
             Interval::Minor7th => "7-",
             Interval::Major7th => "7",
             Interval::Perfect8ve => "8",
+            Interval::Minor9th => "9-",
+            Interval::Major9th => "9",
+            Interval::Augmented9th => "9+",
+            Interval::Perfect11th => "11",
+            Interval::Augmented11th => "11+",
+            Interval::Minor13th => "13-",
+            Interval::Major13th => "13",
         }
         .into()
     }
@@ -224,6 +290,13 @@ impl Interval {
             "7-" => Self::Minor7th,
             "7" => Self::Major7th,
             "8" => Self::Perfect8ve,
+            "9-" => Self::Minor9th,
+            "9" => Self::Major9th,
+            "9+" => Self::Augmented9th,
+            "11" => Self::Perfect11th,
+            "11+" => Self::Augmented11th,
+            "13-" => Self::Minor13th,
+            "13" => Self::Major13th,
             _ => Self::Tritone,
         }
     }
@@ -12,6 +12,8 @@ pub enum Intervals {
     Triad(Interval, Interval),
     Tetrad(Interval, Interval, Interval),
     Pentad(Interval, Interval, Interval, Interval),
+    Hexad(Interval, Interval, Interval, Interval, Interval),
+    Heptad(Interval, Interval, Interval, Interval, Interval, Interval),
 }
 impl Display for Intervals {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -26,6 +28,12 @@ impl Intervals {
             Self::Triad(n1, n2) => format!("{}, {}", n1, n2),
             Self::Tetrad(n1, n2, n3) => format!("{}, {}, {}", n1, n2, n3),
             Self::Pentad(n1, n2, n3, n4) => format!("{}, {}, {}, {}", n1, n2, n3, n4),
+            Self::Hexad(n1, n2, n3, n4, n5) => {
+                format!("{}, {}, {}, {}, {}", n1, n2, n3, n4, n5)
+            }
+            Self::Heptad(n1, n2, n3, n4, n5, n6) => {
+                format!("{}, {}, {}, {}, {}, {}", n1, n2, n3, n4, n5, n6)
+            }
         }
     }
 }
@@ -59,6 +67,18 @@ impl From<(Interval, Interval, Interval, Interval)> for Intervals {
     }
 }
 
+impl From<(Interval, Interval, Interval, Interval, Interval)> for Intervals {
+    fn from(v: (Interval, Interval, Interval, Interval, Interval)) -> Self {
+        Intervals::Hexad(v.0, v.1, v.2, v.3, v.4)
+    }
+}
+
+impl From<(Interval, Interval, Interval, Interval, Interval, Interval)> for Intervals {
+    fn from(v: (Interval, Interval, Interval, Interval, Interval, Interval)) -> Self {
+        Intervals::Heptad(v.0, v.1, v.2, v.3, v.4, v.5)
+    }
+}
+
 impl From<Vec<Interval>> for Intervals {
     fn from(v: Vec<Interval>) -> Self {
         match v.len() {
@@ -66,6 +86,8 @@ impl From<Vec<Interval>> for Intervals {
             2 => Self::from((v[0], v[1])),
             3 => Self::from((v[0], v[1], v[2])),
             4 => Self::from((v[0], v[1], v[2], v[3])),
+            5 => Self::from((v[0], v[1], v[2], v[3], v[4])),
+            6 => Self::from((v[0], v[1], v[2], v[3], v[4], v[5])),
             _ => {
                 println!("Unsupported Intervals: {}", v.len());
                 Self::Monad
@@ -93,6 +115,8 @@ impl Intervals {
             Self::Triad(n1, n2) => vec![n1, n2],
             Self::Tetrad(n1, n2, n3) => vec![n1, n2, n3],
             Self::Pentad(n1, n2, n3, n4) => vec![n1, n2, n3, n4],
+            Self::Hexad(n1, n2, n3, n4, n5) => vec![n1, n2, n3, n4, n5],
+            Self::Heptad(n1, n2, n3, n4, n5, n6) => vec![n1, n2, n3, n4, n5, n6],
         }
     }
 }
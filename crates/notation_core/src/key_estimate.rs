@@ -0,0 +1,147 @@
+use std::fmt::Display;
+
+use crate::prelude::{Key, Note, Scale, Semitones};
+
+/// Weights of the 12 pitch classes, starting from C, usually the note durations.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct PitchWeights(pub [f32; 12]);
+
+impl PitchWeights {
+    pub fn add_semitones(&mut self, semitones: Semitones, weight: f32) {
+        self.0[semitones.0.rem_euclid(12) as usize] += weight;
+    }
+    pub fn add_note(&mut self, note: &Note, weight: f32) {
+        self.add_semitones(Semitones::from(*note), weight);
+    }
+    pub fn merge(&mut self, other: &PitchWeights) {
+        for (weight, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *weight += other;
+        }
+    }
+    pub fn total(&self) -> f32 {
+        self.0.iter().sum()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.total() <= 0.0
+    }
+    /// Weight of the notes in the scale, from 0.0 to 1.0.
+    pub fn calc_scale_ratio(&self, key: &Key, scale: &Scale) -> f32 {
+        let total = self.total();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let do_semitones = scale.calc_do_semitones(key);
+        let in_scale: f32 = scale
+            .get_syllables()
+            .iter()
+            .map(|x| self.0[(do_semitones + Semitones::from(*x)).0.rem_euclid(12) as usize])
+            .sum();
+        in_scale / total
+    }
+}
+
+/// Estimated key and scale, the correlation is with the profile of the major
+/// or minor key, the confidence is the margin over the runner-up key.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct KeyEstimate {
+    pub key: Key,
+    pub scale: Scale,
+    pub correlation: f32,
+    pub confidence: f32,
+}
+impl Display for KeyEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "<KeyEstimate>({} {} R:{:.2} C:{:.2})",
+            self.key, self.scale, self.correlation, self.confidence
+        )
+    }
+}
+
+impl KeyEstimate {
+    // https://en.wikipedia.org/wiki/Krumhansl%E2%80%93Schmuckler_key-finding_algorithm
+    pub const MAJOR_PROFILE: [f32; 12] = [
+        6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+    ];
+    pub const MINOR_PROFILE: [f32; 12] = [
+        6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+    ];
+    /// Modes that can replace the major or minor key when they fit the notes better.
+    pub const MAJOR_MODES: [Scale; 3] = [Scale::Ionian, Scale::Mixolydian, Scale::Lydian];
+    pub const MINOR_MODES: [Scale; 3] = [Scale::Aeolian, Scale::Dorian, Scale::Phrygian];
+
+    fn calc_correlation(weights: &[f32; 12], profile: &[f32; 12], tonic: usize) -> f32 {
+        let mean_x = weights.iter().sum::<f32>() / 12.0;
+        let mean_y = profile.iter().sum::<f32>() / 12.0;
+        let (mut xy, mut xx, mut yy) = (0.0, 0.0, 0.0);
+        for (index, weight) in weights.iter().enumerate() {
+            let x = weight - mean_x;
+            let y = profile[(index + 12 - tonic) % 12] - mean_y;
+            xy += x * y;
+            xx += x * x;
+            yy += y * y;
+        }
+        if xx <= 0.0 || yy <= 0.0 {
+            0.0
+        } else {
+            xy / (xx * yy).sqrt()
+        }
+    }
+    /// Returns all the 24 major and minor keys, the best one first.
+    pub fn rank(weights: &PitchWeights) -> Vec<Self> {
+        let mut result = Vec::new();
+        for tonic in 0..12 {
+            for (scale, profile) in [
+                (Scale::Major, &Self::MAJOR_PROFILE),
+                (Scale::Minor, &Self::MINOR_PROFILE),
+            ] {
+                result.push(Self {
+                    key: scale.calc_key_from_semitones(Semitones(tonic as i8)),
+                    scale,
+                    correlation: Self::calc_correlation(&weights.0, profile, tonic),
+                    confidence: 0.0,
+                });
+            }
+        }
+        result.sort_by(|a, b| b.correlation.total_cmp(&a.correlation));
+        for index in 0..result.len() {
+            let next = result.get(index + 1).map(|x| x.correlation);
+            result[index].confidence = next.map(|x| result[index].correlation - x).unwrap_or(0.0);
+        }
+        result
+    }
+    /// The mode of the best key is the one holding most of the notes, major
+    /// and minor are kept on ties, e.g. Dorian for a minor tune with raised Fa.
+    pub fn estimate(weights: &PitchWeights) -> Option<Self> {
+        if weights.is_empty() {
+            return None;
+        }
+        let best = Self::rank(weights).into_iter().next()?;
+        let modes = if best.scale == Scale::Major {
+            Self::MAJOR_MODES
+        } else {
+            Self::MINOR_MODES
+        };
+        let mut scale = best.scale;
+        let mut ratio = weights.calc_scale_ratio(&best.key, &scale);
+        for mode in modes.iter().skip(1) {
+            let key = mode.calc_key_from_semitones(Semitones::from(best.key));
+            let mode_ratio = weights.calc_scale_ratio(&key, mode);
+            if mode_ratio > ratio + f32::EPSILON {
+                scale = *mode;
+                ratio = mode_ratio;
+            }
+        }
+        Some(Self {
+            key: scale.calc_key_from_semitones(Semitones::from(best.key)),
+            scale,
+            ..best
+        })
+    }
+    /// Same do means same syllables, e.g. A minor matches C major.
+    pub fn matches(&self, key: &Key, scale: &Scale) -> bool {
+        let do_semitones = self.scale.calc_do_semitones(&self.key).0;
+        (do_semitones - scale.calc_do_semitones(key).0).rem_euclid(12) == 0
+    }
+}
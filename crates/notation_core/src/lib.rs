@@ -6,14 +6,17 @@ pub mod entry;
 pub mod interval;
 pub mod intervals;
 pub mod key;
+pub mod key_estimate;
 pub mod meta_entry;
 pub mod note;
 pub mod octave;
 pub mod pitch;
+pub mod rational;
 pub mod semitones;
 pub mod scale;
 pub mod signature;
 pub mod syllable;
+pub mod temperament;
 pub mod tempo;
 pub mod tone;
 
@@ -35,6 +38,8 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::key::Key;
     #[doc(hidden)]
+    pub use crate::key_estimate::{KeyEstimate, PitchWeights};
+    #[doc(hidden)]
     pub use crate::meta_entry::MetaEntry;
     #[doc(hidden)]
     pub use crate::note::Note;
@@ -43,6 +48,8 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::pitch::{Pitch, PitchName, PitchSign};
     #[doc(hidden)]
+    pub use crate::rational::Rational;
+    #[doc(hidden)]
     pub use crate::semitones::Semitones;
     #[doc(hidden)]
     pub use crate::scale::Scale;
@@ -51,6 +58,8 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::syllable::Syllable;
     #[doc(hidden)]
+    pub use crate::temperament::{Intonation, KeyboardMapping, ScalaTuning, Temperament};
+    #[doc(hidden)]
     pub use crate::tempo::{Bpm, BpmRange, Tempo};
     #[doc(hidden)]
     pub use crate::tone::Tone;
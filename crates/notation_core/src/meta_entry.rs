@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{Entry, Key, Scale, Signature, Tempo};
+use crate::prelude::{Entry, Key, Scale, Semitones, Signature, Tempo};
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub enum MetaEntry {
@@ -34,6 +34,27 @@ impl MetaEntry {
     pub fn is_tempo(&self) -> bool {
         matches!(self, Self::Tempo(..))
     }
+
+    /// Returns `true` if the entry is [`Scale`].
+    pub fn is_scale(&self) -> bool {
+        matches!(self, Self::Scale(..))
+    }
+
+    /// Returns `true` if the entry is [`Key`].
+    pub fn is_key(&self) -> bool {
+        matches!(self, Self::Key(..))
+    }
+}
+
+impl MetaEntry {
+    pub fn transpose(&self, scale: &Scale, semitones: Semitones) -> Self {
+        match self {
+            Self::Key(x) => {
+                Self::Key(scale.calc_key_from_semitones(Semitones::from(*x) + semitones))
+            }
+            _ => *self,
+        }
+    }
 }
 
 impl From<Signature> for MetaEntry {
@@ -47,3 +68,15 @@ impl From<Tempo> for MetaEntry {
         Self::Tempo(v)
     }
 }
+
+impl From<Scale> for MetaEntry {
+    fn from(v: Scale) -> Self {
+        Self::Scale(v)
+    }
+}
+
+impl From<Key> for MetaEntry {
+    fn from(v: Key) -> Self {
+        Self::Key(v)
+    }
+}
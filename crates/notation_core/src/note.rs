@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{Octave, Pitch, Syllable, Semitones};
+use crate::prelude::{Key, Octave, Pitch, Scale, Semitones, Syllable};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
 pub struct Note {
@@ -21,6 +21,10 @@ impl Note {
     pub fn new(octave: Octave, pitch: Pitch, syllable: Syllable) -> Self {
         Self { octave, pitch, syllable }
     }
+    /// The key is the one after transposing, which the new note is spelled in.
+    pub fn transpose(&self, scale: &Scale, key: &Key, semitones: Semitones) -> Self {
+        scale.calc_note_from_semitones(key, Semitones::from(*self) + semitones)
+    }
 }
 
 impl From<(Octave, Pitch, Syllable)> for Note {
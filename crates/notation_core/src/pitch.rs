@@ -17,6 +17,23 @@ pub enum PitchName {
     B,
 }
 
+impl PitchName {
+    pub const ALL: [PitchName; 7] = [
+        PitchName::C,
+        PitchName::D,
+        PitchName::E,
+        PitchName::F,
+        PitchName::G,
+        PitchName::A,
+        PitchName::B,
+    ];
+    /// Returns the name of the given steps away, e.g. `A` for 5 steps from `C`.
+    pub fn shift(&self, steps: i8) -> Self {
+        let index = Self::ALL.iter().position(|x| x == self).unwrap_or(0) as i8;
+        Self::ALL[(index + steps).rem_euclid(7) as usize]
+    }
+}
+
 impl Display for PitchName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -214,6 +231,16 @@ impl Pitch {
     pub fn new(name: PitchName, sign: PitchSign) -> Self {
         Self { name, sign }
     }
+    /// Spells the semitones with the name, e.g. `Cb` for B with `C`,
+    /// returns None if more than a double sign is needed.
+    pub fn from_name_semitones(name: PitchName, semitones: Semitones) -> Option<Self> {
+        let offset = (semitones.0 - Semitones::from(name).0).rem_euclid(12);
+        let offset = if offset > 6 { offset - 12 } else { offset };
+        if offset.abs() > 2 {
+            return None;
+        }
+        Some(Self::new(name, Semitones(offset).into()))
+    }
 }
 
 impl From<PitchName> for Semitones {
@@ -340,4 +367,22 @@ impl Pitch {
             _ => Pitch::C,
         }
     }
+    /// Returns the pitch at the beginning of the text, e.g. `Bb` of `Bbmaj9`,
+    /// and the rest of it, a double sign followed by a digit is read as a
+    /// single one, e.g. `Abb9` is `Ab` with a flat 9th.
+    pub fn parse_prefix(text: &str) -> Option<(Self, &str)> {
+        if !text.starts_with(|x: char| ('A'..='G').contains(&x)) {
+            return None;
+        }
+        let rest = &text[1..];
+        let double = rest.starts_with("##") || rest.starts_with("bb");
+        let sign_len = if double && !rest[2..].starts_with(|x: char| x.is_ascii_digit()) {
+            2
+        } else if rest.starts_with(['#', 'b']) {
+            1
+        } else {
+            0
+        };
+        Some((Self::from_text(&text[..1 + sign_len]), &rest[sign_len..]))
+    }
 }
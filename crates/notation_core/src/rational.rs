@@ -0,0 +1,206 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::Units;
+
+/// Exact fraction of a whole note, used to sum up durations without the
+/// rounding drift of `Units`, which is only meant for rendering and playing.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub struct Rational {
+    numer: i64,
+    denom: i64,
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl Rational {
+    pub const ZERO: Self = Self { numer: 0, denom: 1 };
+    pub const ONE: Self = Self { numer: 1, denom: 1 };
+
+    fn gcd(a: i64, b: i64) -> i64 {
+        let (mut a, mut b) = (a.abs(), b.abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+    /// A zero denominator is treated as zero, so bad input can't panic.
+    pub fn new(numer: i64, denom: i64) -> Self {
+        if denom == 0 || numer == 0 {
+            return Self::ZERO;
+        }
+        let gcd = Self::gcd(numer, denom);
+        let sign = denom.signum();
+        Self {
+            numer: sign * numer / gcd,
+            denom: sign * denom / gcd,
+        }
+    }
+    pub fn numer(&self) -> i64 {
+        self.numer
+    }
+    pub fn denom(&self) -> i64 {
+        self.denom
+    }
+    pub fn is_zero(&self) -> bool {
+        self.numer == 0
+    }
+    pub fn to_f32(&self) -> f32 {
+        self.numer as f32 / self.denom as f32
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numer as i128 * other.denom as i128).cmp(&(other.numer as i128 * self.denom as i128))
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let gcd = Self::gcd(self.denom, rhs.denom);
+        let denom = self.denom / gcd * rhs.denom;
+        Self::new(
+            self.numer * (denom / self.denom) + rhs.numer * (denom / rhs.denom),
+            denom,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + Self::new(-rhs.numer, rhs.denom)
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let a = Self::gcd(self.numer, rhs.denom).max(1);
+        let b = Self::gcd(rhs.numer, self.denom).max(1);
+        Self::new(
+            (self.numer / a) * (rhs.numer / b),
+            (self.denom / b) * (rhs.denom / a),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let a = Self::gcd(self.numer, rhs.numer).max(1);
+        let b = Self::gcd(self.denom, rhs.denom).max(1);
+        Self::new(
+            (self.numer / a) * (rhs.denom / b),
+            (self.denom / b) * (rhs.numer / a),
+        )
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::iter::Sum for Rational {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(v: i64) -> Self {
+        Self::new(v, 1)
+    }
+}
+
+impl From<(i64, i64)> for Rational {
+    fn from((numer, denom): (i64, i64)) -> Self {
+        Self::new(numer, denom)
+    }
+}
+
+impl From<Rational> for Units {
+    fn from(v: Rational) -> Self {
+        Self(v.to_f32())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Rational::new(2, 4), Rational::new(1, 2));
+        assert_eq!(Rational::new(1, -2), Rational::new(-1, 2));
+        assert_eq!(Rational::new(3, 0), Rational::ZERO);
+        assert_eq!(Rational::new(0, 5), Rational::ZERO);
+        assert_eq!(Rational::new(6, 3), Rational::from(2));
+        assert_eq!(Rational::new(3, 6).to_string(), "1/2");
+        assert_eq!(Rational::new(4, 2).to_string(), "2");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let half = Rational::new(1, 2);
+        let third = Rational::new(1, 3);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(third - half, Rational::new(-1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!(half / Rational::ZERO, Rational::ZERO);
+        assert_eq!(third + third + third, Rational::ONE);
+        let mut sum = Rational::ZERO;
+        sum += half;
+        sum -= third;
+        assert_eq!(sum, Rational::new(1, 6));
+        let total: Rational = vec![third; 6].into_iter().sum();
+        assert_eq!(total, Rational::from(2));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        assert!(Rational::new(-1, 2) < Rational::ZERO);
+        assert!(Rational::new(2, 3) > Rational::new(3, 5));
+        assert_eq!(Units::from(Rational::new(3, 4)), Units(0.75));
+    }
+}
@@ -14,6 +14,20 @@ pub enum Scale {
     Mixolydian,
     Aeolian,
     Locrian,
+    HarmonicMinor,
+    MelodicMinor,
+    MajorPentatonic,
+    MinorPentatonic,
+    Blues,
+    WholeTone,
+    Diminished,
+    // https://en.wikipedia.org/wiki/Harmonic_minor_scale#Modes
+    LocrianNatural6,
+    IonianAugmented,
+    DorianSharp4,
+    PhrygianDominant,
+    LydianSharp2,
+    UltraLocrian,
 }
 impl Display for Scale {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -30,8 +44,13 @@ impl Scale {
     pub const Major: Scale = Scale::Ionian;
     #[allow(non_upper_case_globals)]
     pub const Minor: Scale = Scale::Aeolian;
-    pub const ALL: [ Scale; 7 ] = [
+    pub const ALL: [ Scale; 20 ] = [
         Scale::Ionian, Scale::Dorian, Scale::Phrygian, Scale::Lydian, Scale::Mixolydian, Scale::Aeolian, Scale::Locrian,
+        Scale::HarmonicMinor, Scale::MelodicMinor,
+        Scale::MajorPentatonic, Scale::MinorPentatonic, Scale::Blues,
+        Scale::WholeTone, Scale::Diminished,
+        Scale::LocrianNatural6, Scale::IonianAugmented, Scale::DorianSharp4,
+        Scale::PhrygianDominant, Scale::LydianSharp2, Scale::UltraLocrian,
     ];
 
     pub fn to_ident(&self) -> String {
@@ -48,6 +67,19 @@ impl Scale {
             "Mixolydian" => Self::Mixolydian,
             "Aeolian" => Self::Aeolian,
             "Locrian" => Self::Locrian,
+            "HarmonicMinor" => Self::HarmonicMinor,
+            "MelodicMinor" => Self::MelodicMinor,
+            "MajorPentatonic" => Self::MajorPentatonic,
+            "MinorPentatonic" => Self::MinorPentatonic,
+            "Blues" => Self::Blues,
+            "WholeTone" => Self::WholeTone,
+            "Diminished" => Self::Diminished,
+            "LocrianNatural6" => Self::LocrianNatural6,
+            "IonianAugmented" => Self::IonianAugmented,
+            "DorianSharp4" => Self::DorianSharp4,
+            "PhrygianDominant" => Self::PhrygianDominant,
+            "LydianSharp2" => Self::LydianSharp2,
+            "UltraLocrian" => Self::UltraLocrian,
             _ => Self::default(),
         }
     }
@@ -116,6 +148,118 @@ impl Scale {
                 Syllable::So,
                 Syllable::La,
             ],
+            Scale::HarmonicMinor => vec![
+                Syllable::La,
+                Syllable::Ti,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::Fa,
+                Syllable::Si,
+            ],
+            Scale::MelodicMinor => vec![
+                Syllable::La,
+                Syllable::Ti,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::Fi,
+                Syllable::Si,
+            ],
+            Scale::MajorPentatonic => vec![
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::So,
+                Syllable::La,
+            ],
+            Scale::MinorPentatonic => vec![
+                Syllable::La,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::So,
+            ],
+            Scale::Blues => vec![
+                Syllable::La,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Me,
+                Syllable::Mi,
+                Syllable::So,
+            ],
+            Scale::WholeTone => vec![
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::Fi,
+                Syllable::Si,
+                Syllable::Li,
+            ],
+            Scale::Diminished => vec![
+                Syllable::La,
+                Syllable::Ti,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Me,
+                Syllable::Fa,
+                Syllable::Fi,
+                Syllable::Si,
+            ],
+            Scale::LocrianNatural6 => vec![
+                Syllable::Ti,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::Fa,
+                Syllable::Si,
+                Syllable::La,
+            ],
+            Scale::IonianAugmented => vec![
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::Fa,
+                Syllable::Si,
+                Syllable::La,
+                Syllable::Ti,
+            ],
+            Scale::DorianSharp4 => vec![
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::Fa,
+                Syllable::Si,
+                Syllable::La,
+                Syllable::Ti,
+                Syllable::Do,
+            ],
+            Scale::PhrygianDominant => vec![
+                Syllable::Mi,
+                Syllable::Fa,
+                Syllable::Si,
+                Syllable::La,
+                Syllable::Ti,
+                Syllable::Do,
+                Syllable::Re,
+            ],
+            Scale::LydianSharp2 => vec![
+                Syllable::Fa,
+                Syllable::Si,
+                Syllable::La,
+                Syllable::Ti,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+            ],
+            Scale::UltraLocrian => vec![
+                Syllable::Si,
+                Syllable::La,
+                Syllable::Ti,
+                Syllable::Do,
+                Syllable::Re,
+                Syllable::Mi,
+                Syllable::Fa,
+            ],
         }
     }
     pub fn calc_key_index(&self, key: Key) -> usize {
@@ -140,7 +284,7 @@ impl Scale {
     //https://www.hooktheory.com/cheat-sheet
     pub fn get_keys(&self) -> [Key; 12] {
         match self {
-            Scale::Ionian => [
+            Scale::Ionian | Scale::MajorPentatonic | Scale::WholeTone | Scale::IonianAugmented => [
                 Key::C,
                 Key::G,
                 Key::D,
@@ -154,7 +298,7 @@ impl Scale {
                 Key::B_FLAT,
                 Key::F,
             ],
-            Scale::Dorian => [
+            Scale::Dorian | Scale::DorianSharp4 => [
                 Key::D,
                 Key::A,
                 Key::E,
@@ -168,7 +312,7 @@ impl Scale {
                 Key::C,
                 Key::G,
             ],
-            Scale::Phrygian => [
+            Scale::Phrygian | Scale::PhrygianDominant => [
                 Key::E,
                 Key::B,
                 Key::F_SHARP,
@@ -182,7 +326,7 @@ impl Scale {
                 Key::D,
                 Key::A,
             ],
-            Scale::Lydian => [
+            Scale::Lydian | Scale::LydianSharp2 => [
                 Key::F,
                 Key::C,
                 Key::G,
@@ -210,7 +354,7 @@ impl Scale {
                 Key::F,
                 Key::C,
             ],
-            Scale::Aeolian => [
+            Scale::Aeolian | Scale::HarmonicMinor | Scale::MelodicMinor | Scale::MinorPentatonic | Scale::Blues | Scale::Diminished => [
                 Key::A,
                 Key::E,
                 Key::B,
@@ -224,7 +368,7 @@ impl Scale {
                 Key::G,
                 Key::D,
             ],
-            Scale::Locrian => [
+            Scale::Locrian | Scale::LocrianNatural6 => [
                 Key::B,
                 Key::F_SHARP,
                 Key::C_SHARP,
@@ -238,6 +382,35 @@ impl Scale {
                 Key::A,
                 Key::E,
             ],
+            Scale::UltraLocrian => [
+                Key::G_SHARP,
+                Key::D_SHARP,
+                Key::A_SHARP,
+                Key::F,
+                Key::C,
+                Key::G,
+                Key::D,
+                Key::A,
+                Key::E,
+                Key::B,
+                Key::F_SHARP,
+                Key::C_SHARP,
+            ],
+        }
+    }
+    pub fn is_diatonic(&self) -> bool {
+        Self::ALL[0..7].contains(self)
+    }
+    /// The closest of the seven modes, for formats that don't know about other scales.
+    pub fn to_diatonic(&self) -> Scale {
+        match self {
+            Scale::HarmonicMinor | Scale::MelodicMinor | Scale::MinorPentatonic | Scale::Blues | Scale::Diminished => Scale::Aeolian,
+            Scale::MajorPentatonic | Scale::WholeTone | Scale::IonianAugmented => Scale::Ionian,
+            Scale::LocrianNatural6 | Scale::UltraLocrian => Scale::Locrian,
+            Scale::DorianSharp4 => Scale::Dorian,
+            Scale::PhrygianDominant => Scale::Phrygian,
+            Scale::LydianSharp2 => Scale::Lydian,
+            _ => *self,
         }
     }
 }
@@ -245,19 +418,34 @@ impl Scale {
 impl Scale {
     pub fn calc_do_offset(&self) -> i8 {
         match self {
-            Scale::Ionian => 0,
-            Scale::Dorian => -2,
-            Scale::Phrygian => -4,
-            Scale::Lydian => -5,
+            Scale::Ionian | Scale::MajorPentatonic | Scale::WholeTone | Scale::IonianAugmented => 0,
+            Scale::Dorian | Scale::DorianSharp4 => -2,
+            Scale::Phrygian | Scale::PhrygianDominant => -4,
+            Scale::Lydian | Scale::LydianSharp2 => -5,
             Scale::Mixolydian => 5,
-            Scale::Aeolian => 3,
-            Scale::Locrian => 1,
+            Scale::Aeolian | Scale::HarmonicMinor | Scale::MelodicMinor | Scale::MinorPentatonic | Scale::Blues | Scale::Diminished => 3,
+            Scale::Locrian | Scale::LocrianNatural6 => 1,
+            Scale::UltraLocrian => 4,
         }
     }
     pub fn calc_do_semitones(&self, key: &Key) -> Semitones {
         let semitones = Semitones::from(*key).0 + self.calc_do_offset();
         Semitones(semitones)
     }
+    // https://en.wikipedia.org/wiki/Key_signature
+    /// Returns number of sharps in key signature, flats are negative.
+    pub fn calc_key_signature(&self, key: &Key) -> i8 {
+        let index = self.calc_key_index(*key) as i8;
+        let sharps = if index <= 6 { index } else { index - 12 };
+        match key {
+            Key::Sharp(_) if sharps < 0 => sharps + 12,
+            Key::Flat(_) if sharps > 0 => sharps - 12,
+            _ => sharps,
+        }
+    }
+    pub fn calc_key_from_signature(&self, sharps: i8) -> Key {
+        self.get_keys()[sharps.rem_euclid(12) as usize]
+    }
     pub fn calc_root_syllable(&self) -> Syllable {
         Semitones(0 - self.calc_do_offset()).into()
     }
@@ -277,23 +465,80 @@ impl Scale {
         }
     }
     pub fn calc_syllable(&self, key: &Key, pitch: &Pitch) -> Syllable {
-        (Semitones::from(*pitch) - self.calc_do_semitones(key)).into()
+        let syllable: Syllable = (Semitones::from(*pitch) - self.calc_do_semitones(key)).into();
+        if !self.is_diatonic() {
+            // use the spelling of the scale for chromatic syllables, e.g. Me instead of Ri in blues
+            let semitones = Semitones::from(syllable);
+            if let Some(x) = self
+                .get_syllables()
+                .into_iter()
+                .find(|x| Semitones::from(*x) == semitones)
+            {
+                return x;
+            }
+        }
+        // keep the spelling of the pitch, e.g. Le for Ab and Si for G# in C major
+        let other = syllable.to_enharmonic();
+        if other != syllable && self.spell_syllable(key, &other).name == pitch.name {
+            return other;
+        }
+        syllable
     }
+    /// Spells the pitch of the syllable, the spellings of the scale are used for
+    /// the syllables in it, other ones are spelled with less signs, or follow the
+    /// key signature, e.g. `Gb` in Db major and `F#` in D major.
     pub fn calc_pitch(&self, key: &Key, syllable: &Syllable) -> Pitch {
-        let key_index = self.calc_key_index(key.clone());
-        if let Some(keys) = match syllable {
-            Syllable::Do => Some(Scale::Ionian.get_keys()),
-            Syllable::Re => Some(Scale::Dorian.get_keys()),
-            Syllable::Mi => Some(Scale::Phrygian.get_keys()),
-            Syllable::Fa => Some(Scale::Lydian.get_keys()),
-            Syllable::So => Some(Scale::Mixolydian.get_keys()),
-            Syllable::La => Some(Scale::Aeolian.get_keys()),
-            Syllable::Ti => Some(Scale::Locrian.get_keys()),
-            _ => None,
-        } {
-            keys[key_index].into()
-        } else {
-            (Semitones::from(*syllable) + self.calc_do_semitones(key)).into()
+        let syllable = self.calc_spelling_syllable(key, syllable);
+        self.spell_syllable(key, &syllable)
+    }
+    /// Returns the key with the usual spelling of the scale, e.g. `Bb` instead of `A#` for major.
+    pub fn calc_key_from_semitones(&self, semitones: Semitones) -> Key {
+        let keys = self.get_keys();
+        keys.iter()
+            .find(|x| (Semitones::from(**x).0 - semitones.0).rem_euclid(12) == 0)
+            .copied()
+            .unwrap_or_else(|| semitones.into())
+    }
+    pub fn calc_do_pitch(&self, key: &Key) -> Pitch {
+        let semitones = self.calc_do_semitones(key);
+        let name = Pitch::from(*key).name.shift(-self.calc_root_syllable().calc_degree());
+        Pitch::from_name_semitones(name, semitones).unwrap_or_else(|| semitones.into())
+    }
+    /// Spells the syllable with the name of its degree from do, e.g. `Cb` for Fa in Gb major.
+    pub fn spell_syllable(&self, key: &Key, syllable: &Syllable) -> Pitch {
+        let do_pitch = self.calc_do_pitch(key);
+        let semitones = Semitones::from(do_pitch) + Semitones::from(*syllable);
+        let name = do_pitch.name.shift(syllable.calc_degree());
+        Pitch::from_name_semitones(name, semitones).unwrap_or_else(|| semitones.into())
+    }
+    fn calc_spelling_syllable(&self, key: &Key, syllable: &Syllable) -> Syllable {
+        let semitones = Semitones::from(*syllable);
+        if let Some(x) = self.get_syllables().into_iter().find(|x| Semitones::from(*x) == semitones) {
+            return x;
+        }
+        let other = syllable.to_enharmonic();
+        if other == *syllable {
+            return other;
+        }
+        let (sharp, flat) = match syllable {
+            Syllable::Di | Syllable::Ri | Syllable::Fi | Syllable::Si | Syllable::Li => (*syllable, other),
+            _ => (other, *syllable),
+        };
+        let signs = |x: &Syllable| Semitones::from(self.spell_syllable(key, x).sign).0.abs();
+        match signs(&sharp).cmp(&signs(&flat)) {
+            std::cmp::Ordering::Less => sharp,
+            std::cmp::Ordering::Greater => flat,
+            std::cmp::Ordering::Equal => {
+                // keep the syllable as it is unless the key signature has signs
+                let sharps = self.calc_key_signature(key);
+                if sharps > 0 {
+                    sharp
+                } else if sharps < 0 || *syllable == flat {
+                    flat
+                } else {
+                    sharp
+                }
+            }
         }
     }
     pub fn calc_note_from_pitch(&self, key: &Key, pitch: &Pitch, octave: &Octave) -> Note {
@@ -303,10 +548,18 @@ impl Scale {
     pub fn calc_note_from_syllable(&self, key: &Key, syllable: &Syllable, octave: &Octave) -> Note {
         let semitones = Semitones::from(*octave) + self.calc_do_semitones(key) + Semitones::from(*syllable);
         let pitch = self.calc_pitch(key, syllable);
-        Note::new(Octave::from(semitones), pitch, *syllable)
+        Note::new(Octave::from(semitones - Semitones::from(pitch)), pitch, *syllable)
     }
     pub fn calc_note_from_semitones(&self, key: &Key, semitones: Semitones) -> Note {
-        let (pitch, octave) = semitones.as_pitch_octave();
+        let syllable: Syllable = (semitones - self.calc_do_semitones(key)).into();
+        // no spelling to keep here, Me and Te are more common than Ri and Li
+        let syllable = match syllable {
+            Syllable::Ri | Syllable::Li => syllable.to_enharmonic(),
+            _ => syllable,
+        };
+        let pitch = self.calc_pitch(key, &syllable);
+        // the octave is for the name, e.g. B#3 is the same as C4
+        let octave = Octave::from(semitones - Semitones::from(pitch));
         self.calc_note_from_pitch(key, &pitch, &octave)
     }
     pub fn calc_click_note(&self, key: &Key, octave: &Octave, syllable: &Syllable) -> Note {
@@ -317,3 +570,31 @@ impl Scale {
         Tone::Single(self.calc_click_note(key, octave, syllable))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn respell(scale: Scale, key: Key, text: &str) -> String {
+        let pitch = Pitch::from_text(text);
+        let syllable = scale.calc_syllable(&key, &pitch);
+        scale.calc_pitch(&key, &syllable).to_text()
+    }
+
+    #[test]
+    fn test_flat_spellings_in_natural_key() {
+        for text in ["Db", "Eb", "Gb", "Ab", "Bb", "C#", "D#", "F#", "G#", "A#"] {
+            assert_eq!(respell(Scale::Ionian, Key::C, text), text);
+        }
+    }
+
+    #[test]
+    fn test_spellings_follow_key_signature() {
+        assert_eq!(respell(Scale::Ionian, Key::D, "Ab"), "G#");
+        assert_eq!(respell(Scale::Ionian, Key::F, "G#"), "Ab");
+        let syllable: Syllable = Semitones(8).into();
+        assert_eq!(Scale::Ionian.calc_pitch(&Key::B_FLAT, &syllable).to_text(), "Gb");
+        let note = Scale::Ionian.calc_note_from_semitones(&Key::C, Semitones(63));
+        assert_eq!(note.pitch.to_text(), "Eb");
+    }
+}
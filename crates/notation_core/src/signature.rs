@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{Unit, Units};
+use crate::prelude::{Rational, Unit, Units};
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Serialize, Deserialize, Debug)]
 pub struct Beats(pub f32);
@@ -14,14 +14,7 @@ pub struct Signature {
 }
 impl Display for Signature {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}/{}", self.bar_beats, match self.beat_unit {
-            Unit::Whole => 1,
-            Unit::Half => 2,
-            Unit::Quarter => 4,
-            Unit::Eighth => 8,
-            Unit::Sixteenth => 16,
-            Unit::ThirtySecondth => 32,
-        })
+        write!(f, "{}/{}", self.bar_beats, self.beat_unit.to_denom())
     }
 }
 
@@ -59,9 +52,15 @@ impl From<f32> for Beats {
     }
 }
 
+impl From<Signature> for Rational {
+    fn from(v: Signature) -> Self {
+        Rational::from(v.beat_unit) * Rational::from(v.bar_beats as i64)
+    }
+}
+
 impl From<Signature> for Units {
     fn from(v: Signature) -> Self {
-        Self::from(Units::from(v.beat_unit).0 * (v.bar_beats as f32))
+        Rational::from(v).into()
     }
 }
 
@@ -122,6 +122,37 @@ impl Syllable {
     }
 }
 
+impl Syllable {
+    /// Returns the steps from Do in letter names, e.g. 2 for both Me and Mi.
+    pub fn calc_degree(&self) -> i8 {
+        match self {
+            Syllable::Do | Syllable::Di => 0,
+            Syllable::Ra | Syllable::Re | Syllable::Ri => 1,
+            Syllable::Me | Syllable::Mi => 2,
+            Syllable::Fa | Syllable::Fi => 3,
+            Syllable::Se | Syllable::So | Syllable::Si => 4,
+            Syllable::Le | Syllable::La | Syllable::Li => 5,
+            Syllable::Te | Syllable::Ti => 6,
+        }
+    }
+    /// Returns the other name of sharp and flat syllables, e.g. Me for Ri.
+    pub fn to_enharmonic(&self) -> Self {
+        match self {
+            Syllable::Di => Syllable::Ra,
+            Syllable::Ri => Syllable::Me,
+            Syllable::Fi => Syllable::Se,
+            Syllable::Si => Syllable::Le,
+            Syllable::Li => Syllable::Te,
+            Syllable::Ra => Syllable::Di,
+            Syllable::Me => Syllable::Ri,
+            Syllable::Se => Syllable::Fi,
+            Syllable::Le => Syllable::Si,
+            Syllable::Te => Syllable::Li,
+            _ => *self,
+        }
+    }
+}
+
 impl From<Syllable> for Semitones {
     fn from(v: Syllable) -> Self {
         match v {
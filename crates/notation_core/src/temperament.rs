@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::prelude::{Key, Note, Semitones};
+
+// http://www.huygens-fokker.org/scala/help.htm#mappings
+/// Keyboard mapping of a `.kbm` file, the keys are midi key numbers, an empty
+/// mapping means every key is the next degree of the scale.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct KeyboardMapping {
+    pub first_key: u8,
+    pub last_key: u8,
+    pub middle_key: u8,
+    pub reference_key: u8,
+    /// None to use the equal tempered frequency of the reference key.
+    pub reference_frequency: Option<f32>,
+    /// 0 to use the period of the scale.
+    pub octave_degree: usize,
+    pub degrees: Vec<Option<usize>>,
+}
+impl Default for KeyboardMapping {
+    fn default() -> Self {
+        Self {
+            first_key: 0,
+            last_key: 127,
+            middle_key: 60,
+            reference_key: 69,
+            reference_frequency: None,
+            octave_degree: 0,
+            degrees: vec![],
+        }
+    }
+}
+
+// http://www.huygens-fokker.org/scala/scl_format.html
+/// Scale of a `.scl` file, the pitches are in cents from the first degree,
+/// the last one is the period, usually 1200.0 for an octave.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ScalaTuning {
+    pub description: String,
+    pub cents: Vec<f32>,
+    pub mapping: KeyboardMapping,
+}
+impl ScalaTuning {
+    pub fn new(description: String, cents: Vec<f32>, mapping: KeyboardMapping) -> Self {
+        Self {
+            description,
+            cents,
+            mapping,
+        }
+    }
+    fn calc_degree_cents(&self, degree: i32) -> f32 {
+        let size = self.cents.len() as i32;
+        if size == 0 {
+            return degree as f32 * 100.0;
+        }
+        let period = self.cents[self.cents.len() - 1];
+        let index = degree.rem_euclid(size);
+        let offset = if index == 0 {
+            0.0
+        } else {
+            self.cents[index as usize - 1]
+        };
+        degree.div_euclid(size) as f32 * period + offset
+    }
+    fn calc_key_degree(&self, key_number: i32) -> Option<i32> {
+        let mapping = &self.mapping;
+        let offset = key_number - mapping.middle_key as i32;
+        if mapping.degrees.is_empty() {
+            return Some(offset);
+        }
+        let size = mapping.degrees.len() as i32;
+        let octave_degree = if mapping.octave_degree == 0 {
+            self.cents.len()
+        } else {
+            mapping.octave_degree
+        } as i32;
+        mapping.degrees[offset.rem_euclid(size) as usize]
+            .map(|x| x as i32 + offset.div_euclid(size) * octave_degree)
+    }
+    /// Returns None for the keys out of range or not mapped.
+    pub fn calc_frequency(&self, reference_frequency: f32, key_number: i32) -> Option<f32> {
+        let mapping = &self.mapping;
+        if key_number < mapping.first_key as i32 || key_number > mapping.last_key as i32 {
+            return None;
+        }
+        let degree = self.calc_key_degree(key_number)?;
+        let reference_degree = self
+            .calc_key_degree(mapping.reference_key as i32)
+            .unwrap_or(mapping.reference_key as i32 - mapping.middle_key as i32);
+        let cents = self.calc_degree_cents(degree) - self.calc_degree_cents(reference_degree);
+        Some(reference_frequency * 2.0_f32.powf(cents / 1200.0))
+    }
+}
+
+// https://en.wikipedia.org/wiki/Musical_temperament
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum Temperament {
+    #[default]
+    Equal,
+    /// Five-limit just intonation, relative to the tonic.
+    Just,
+    /// Pure fifths, relative to the tonic.
+    Pythagorean,
+    Scala(ScalaTuning),
+}
+impl Display for Temperament {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Temperament::Scala(x) => write!(f, "Scala({})", x.description),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+impl Temperament {
+    // https://en.wikipedia.org/wiki/Five-limit_tuning
+    pub const JUST_RATIOS: [f32; 12] = [
+        1.0,
+        16.0 / 15.0,
+        9.0 / 8.0,
+        6.0 / 5.0,
+        5.0 / 4.0,
+        4.0 / 3.0,
+        45.0 / 32.0,
+        3.0 / 2.0,
+        8.0 / 5.0,
+        5.0 / 3.0,
+        9.0 / 5.0,
+        15.0 / 8.0,
+    ];
+    // https://en.wikipedia.org/wiki/Pythagorean_tuning
+    pub const PYTHAGOREAN_RATIOS: [f32; 12] = [
+        1.0,
+        256.0 / 243.0,
+        9.0 / 8.0,
+        32.0 / 27.0,
+        81.0 / 64.0,
+        4.0 / 3.0,
+        729.0 / 512.0,
+        3.0 / 2.0,
+        128.0 / 81.0,
+        27.0 / 16.0,
+        16.0 / 9.0,
+        243.0 / 128.0,
+    ];
+    pub fn get_ratios(&self) -> Option<[f32; 12]> {
+        match self {
+            Temperament::Just => Some(Self::JUST_RATIOS),
+            Temperament::Pythagorean => Some(Self::PYTHAGOREAN_RATIOS),
+            _ => None,
+        }
+    }
+    pub fn is_equal(&self) -> bool {
+        *self == Temperament::Equal
+    }
+}
+
+/// The frequency of A4 and the temperament, to turn notes into Hz.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Intonation {
+    pub reference: f32,
+    pub temperament: Temperament,
+}
+impl Display for Intonation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "<Intonation>(A4={} {})",
+            self.reference, self.temperament
+        )
+    }
+}
+impl Default for Intonation {
+    fn default() -> Self {
+        Self {
+            reference: Self::DEFAULT_REFERENCE,
+            temperament: Temperament::Equal,
+        }
+    }
+}
+impl Intonation {
+    pub const DEFAULT_REFERENCE: f32 = 440.0;
+    /// A4 is 57 semitones from C0, which is midi key 69.
+    pub const REFERENCE_SEMITONES: Semitones = Semitones(57);
+    pub const MIDI_KEY_OFFSET: i32 = 12;
+
+    pub fn new(reference: f32, temperament: Temperament) -> Self {
+        Self {
+            reference,
+            temperament,
+        }
+    }
+    pub fn is_standard(&self) -> bool {
+        self.temperament.is_equal() && self.reference == Self::DEFAULT_REFERENCE
+    }
+    pub fn calc_equal_frequency(&self, semitones: Semitones) -> f32 {
+        let offset = semitones.0 as f32 - Self::REFERENCE_SEMITONES.0 as f32;
+        self.reference * 2.0_f32.powf(offset / 12.0)
+    }
+    /// The tonic is the key, which is kept at its equal tempered frequency,
+    /// unmapped keys of scala tunings fall back to equal temperament.
+    pub fn calc_semitones_frequency(&self, key: &Key, semitones: Semitones) -> f32 {
+        match &self.temperament {
+            Temperament::Equal => self.calc_equal_frequency(semitones),
+            Temperament::Just | Temperament::Pythagorean => {
+                let ratios = self
+                    .temperament
+                    .get_ratios()
+                    .unwrap_or(Temperament::JUST_RATIOS);
+                let tonic = Semitones::from(*key).0 as i32;
+                let offset = semitones.0 as i32 - tonic;
+                let tonic_frequency = self.calc_equal_frequency(Semitones(tonic as i8));
+                tonic_frequency
+                    * 2.0_f32.powi(offset.div_euclid(12))
+                    * ratios[offset.rem_euclid(12) as usize]
+            }
+            Temperament::Scala(tuning) => {
+                let reference_frequency = tuning.mapping.reference_frequency.unwrap_or_else(|| {
+                    let reference_key = tuning.mapping.reference_key as i32;
+                    self.calc_equal_frequency(Semitones(
+                        (reference_key - Self::MIDI_KEY_OFFSET) as i8,
+                    ))
+                });
+                let key_number = semitones.0 as i32 + Self::MIDI_KEY_OFFSET;
+                tuning
+                    .calc_frequency(reference_frequency, key_number)
+                    .unwrap_or_else(|| self.calc_equal_frequency(semitones))
+            }
+        }
+    }
+    pub fn calc_frequency(&self, key: &Key, note: &Note) -> f32 {
+        self.calc_semitones_frequency(key, Semitones::from(*note))
+    }
+    /// Returns the cents away from the equal tempered pitch at A4 = 440.
+    pub fn calc_cents(&self, key: &Key, semitones: Semitones) -> f32 {
+        let standard = Intonation::default().calc_equal_frequency(semitones);
+        1200.0 * (self.calc_semitones_frequency(key, semitones) / standard).log2()
+    }
+}
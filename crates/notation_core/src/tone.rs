@@ -3,6 +3,7 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 
 use super::note::Note;
+use crate::prelude::{Key, Scale, Semitones};
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub enum Tone {
@@ -13,6 +14,8 @@ pub enum Tone {
     Tetra(Note, Note, Note, Note),
     Penta(Note, Note, Note, Note, Note),
     Hexa(Note, Note, Note, Note, Note, Note),
+    Hepta(Note, Note, Note, Note, Note, Note, Note),
+    Octa(Note, Note, Note, Note, Note, Note, Note, Note),
 }
 
 impl Tone {
@@ -37,6 +40,20 @@ impl Display for Tone {
             Tone::Hexa(n1, n2, n3, n4, n5, n6) => {
                 write!(f, "<Tone>({}, {}, {}, {}, {}, {})", n1, n2, n3, n4, n5, n6)
             }
+            Tone::Hepta(n1, n2, n3, n4, n5, n6, n7) => {
+                write!(
+                    f,
+                    "<Tone>({}, {}, {}, {}, {}, {}, {})",
+                    n1, n2, n3, n4, n5, n6, n7
+                )
+            }
+            Tone::Octa(n1, n2, n3, n4, n5, n6, n7, n8) => {
+                write!(
+                    f,
+                    "<Tone>({}, {}, {}, {}, {}, {}, {}, {})",
+                    n1, n2, n3, n4, n5, n6, n7, n8
+                )
+            }
         }
     }
 }
@@ -83,6 +100,18 @@ impl From<(Note, Note, Note, Note, Note, Note)> for Tone {
     }
 }
 
+impl From<(Note, Note, Note, Note, Note, Note, Note)> for Tone {
+    fn from(v: (Note, Note, Note, Note, Note, Note, Note)) -> Self {
+        Tone::Hepta(v.0, v.1, v.2, v.3, v.4, v.5, v.6)
+    }
+}
+
+impl From<(Note, Note, Note, Note, Note, Note, Note, Note)> for Tone {
+    fn from(v: (Note, Note, Note, Note, Note, Note, Note, Note)) -> Self {
+        Tone::Octa(v.0, v.1, v.2, v.3, v.4, v.5, v.6, v.7)
+    }
+}
+
 impl From<Vec<Note>> for Tone {
     fn from(v: Vec<Note>) -> Self {
         match v.len() {
@@ -93,9 +122,11 @@ impl From<Vec<Note>> for Tone {
             4 => Self::from((v[0], v[1], v[2], v[3])),
             5 => Self::from((v[0], v[1], v[2], v[3], v[4])),
             6 => Self::from((v[0], v[1], v[2], v[3], v[4], v[5])),
+            7 => Self::from((v[0], v[1], v[2], v[3], v[4], v[5], v[6])),
+            8 => Self::from((v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7])),
             _ => {
-                println!("ToneNote lost: {}", v.len() - 6);
-                Self::from((v[0], v[1], v[2], v[3], v[4], v[5]))
+                println!("ToneNote lost: {}", v.len() - 8);
+                Self::from((v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]))
             }
         }
     }
@@ -122,8 +153,17 @@ impl Tone {
             Self::Tetra(n1, n2, n3, n4) => vec![n1, n2, n3, n4],
             Self::Penta(n1, n2, n3, n4, n5) => vec![n1, n2, n3, n4, n5],
             Self::Hexa(n1, n2, n3, n4, n5, n6) => vec![n1, n2, n3, n4, n5, n6],
+            Self::Hepta(n1, n2, n3, n4, n5, n6, n7) => vec![n1, n2, n3, n4, n5, n6, n7],
+            Self::Octa(n1, n2, n3, n4, n5, n6, n7, n8) => vec![n1, n2, n3, n4, n5, n6, n7, n8],
         }
     }
+    pub fn transpose(&self, scale: &Scale, key: &Key, semitones: Semitones) -> Self {
+        self.get_notes()
+            .iter()
+            .map(|x| x.transpose(scale, key, semitones))
+            .collect::<Vec<Note>>()
+            .into()
+    }
 }
 
 impl From<Tone> for Vec<Note> {
@@ -8,7 +8,7 @@ use notation_proto::prelude::{
 };
 use notation_proto::proto_entry::ProtoEntry;
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::parse::{Error, Parse, ParseStream};
 use syn::{Ident, LitInt, Token};
 
@@ -28,32 +28,27 @@ impl Default for FrettedContext {
     }
 }
 impl FrettedContext {
-    pub fn fretted_entry_quote(&self) -> TokenStream {
-        if self.string_num == 6 {
-            quote! { FrettedEntry6 }
-        } else if self.string_num == 4 {
-            quote! { FrettedEntry4 }
-        } else {
+    pub const MIN_STRING_NUM: usize = 4;
+    pub const MAX_STRING_NUM: usize = 8;
+    pub fn is_supported(string_num: usize) -> bool {
+        (Self::MIN_STRING_NUM..=Self::MAX_STRING_NUM).contains(&string_num)
+    }
+    /// `$string_num` is checked when parsed, so this can't panic from the DSL.
+    fn type_quote(&self, prefix: &str) -> TokenStream {
+        if !Self::is_supported(self.string_num) {
             panic!("Unsupported string_num: {}", self.string_num);
         }
+        let ident = format_ident!("{}{}", prefix, self.string_num);
+        quote! { #ident }
+    }
+    pub fn fretted_entry_quote(&self) -> TokenStream {
+        self.type_quote("FrettedEntry")
     }
     pub fn fretboard_quote(&self) -> TokenStream {
-        if self.string_num == 6 {
-            quote! { Fretboard6 }
-        } else if self.string_num == 4 {
-            quote! { Fretboard4 }
-        } else {
-            panic!("Unsupported string_num: {}", self.string_num);
-        }
+        self.type_quote("Fretboard")
     }
     pub fn hand_shape_quote(&self) -> TokenStream {
-        if self.string_num == 6 {
-            quote! { HandShape6 }
-        } else if self.string_num == 4 {
-            quote! { HandShape4 }
-        } else {
-            panic!("Unsupported string_num: {}", self.string_num);
-        }
+        self.type_quote("HandShape")
     }
 }
 
@@ -175,7 +170,11 @@ impl Parse for ContextDsl {
             }
             "string_num" => {
                 input.parse::<Token![=]>()?;
-                let string_num = input.parse::<LitInt>()?.base10_parse::<usize>()?;
+                let lit = input.parse::<LitInt>()?;
+                let string_num = lit.base10_parse::<usize>()?;
+                if !FrettedContext::is_supported(string_num) {
+                    throw!(Error::new(lit.span(), "Unsupported string_num"));
+                }
                 Self::StringNum(string_num)
             }
             _ => throw!(Error::new(input.span(), "Invalid Context")),
@@ -1,10 +1,10 @@
 use fehler::{throw, throws};
-use notation_proto::prelude::{Chord, CoreEntry, Interval};
+use notation_proto::prelude::{Chord, CoreEntry, Interval, Key, Scale};
 use notation_proto::proto_entry::ProtoEntry;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::parse::{Error, ParseStream};
-use syn::Token;
+use syn::{LitStr, Token};
 
 use crate::context::Context;
 
@@ -19,6 +19,7 @@ pub struct ChordDsl {
     pub intervals: Vec<IntervalDsl>,
     pub bass: Option<IntervalDsl>,
     pub duration_tweak: Option<DurationTweakDsl>,
+    pub symbol: Option<String>,
 }
 impl ChordDsl {
     pub fn empty(empty: EmptyDsl) -> Self {
@@ -28,6 +29,7 @@ impl ChordDsl {
             intervals: vec![],
             bass: None,
             duration_tweak: None,
+            symbol: None,
         }
     }
     pub fn chord(
@@ -42,8 +44,26 @@ impl ChordDsl {
             intervals,
             bass,
             duration_tweak,
+            symbol: None,
         }
     }
+    pub fn symbol(symbol: String, duration_tweak: Option<DurationTweakDsl>) -> Self {
+        Self {
+            empty: None,
+            root: None,
+            intervals: vec![],
+            bass: None,
+            duration_tweak,
+            symbol: Some(symbol),
+        }
+    }
+    /// Symbols are relative to the key, which is only set in context after
+    /// the meta is converted, so they are resolved when converting too.
+    fn symbol_chord(&self) -> Option<Chord> {
+        self.symbol
+            .as_ref()
+            .and_then(|x| Chord::from_text(&Context::scale(), &Context::key(), x))
+    }
 }
 
 impl ChordDsl {
@@ -55,6 +75,14 @@ impl ChordDsl {
         if EmptyDsl::peek(input) {
             return ChordDsl::empty(input.parse()?);
         }
+        if input.peek(LitStr) {
+            let symbol = input.parse::<LitStr>()?;
+            if Chord::from_text(&Scale::default(), &Key::default(), &symbol.value()).is_none() {
+                throw!(Error::new(symbol.span(), "Invalid Chord Symbol"));
+            }
+            let duration_tweak = DurationTweakDsl::try_parse(input);
+            return ChordDsl::symbol(symbol.value(), duration_tweak);
+        }
         let root = input.parse::<SyllableDsl>()?;
         input.parse::<Token![:]>()?;
         let mut intervals = Vec::new();
@@ -74,12 +102,31 @@ impl ChordDsl {
 
 impl ToTokens for ChordDsl {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let duration_tweak = &self.duration_tweak;
+        if let Some(chord) = self.symbol_chord() {
+            let root = SyllableDsl::new(chord.root);
+            let intervals = chord
+                .intervals
+                .get_intervals()
+                .into_iter()
+                .map(IntervalDsl::new)
+                .collect();
+            let bass = chord.bass.map(IntervalDsl::new);
+            return ChordDsl::chord(root, intervals, bass, None)
+                .to_tokens_with(tokens, duration_tweak);
+        }
+        self.to_tokens_with(tokens, duration_tweak);
+    }
+}
+
+impl ChordDsl {
+    fn to_tokens_with(&self, tokens: &mut TokenStream, duration_tweak: &Option<DurationTweakDsl>) {
         let ChordDsl {
             empty,
             root,
             intervals,
             bass,
-            duration_tweak,
+            ..
         } = self;
         let duration_quote = Context::duration_quote(duration_tweak);
         if empty.is_some() {
@@ -114,9 +161,12 @@ impl ChordDsl {
             intervals,
             bass,
             duration_tweak,
+            symbol: _,
         } = self;
         let duration = Context::tweaked_duration(duration_tweak);
-        if empty.is_some() {
+        if let Some(chord) = self.symbol_chord() {
+            ProtoEntry::from(CoreEntry::from((chord, duration)))
+        } else if empty.is_some() {
             empty.as_ref().unwrap().to_proto(duration)
         } else if let Some(root) = root {
             let intervals = intervals
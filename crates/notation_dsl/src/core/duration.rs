@@ -8,7 +8,7 @@ use syn::Token;
 #[derive(Debug)]
 pub struct DurationTweakDsl {
     pub half_num: i8,
-    pub dotted: bool,
+    pub dots: u8,
     pub triplet: bool,
 }
 
@@ -26,14 +26,18 @@ impl Parse for DurationTweakDsl {
             }
         }
         half_num = min(max(half_num, -4), 4);
-        let mut dotted = false;
+        let mut dots = 0;
         let mut triplet = false;
         if input.peek(Token![+]) {
             input.parse::<Token![+]>()?;
-            dotted = true;
+            dots = 1;
+            if input.peek(Token![+]) {
+                input.parse::<Token![+]>()?;
+                dots = 2;
+            }
         } else if input.peek(Token![=]) {
             input.parse::<Token![=]>()?;
-            dotted = true;
+            dots = 1;
             triplet = true;
         } else if input.peek(Token![-]) {
             input.parse::<Token![-]>()?;
@@ -41,7 +45,7 @@ impl Parse for DurationTweakDsl {
         }
         DurationTweakDsl {
             half_num,
-            dotted,
+            dots,
             triplet,
         }
     }
@@ -62,27 +66,35 @@ impl DurationTweakDsl {
             None
         }
     }
+    /// Tuplets can only be halfed or doubled, e.g. `T5_4_1_8` to `T5_4_1_16`.
     pub fn tweak(&self, base: &Duration) -> Duration {
-        if let Some(base_unit) = base.as_simple() {
-            let mut unit = base_unit.clone();
-            if self.half_num > 0 {
-                for _ in 0..self.half_num {
-                    unit = unit.halfed();
-                }
-            } else if self.half_num < 0 {
-                for _ in 0..(-self.half_num) {
-                    unit = unit.doubled();
-                }
+        let is_tuplet = matches!(base, Duration::Tuplet(..));
+        let base_unit = match base.unit() {
+            Some(unit) if base.is_simple() || (is_tuplet && self.dots == 0 && !self.triplet) => {
+                unit
             }
-            match (self.dotted, self.triplet) {
-                (false, false) => Duration::Simple(unit),
-                (true, false) => Duration::Dotted(unit),
-                (false, true) => Duration::Triplet(unit),
-                (true, true) => Duration::DottedTriplet(unit),
+            _ => {
+                println!("Can only tweak simple duration: {} - {:?}", base, self);
+                return *base;
             }
-        } else {
-            println!("Can only tweak simple duration: {} - {:?}", base, self);
-            return base.clone();
+        };
+        let mut unit = base_unit;
+        if self.half_num > 0 {
+            for _ in 0..self.half_num {
+                unit = unit.halfed();
+            }
+        } else if self.half_num < 0 {
+            for _ in 0..(-self.half_num) {
+                unit = unit.doubled();
+            }
+        }
+        match (base, self.dots, self.triplet) {
+            (Duration::Tuplet(_, actual, normal), _, _) => Duration::Tuplet(unit, *actual, *normal),
+            (_, 0, false) => Duration::Simple(unit),
+            (_, 1, false) => Duration::Dotted(unit),
+            (_, _, false) => Duration::DoubleDotted(unit),
+            (_, 0, true) => Duration::Triplet(unit),
+            (_, _, true) => Duration::DottedTriplet(unit),
         }
     }
 }
@@ -19,7 +19,7 @@ impl Parse for IntervalDsl {
     fn parse(input: ParseStream) -> Self {
         let interval = match input.parse::<LitInt>()?.base10_parse::<u8>()? {
             1 => Interval::Unison,
-            2 | 9 => {
+            2 => {
                 if input.peek(Token![-]) {
                     input.parse::<Token![-]>()?;
                     Interval::Minor2nd
@@ -35,7 +35,7 @@ impl Parse for IntervalDsl {
                     Interval::Major3nd
                 }
             }
-            4 | 11 => {
+            4 => {
                 if input.peek(Token![+]) {
                     input.parse::<Token![+]>()?;
                     Interval::Augmented4th
@@ -54,7 +54,7 @@ impl Parse for IntervalDsl {
                     Interval::Perfect5th
                 }
             }
-            6 | 13 => {
+            6 => {
                 if input.peek(Token![-]) {
                     input.parse::<Token![-]>()?;
                     Interval::Minor6th
@@ -74,6 +74,33 @@ impl Parse for IntervalDsl {
                 }
             }
             8 => Interval::Perfect8ve,
+            9 => {
+                if input.peek(Token![-]) {
+                    input.parse::<Token![-]>()?;
+                    Interval::Minor9th
+                } else if input.peek(Token![+]) {
+                    input.parse::<Token![+]>()?;
+                    Interval::Augmented9th
+                } else {
+                    Interval::Major9th
+                }
+            }
+            11 => {
+                if input.peek(Token![+]) {
+                    input.parse::<Token![+]>()?;
+                    Interval::Augmented11th
+                } else {
+                    Interval::Perfect11th
+                }
+            }
+            13 => {
+                if input.peek(Token![-]) {
+                    input.parse::<Token![-]>()?;
+                    Interval::Minor13th
+                } else {
+                    Interval::Major13th
+                }
+            }
             _ => throw!(Error::new(input.span(), "Invalid Interval")),
         };
         IntervalDsl::new(interval)
@@ -1,18 +1,22 @@
-use fehler::throws;
+use fehler::{throw, throws};
 use notation_proto::prelude::{
-    Fretboard4, Fretboard6, FrettedEntry4, FrettedEntry6, GuitarTuning,
-    GUITAR_FRET_NUM_ACOUSTIC, Pitch, Octave,
+    Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8, FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8,
+    FrettedInstrument, GuitarTuning, Pitch, Semitones, GUITAR_FRET_NUM_ACOUSTIC,
 };
 use notation_proto::proto_entry::ProtoEntry;
-use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
 use syn::parse::{Error, Parse, ParseStream};
-use syn::{Ident, LitInt, Token};
+use syn::{bracketed, Ident, LitInt, Token};
 
 use crate::context::Context;
 
 pub struct FretboardDsl {
     pub tuning: Option<Ident>,
+    /// Custom tuning, written from the lowest string, e.g. `[D2 A2 D3 G3 A3 D4]`,
+    /// stored from the first string, same as `Fretboard::string_notes`.
+    pub string_notes: Option<Vec<Semitones>>,
+    pub string_notes_span: Option<Span>,
     pub fret_num: Option<usize>,
     pub capo: Option<u8>,
 }
@@ -28,12 +32,26 @@ impl Parse for FretboardDsl {
     fn parse(input: ParseStream) -> Self {
         let mut fret_num = None;
         let mut tuning = None;
+        let mut string_notes = None;
+        let mut string_notes_span = None;
         let mut capo = None;
         loop {
             if input.peek(kw::tuning) {
                 input.parse::<kw::tuning>()?;
                 input.parse::<Token![:]>()?;
-                tuning = Some(input.parse::<Ident>()?);
+                if input.peek(syn::token::Bracket) {
+                    string_notes_span = Some(input.span());
+                    string_notes = Some(Self::parse_string_notes(input)?);
+                } else {
+                    let ident = input.parse::<Ident>()?;
+                    let name = ident.to_string();
+                    if GuitarTuning::from_ident(&name).is_none()
+                        && FrettedInstrument::from_ident(&name).is_none()
+                    {
+                        throw!(Error::new(ident.span(), "Unknown tuning"));
+                    }
+                    tuning = Some(ident);
+                }
             } else if input.peek(kw::fret_num) {
                 input.parse::<kw::fret_num>()?;
                 input.parse::<Token![:]>()?;
@@ -48,75 +66,236 @@ impl Parse for FretboardDsl {
         }
         FretboardDsl {
             tuning,
+            string_notes,
+            string_notes_span,
             fret_num,
             capo,
         }
     }
 }
 
+impl FretboardDsl {
+    /// Notes are named in scientific pitch notation, e.g. `E2`, `Bb2`, `F #3`,
+    /// the space after sharp pitch names is needed in Rust 2021.
+    #[throws(Error)]
+    fn parse_string_notes(input: ParseStream) -> Vec<Semitones> {
+        let content;
+        bracketed!(content in input);
+        let mut notes = Vec::new();
+        while !content.is_empty() {
+            let ident = content.parse::<Ident>()?;
+            let text = ident.to_string();
+            let (pitch, rest) = match Pitch::parse_prefix(&text) {
+                Some(x) => x,
+                None => throw!(Error::new(ident.span(), "Invalid Pitch Name")),
+            };
+            let mut sharps = 0;
+            while content.peek(Token![#]) {
+                content.parse::<Token![#]>()?;
+                sharps += 1;
+            }
+            let octave = if rest.is_empty() {
+                content.parse::<LitInt>()?.base10_parse::<i8>()?
+            } else {
+                match rest.parse::<i8>() {
+                    Ok(x) => x,
+                    Err(_) => throw!(Error::new(ident.span(), "Invalid Octave")),
+                }
+            };
+            if !(0..=8).contains(&octave) {
+                throw!(Error::new(ident.span(), "Invalid Octave"));
+            }
+            notes.push(Semitones::from(pitch) + Semitones(octave * 12 + sharps));
+        }
+        notes.reverse();
+        notes
+    }
+    #[throws(Error)]
+    fn check_string_notes(&self, string_num: usize) -> Option<Vec<Semitones>> {
+        let notes = match self.string_notes.clone() {
+            Some(notes) => notes,
+            None => return None,
+        };
+        if notes.len() != string_num {
+            throw!(Error::new(
+                self.string_notes_span.unwrap_or_else(Span::call_site),
+                format!("Tuning has {} strings, not {}", notes.len(), string_num)
+            ));
+        }
+        Some(notes)
+    }
+    /// 6-string tunings are `GuitarTuning`, other string numbers and the
+    /// 12-string guitar are named by `FrettedInstrument`.
+    #[throws(Error)]
+    fn get_instrument(&self, string_num: usize) -> Option<FrettedInstrument> {
+        let tuning = match self.tuning.as_ref() {
+            Some(tuning) => tuning,
+            None if string_num == 6 => return None,
+            None => return FrettedInstrument::default_of_string_num(string_num),
+        };
+        match FrettedInstrument::from_ident(tuning.to_string().as_str()) {
+            Some(instrument) if instrument.string_num() == string_num => Some(instrument),
+            Some(instrument) => throw!(Error::new(
+                tuning.span(),
+                format!("{} has {} strings, not {}", tuning, instrument.string_num(), string_num)
+            )),
+            None if string_num == 6 => None,
+            None => throw!(Error::new(
+                tuning.span(),
+                format!("{} has 6 strings, not {}", tuning, string_num)
+            )),
+        }
+    }
+    #[throws(Error)]
+    fn check(&self, string_num: usize) -> (Option<Vec<Semitones>>, Option<FrettedInstrument>) {
+        (self.check_string_notes(string_num)?, self.get_instrument(string_num)?)
+    }
+    fn get_fret_num(&self, instrument: Option<FrettedInstrument>) -> usize {
+        self.fret_num.unwrap_or(match instrument {
+            Some(instrument) => instrument.fret_num(),
+            None => GUITAR_FRET_NUM_ACOUSTIC,
+        })
+    }
+}
+
 impl ToTokens for FretboardDsl {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let FretboardDsl {
-            tuning,
-            fret_num,
-            capo,
-        } = self;
         let string_num = Context::fretted().string_num;
-        let fret_num = fret_num.unwrap_or(match string_num {
-            6 => GUITAR_FRET_NUM_ACOUSTIC,
-            _ => GUITAR_FRET_NUM_ACOUSTIC,
-        });
-        let capo = capo.unwrap_or(0);
-        let tuning_quote = match tuning {
-            Some(tuning) => {
-                let tuning_quote = tuning.to_string();
-                match string_num {
-                    _ => quote! { GuitarTuning::from_ident(#tuning_quote)},
-                }
+        let (string_notes, instrument) = match self.check(string_num) {
+            Ok(x) => x,
+            Err(err) => {
+                tokens.extend(err.to_compile_error());
+                return;
             }
-            None => match string_num {
-                _ => quote! { GuitarTuning::Standard },
-            },
         };
+        let fret_num = self.get_fret_num(instrument);
+        let capo = self.capo.unwrap_or(0);
         let fretted_entry_quote = Context::fretted().fretted_entry_quote();
         let fretboard_quote = Context::fretted().fretboard_quote();
+        let fretboard = match (string_notes, instrument) {
+            (Some(string_notes), _) => {
+                let notes = string_notes.into_iter().map(|x| x.0);
+                quote! {
+                    #fretboard_quote::new(#fret_num, [#(Semitones(#notes)),*], #capo)
+                }
+            }
+            (None, Some(instrument)) => {
+                let notes = instrument.string_notes().into_iter().map(|x| x.0);
+                let courses = instrument.courses().into_iter().map(|x| match x {
+                    Some(x) => {
+                        let x = x.0;
+                        quote! { Some(Semitones(#x)) }
+                    }
+                    None => quote! { None },
+                });
+                quote! {
+                    #fretboard_quote::new(#fret_num, [#(Semitones(#notes)),*], #capo)
+                        .with_courses([#(#courses),*])
+                }
+            }
+            (None, None) => {
+                let tuning = self
+                    .tuning
+                    .as_ref()
+                    .and_then(|x| GuitarTuning::from_ident(x.to_string().as_str()))
+                    .unwrap_or_default();
+                let tuning_quote = format_ident!("{}", tuning.to_ident());
+                quote! {
+                    #fretboard_quote::new(#fret_num, GuitarTuning::#tuning_quote.into(), #capo)
+                }
+            }
+        };
         tokens.extend(quote! {
-            ProtoEntry::from(#fretted_entry_quote::from(
-                #fretboard_quote::new(#fret_num, #tuning_quote.into(), #capo)
-            ))
+            ProtoEntry::from(#fretted_entry_quote::from(#fretboard))
         });
     }
 }
 
 impl FretboardDsl {
+    #[throws(Error)]
     pub fn to_proto(&self) -> ProtoEntry {
-        let FretboardDsl {
-            tuning,
-            fret_num,
-            capo,
-        } = self;
         let string_num = Context::fretted().string_num;
-        let fret_num = fret_num.unwrap_or(match string_num {
-            6 => GUITAR_FRET_NUM_ACOUSTIC,
-            _ => GUITAR_FRET_NUM_ACOUSTIC,
-        });
-        let capo = capo.unwrap_or(0);
-        match string_num {
-            4 => {
-                let tuning = [(Pitch::E, Octave::P2).into(); 4]; //todo;
-                ProtoEntry::from(FrettedEntry4::from(Fretboard4::new(fret_num, tuning, capo)))
-            }
-            _ => {
-                let tuning = match tuning {
-                    Some(ident) => GuitarTuning::from_ident(ident.to_string().as_str()),
-                    None => GuitarTuning::Standard,
-                };
-                ProtoEntry::from(FrettedEntry6::from(Fretboard6::new(
+        let (string_notes, instrument) = self.check(string_num)?;
+        let fret_num = self.get_fret_num(instrument);
+        let capo = self.capo.unwrap_or(0);
+        if let Some(string_notes) = string_notes {
+            return Self::custom_to_proto(string_notes, fret_num, capo);
+        }
+        let instrument = match instrument {
+            Some(instrument) => instrument,
+            None => {
+                let tuning = self
+                    .tuning
+                    .as_ref()
+                    .and_then(|x| GuitarTuning::from_ident(x.to_string().as_str()))
+                    .unwrap_or_default();
+                return ProtoEntry::from(FrettedEntry6::from(Fretboard6::new(
                     fret_num,
                     tuning.into(),
                     capo,
-                )))
+                )));
             }
-        }
+        };
+        let entry = match string_num {
+            4 => instrument.new_fretboard4().map(|x| {
+                ProtoEntry::from(FrettedEntry4::from(Fretboard4 {
+                    total_fret_num: fret_num,
+                    capo,
+                    ..x
+                }))
+            }),
+            5 => instrument.new_fretboard5().map(|x| {
+                ProtoEntry::from(FrettedEntry5::from(Fretboard5 {
+                    total_fret_num: fret_num,
+                    capo,
+                    ..x
+                }))
+            }),
+            6 => instrument.new_fretboard6().map(|x| {
+                ProtoEntry::from(FrettedEntry6::from(Fretboard6 {
+                    total_fret_num: fret_num,
+                    capo,
+                    ..x
+                }))
+            }),
+            7 => instrument.new_fretboard7().map(|x| {
+                ProtoEntry::from(FrettedEntry7::from(Fretboard7 {
+                    total_fret_num: fret_num,
+                    capo,
+                    ..x
+                }))
+            }),
+            8 => instrument.new_fretboard8().map(|x| {
+                ProtoEntry::from(FrettedEntry8::from(Fretboard8 {
+                    total_fret_num: fret_num,
+                    capo,
+                    ..x
+                }))
+            }),
+            _ => None,
+        };
+        entry.unwrap_or_else(|| panic!("Unsupported string_num: {}", string_num))
+    }
+    fn custom_to_proto(string_notes: Vec<Semitones>, fret_num: usize, capo: u8) -> ProtoEntry {
+        let string_num = string_notes.len();
+        let entry = match string_num {
+            4 => string_notes.try_into().ok().map(|x| {
+                ProtoEntry::from(FrettedEntry4::from(Fretboard4::new(fret_num, x, capo)))
+            }),
+            5 => string_notes.try_into().ok().map(|x| {
+                ProtoEntry::from(FrettedEntry5::from(Fretboard5::new(fret_num, x, capo)))
+            }),
+            6 => string_notes.try_into().ok().map(|x| {
+                ProtoEntry::from(FrettedEntry6::from(Fretboard6::new(fret_num, x, capo)))
+            }),
+            7 => string_notes.try_into().ok().map(|x| {
+                ProtoEntry::from(FrettedEntry7::from(Fretboard7::new(fret_num, x, capo)))
+            }),
+            8 => string_notes.try_into().ok().map(|x| {
+                ProtoEntry::from(FrettedEntry8::from(Fretboard8::new(fret_num, x, capo)))
+            }),
+            _ => None,
+        };
+        entry.unwrap_or_else(|| panic!("Unsupported string_num: {}", string_num))
     }
 }
@@ -1,5 +1,7 @@
 use fehler::throws;
-use notation_proto::prelude::{CoreEntry, FrettedEntry4, FrettedEntry6, Pick};
+use notation_proto::prelude::{
+    CoreEntry, FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8, Pick,
+};
 use notation_proto::proto_entry::ProtoEntry;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
@@ -50,7 +52,6 @@ impl ToTokens for PickDsl {
                 ProtoEntry::from(CoreEntry::from(#duration_quote))
             });
         } else {
-            let _string_num = Context::fretted().string_num;
             let notes_quote: Vec<_> = notes.iter().map(|x| quote! { #x }).collect();
             let fretted_entry_quote = Context::fretted().fretted_entry_quote();
             tokens.extend(quote! {
@@ -74,11 +75,14 @@ impl PickDsl {
         if notes.len() == 0 {
             ProtoEntry::from(CoreEntry::from(duration))
         } else {
-            let _string_num = Context::fretted().string_num;
             let notes: Vec<_> = notes.iter().map(|x| x.to_proto()).collect();
+            let pick = Pick::from(notes);
             match Context::fretted().string_num {
-                4 => ProtoEntry::from(FrettedEntry4::from((Pick::from(notes), duration))),
-                _ => ProtoEntry::from(FrettedEntry6::from((Pick::from(notes), duration))),
+                4 => ProtoEntry::from(FrettedEntry4::from((pick, duration))),
+                5 => ProtoEntry::from(FrettedEntry5::from((pick, duration))),
+                7 => ProtoEntry::from(FrettedEntry7::from((pick, duration))),
+                8 => ProtoEntry::from(FrettedEntry8::from((pick, duration))),
+                _ => ProtoEntry::from(FrettedEntry6::from((pick, duration))),
             }
         }
     }
@@ -1,13 +1,14 @@
-use fehler::throws;
-use notation_proto::prelude::PickNote;
+use fehler::{throw, throws};
+use notation_proto::prelude::{Harmonic, PickAttack, PickNote, PickTechniques};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::parse::{Error, Parse, ParseStream};
-use syn::{LitInt, Token};
+use syn::{Ident, LitInt, Token};
 
 pub struct PickNoteDsl {
     pub string: u8,
     pub fret: Option<u8>,
+    pub techniques: PickTechniques,
 }
 
 impl Parse for PickNoteDsl {
@@ -20,22 +21,94 @@ impl Parse for PickNoteDsl {
         } else {
             None
         };
-        PickNoteDsl { string, fret }
+        let mut techniques = PickTechniques::default();
+        while input.peek(Token![:]) && !input.peek(Token![::]) {
+            input.parse::<Token![:]>()?;
+            let ident = input.parse::<Ident>()?;
+            if !techniques.add_text(&ident.to_string()) {
+                throw!(Error::new(ident.span(), "Unknown technique"));
+            }
+        }
+        PickNoteDsl {
+            string,
+            fret,
+            techniques,
+        }
+    }
+}
+
+impl PickNoteDsl {
+    fn techniques_quote(techniques: &PickTechniques) -> TokenStream {
+        let PickTechniques {
+            bend,
+            slide_in,
+            slide_out,
+            attack,
+            vibrato,
+            harmonic,
+            palm_mute,
+            dead_note,
+            let_ring,
+        } = techniques;
+        let bend_quote = match bend {
+            Some(bend) => {
+                let semitones = bend.semitones;
+                let release = bend.release;
+                quote! { Some(Bend::new(#semitones, #release)) }
+            }
+            None => quote! { None },
+        };
+        let attack_quote = match attack {
+            Some(PickAttack::HammerOn) => quote! { Some(PickAttack::HammerOn) },
+            Some(PickAttack::PullOff) => quote! { Some(PickAttack::PullOff) },
+            Some(PickAttack::Tap) => quote! { Some(PickAttack::Tap) },
+            None => quote! { None },
+        };
+        let harmonic_quote = match harmonic {
+            Some(Harmonic::Natural) => quote! { Some(Harmonic::Natural) },
+            Some(Harmonic::Artificial(node)) => quote! { Some(Harmonic::Artificial(#node)) },
+            None => quote! { None },
+        };
+        quote! {
+            PickTechniques {
+                bend: #bend_quote,
+                slide_in: #slide_in,
+                slide_out: #slide_out,
+                attack: #attack_quote,
+                vibrato: #vibrato,
+                harmonic: #harmonic_quote,
+                palm_mute: #palm_mute,
+                dead_note: #dead_note,
+                let_ring: #let_ring,
+            }
+        }
     }
 }
 
 impl ToTokens for PickNoteDsl {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let PickNoteDsl { string, fret } = self;
+        let PickNoteDsl {
+            string,
+            fret,
+            techniques,
+        } = self;
         let fret_quote = fret.map(|f| quote! { Some(#f) }).unwrap_or(quote! {None});
-        tokens.extend(quote! {
-            PickNote::new(#string, #fret_quote, None, None, None)
-        });
+        if techniques.is_empty() {
+            tokens.extend(quote! {
+                PickNote::new(#string, #fret_quote, None, None, None)
+            });
+        } else {
+            let techniques_quote = Self::techniques_quote(techniques);
+            tokens.extend(quote! {
+                PickNote::new(#string, #fret_quote, None, None, None)
+                    .with_techniques(#techniques_quote)
+            });
+        }
     }
 }
 
 impl PickNoteDsl {
     pub fn to_proto(&self) -> PickNote {
-        PickNote::new(self.string, self.fret, None, None, None)
+        PickNote::new(self.string, self.fret, None, None, None).with_techniques(self.techniques)
     }
 }
@@ -1,5 +1,8 @@
 use fehler::throws;
-use notation_proto::prelude::{FrettedEntry4, FrettedEntry6, HandShape4, HandShape6};
+use notation_proto::prelude::{
+    FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8, HandShape4,
+    HandShape5, HandShape6, HandShape7, HandShape8,
+};
 use notation_proto::proto_entry::ProtoEntry;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
@@ -82,37 +85,37 @@ impl ToTokens for ShapeDsl {
 }
 
 impl ShapeDsl {
+    fn get_frets<const N: usize>(&self) -> [Option<u8>; N] {
+        let mut frets = [None; N];
+        for (index, fret) in self.frets.iter().take(N).enumerate() {
+            frets[index] = *fret;
+        }
+        frets
+    }
     pub fn to_proto(&self) -> ProtoEntry {
-        let ShapeDsl {
-            barre,
-            frets: _,
-            duration_tweak,
-        } = self;
-        let barre = barre.unwrap_or(0);
-        let duration = Context::tweaked_duration(duration_tweak);
+        let barre = self.barre.unwrap_or(0);
+        let duration = Context::tweaked_duration(&self.duration_tweak);
         match Context::fretted().string_num {
-            4 => {
-                let mut frets = [None; 4];
-                for i in 0..4 {
-                    frets[i] = self.frets.get(i).unwrap().clone();
-                }
-                let fingers = [None; 4];
-                ProtoEntry::from(FrettedEntry4::from((
-                    HandShape4::new_barre(barre, frets, fingers),
-                    duration,
-                )))
-            }
-            _ => {
-                let mut frets = [None; 6];
-                for i in 0..6 {
-                    frets[i] = self.frets.get(i).unwrap().clone();
-                }
-                let fingers = [None; 6];
-                ProtoEntry::from(FrettedEntry6::from((
-                    HandShape6::new_barre(barre, frets, fingers),
-                    duration,
-                )))
-            }
+            4 => ProtoEntry::from(FrettedEntry4::from((
+                HandShape4::new_barre(barre, self.get_frets(), [None; 4]),
+                duration,
+            ))),
+            5 => ProtoEntry::from(FrettedEntry5::from((
+                HandShape5::new_barre(barre, self.get_frets(), [None; 5]),
+                duration,
+            ))),
+            7 => ProtoEntry::from(FrettedEntry7::from((
+                HandShape7::new_barre(barre, self.get_frets(), [None; 7]),
+                duration,
+            ))),
+            8 => ProtoEntry::from(FrettedEntry8::from((
+                HandShape8::new_barre(barre, self.get_frets(), [None; 8]),
+                duration,
+            ))),
+            _ => ProtoEntry::from(FrettedEntry6::from((
+                HandShape6::new_barre(barre, self.get_frets(), [None; 6]),
+                duration,
+            ))),
         }
     }
 }
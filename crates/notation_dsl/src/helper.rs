@@ -15,7 +15,7 @@ pub fn parse_get_tab(content: &str) -> Result<Tab, Error> {
     let get_tab: GetTabDsl = syn::parse2(tokens)?;
     let tab = get_tab.tab;
     //println!("Tab: T:{}, S:{}", tab.tracks.len(), tab.sections.len());
-    Ok(tab.to_proto())
+    Ok(tab.to_proto()?)
 }
 pub fn parse_get_tab_file(path: &str) -> Result<Tab, Error> {
     let mut file = File::open(path)?;
@@ -28,7 +28,7 @@ pub fn parse_get_tab_file(path: &str) -> Result<Tab, Error> {
 pub fn parse_tab(content: &str) -> Result<Tab, Error> {
     let tab = syn::parse_str::<TabDsl>(content)?;
     //println!("Tab: T:{}, S:{}", tab.tracks.len(), tab.sections.len());
-    Ok(tab.to_proto())
+    Ok(tab.to_proto()?)
 }
 pub fn parse_tab_file(path: &str) -> Result<Tab, Error> {
     let mut file = File::open(path)?;
@@ -14,6 +14,7 @@ use crate::fretted::fretboard::FretboardDsl;
 use crate::fretted::pick::PickDsl;
 use crate::fretted::shape::ShapeDsl;
 use crate::proto::mark::MarkDsl;
+use crate::proto::meta::MetaEntryDsl;
 
 pub struct MultibleDsl<T> {
     pub items: Vec<T>,
@@ -22,6 +23,7 @@ pub struct MultibleDsl<T> {
 pub enum EntryDsl {
     Context(ContextDsl),
     Mark(MarkDsl),
+    Meta(MetaEntryDsl),
     Tone(MultibleDsl<ToneDsl>),
     Chord(MultibleDsl<ChordDsl>),
     Word(MultibleDsl<WordDsl>),
@@ -39,6 +41,7 @@ impl EntryDsl {
             Self::Mark(input.parse()?)
         } else {
             match input.parse::<Ident>()?.to_string().as_str() {
+                "Meta" => Self::Meta(input.parse()?),
                 "Tone" => Self::Tone(input.parse()?),
                 "Chord" => Self::Chord(input.parse()?),
                 "Word" => Self::Word(input.parse()?),
@@ -56,6 +59,7 @@ impl ToTokens for EntryDsl {
         tokens.extend(match self {
             Self::Mark(x) => quote! { ProtoEntry::from(#x) },
             Self::Context(x) => quote! { #x },
+            Self::Meta(x) => quote! { #x },
             Self::Tone(x) => quote! { #x },
             Self::Chord(x) => quote! { #x },
             Self::Word(x) => quote! { #x },
@@ -66,16 +70,18 @@ impl ToTokens for EntryDsl {
     }
 }
 impl EntryDsl {
+    #[throws(Error)]
     pub fn add_proto(&self, entries: &mut Vec<ProtoEntry>) {
         match self {
             EntryDsl::Context(x) => entries.push(x.to_proto()),
             EntryDsl::Mark(x) => entries.push(ProtoEntry::from(x.mark.clone())),
+            EntryDsl::Meta(x) => entries.push(x.to_proto()),
             EntryDsl::Tone(x) => x.add_proto(entries),
             EntryDsl::Chord(x) => x.add_proto(entries),
             EntryDsl::Word(x) => x.add_proto(entries),
             EntryDsl::Pick(x) => x.add_proto(entries),
             EntryDsl::Shape(x) => entries.push(x.to_proto()),
-            EntryDsl::Fretboard(x) => entries.push(x.to_proto()),
+            EntryDsl::Fretboard(x) => entries.push(x.to_proto()?),
         }
     }
 }
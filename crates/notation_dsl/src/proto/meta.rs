@@ -1,6 +1,6 @@
-use fehler::throws;
+use fehler::{throw, throws};
 
-use notation_proto::prelude::{Key, Scale, TabMeta};
+use notation_proto::prelude::{Key, MetaEntry, ProtoEntry, Scale, TabMeta};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::parse::{Error, Parse, ParseStream};
@@ -63,3 +63,51 @@ impl MetaDsl {
         TabMeta::new(key, scale, self.signature.to_proto(), self.tempo.to_proto())
     }
 }
+
+/// Changes the meta from the bar it's in, e.g. `Meta Key D`, `Meta Scale Dorian`,
+/// `Meta Signature 3 Quarter` or `Meta Tempo 120`.
+pub enum MetaEntryDsl {
+    Signature(SignatureDsl),
+    Tempo(TempoDsl),
+    Scale(Ident),
+    Key(Ident),
+}
+
+impl Parse for MetaEntryDsl {
+    #[throws(Error)]
+    fn parse(input: ParseStream) -> Self {
+        match input.parse::<Ident>()?.to_string().as_str() {
+            "Signature" => Self::Signature(input.parse()?),
+            "Tempo" => Self::Tempo(input.parse()?),
+            "Scale" => Self::Scale(input.parse()?),
+            "Key" => Self::Key(input.parse()?),
+            _ => throw!(Error::new(input.span(), "Invalid Meta")),
+        }
+    }
+}
+impl ToTokens for MetaEntryDsl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Signature(x) => quote! { ProtoEntry::from(MetaEntry::Signature(#x)) },
+            Self::Tempo(x) => quote! { ProtoEntry::from(MetaEntry::Tempo(#x)) },
+            Self::Scale(x) => {
+                let scale_ident = x.to_string();
+                quote! { ProtoEntry::from(MetaEntry::Scale(Scale::from_ident(#scale_ident))) }
+            }
+            Self::Key(x) => {
+                let key_ident = x.to_string();
+                quote! { ProtoEntry::from(MetaEntry::Key(Key::from_ident(#key_ident))) }
+            }
+        });
+    }
+}
+impl MetaEntryDsl {
+    pub fn to_proto(&self) -> ProtoEntry {
+        ProtoEntry::from(match self {
+            Self::Signature(x) => MetaEntry::Signature(x.to_proto()),
+            Self::Tempo(x) => MetaEntry::Tempo(x.to_proto()),
+            Self::Scale(x) => MetaEntry::Scale(Scale::from_ident(x.to_string().as_str())),
+            Self::Key(x) => MetaEntry::Key(Key::from_ident(x.to_string().as_str())),
+        })
+    }
+}
@@ -83,9 +83,14 @@ impl ToTokens for TabDsl {
 }
 
 impl TabDsl {
+    #[throws(Error)]
     pub fn to_proto(&self) -> Tab {
         let meta = self.meta.to_proto();
-        let tracks = self.tracks.iter().map(|x| x.to_proto()).collect();
+        let tracks = self
+            .tracks
+            .iter()
+            .map(|x| x.to_proto())
+            .collect::<Result<_, Error>>()?;
         let sections = self.sections.iter().map(|x| x.to_proto()).collect();
         Tab::new(&self.uuid, meta, tracks, sections, self.form.to_proto())
     }
@@ -38,10 +38,11 @@ impl ToTokens for TrackDsl {
 }
 
 impl TrackDsl {
+    #[throws(Error)]
     pub fn to_proto(&self) -> Track {
         let mut entries = Vec::new();
         for entry in self.entries.iter() {
-            entry.add_proto(&mut entries);
+            entry.add_proto(&mut entries)?;
         }
         Track::new(
             self.id.id.clone(),
@@ -0,0 +1,308 @@
+use fehler::{throw, throws};
+use std::fmt::Write;
+use std::path::Path;
+use thiserror::Error;
+
+use notation_model::prelude::*;
+
+use crate::chord_name_util::ChordNameUtil;
+
+#[derive(Error, Debug)]
+pub enum AsciiTabExportError {
+    #[error("parse error")]
+    Parse(#[from] ParseError),
+    #[error("format error")]
+    Format(#[from] std::fmt::Error),
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("no guitar track")]
+    NoGuitarTrack,
+    #[error("track without fretboard")]
+    NoFretboard(String),
+}
+
+/// Rendered bar, with the chord line and one line per string, all lines
+/// are of the same width.
+struct AsciiBar {
+    chords: String,
+    strings: [String; 6],
+}
+
+/// Renders a guitar track as classic six lines ASCII tab, the form is expanded,
+/// so repeated sections are written out as played.
+#[derive(Copy, Clone, Debug)]
+pub struct AsciiTabExporter {
+    pub columns_per_beat: usize,
+    pub bars_per_line: usize,
+}
+
+impl Default for AsciiTabExporter {
+    fn default() -> Self {
+        Self {
+            columns_per_beat: 4,
+            bars_per_line: 4,
+        }
+    }
+}
+
+impl AsciiTabExporter {
+    pub const MAX_STRETCH: usize = 4;
+
+    #[throws(AsciiTabExportError)]
+    pub fn export_file<P: AsRef<Path>>(&self, tab: &ProtoTab, path: P) {
+        std::fs::write(path, self.export(tab)?)?;
+    }
+    /// Exports the first guitar track of the tab.
+    #[throws(AsciiTabExportError)]
+    pub fn export(&self, tab: &ProtoTab) -> String {
+        let tab = Tab::try_parse_arc(tab.clone(), false, None)?;
+        let track = tab
+            .tracks
+            .iter()
+            .find(|x| x.kind == TrackKind::Guitar && x.get_fretboard6().is_some())
+            .cloned();
+        match track {
+            Some(track) => self.export_track(&tab, &track)?,
+            None => throw!(AsciiTabExportError::NoGuitarTrack),
+        }
+    }
+    #[throws(AsciiTabExportError)]
+    pub fn export_track(&self, tab: &Tab, track: &Track) -> String {
+        let fretboard = match track.get_fretboard6() {
+            Some(fretboard) => fretboard,
+            None => throw!(AsciiTabExportError::NoFretboard(track.id.clone())),
+        };
+        let names = Self::get_string_names(tab, &fretboard);
+        let mut text = String::new();
+        writeln!(text, "{}", Self::meta_line(&tab.meta))?;
+        if fretboard.capo > 0 {
+            writeln!(text, "Capo: {}", fretboard.capo)?;
+        }
+        writeln!(text)?;
+        let mut line: Vec<AsciiBar> = Vec::new();
+        for bar in tab.bars.iter() {
+            if bar.props.bar_index == 0 || line.len() >= self.bars_per_line.max(1) {
+                self.write_line(&mut text, &names, &line)?;
+                line.clear();
+            }
+            if bar.props.bar_index == 0 {
+                let kind = match &bar.section.kind {
+                    SectionKind::Custom(x) => x.clone(),
+                    x => x.to_string(),
+                };
+                writeln!(text, "[{}]", kind)?;
+            }
+            line.push(self.render_bar(bar, track));
+        }
+        self.write_line(&mut text, &names, &line)?;
+        text
+    }
+}
+
+impl AsciiTabExporter {
+    /// The meta of the tab, e.g. `Key: E Dorian, Time: 3/4, Tempo: 110`, named
+    /// tempos are written in bpm.
+    pub fn meta_line(meta: &TabMeta) -> String {
+        format!(
+            "Key: {} {}, Time: {}, Tempo: {}",
+            Pitch::from(meta.key),
+            meta.scale,
+            meta.signature,
+            Bpm::from(meta.tempo)
+        )
+    }
+    /// The first string is in lower case, e.g. `e` and `E` in standard tuning.
+    fn get_string_names(tab: &Tab, fretboard: &Fretboard6) -> [String; 6] {
+        let meta = &tab.meta;
+        let mut names = fretboard.string_notes.map(|x| {
+            meta.scale
+                .calc_note_from_semitones(&meta.key, x)
+                .pitch
+                .to_string()
+        });
+        names[0] = names[0].to_lowercase();
+        let width = names.iter().map(|x| x.len()).max().unwrap_or(1);
+        names.map(|x| format!("{:<width$}", x, width = width))
+    }
+    #[throws(std::fmt::Error)]
+    fn write_line(&self, text: &mut String, names: &[String; 6], line: &[AsciiBar]) {
+        if line.is_empty() {
+            return;
+        }
+        if line.iter().any(|x| !x.chords.trim().is_empty()) {
+            let mut chords = " ".repeat(names[0].len() + 1);
+            for bar in line.iter() {
+                chords.push_str(&bar.chords);
+                chords.push(' ');
+            }
+            writeln!(text, "{}", chords.trim_end())?;
+        }
+        for (index, name) in names.iter().enumerate() {
+            write!(text, "{}|", name)?;
+            for bar in line.iter() {
+                write!(text, "{}|", bar.strings[index])?;
+            }
+            writeln!(text)?;
+        }
+        writeln!(text)?;
+    }
+    fn calc_column(bar: &TabBar, pos: Units, columns_per_beat: usize) -> usize {
+        let beat_units = Units::from(bar.beat_unit()).0;
+        1 + (pos.0 / beat_units * columns_per_beat as f32).round() as usize
+    }
+    /// Returns the columns of the items, and the end of the last one, `None` if
+    /// any item is overlapping the previous one, unless forced to push it to
+    /// the right.
+    fn calc_columns(
+        bar: &TabBar,
+        items: &[(Units, usize)],
+        columns_per_beat: usize,
+        force: bool,
+    ) -> Option<(Vec<usize>, usize)> {
+        let mut columns = Vec::new();
+        let mut next_column = 1;
+        for (pos, width) in items.iter() {
+            let mut column = Self::calc_column(bar, *pos, columns_per_beat);
+            if column < next_column {
+                if !force {
+                    return None;
+                }
+                column = next_column;
+            }
+            next_column = column + width + 1;
+            columns.push(column);
+        }
+        Some((columns, next_column))
+    }
+    /// Frets and chord names are placed by the in bar position, the bar is
+    /// stretched if they don't fit, so the spacing is kept proportional.
+    fn render_bar(&self, bar: &TabBar, track: &Track) -> AsciiBar {
+        let frets: Vec<(Units, [Option<String>; 6])> = Self::get_frets(bar, track)
+            .into_iter()
+            .map(|(pos, notes)| (pos, notes.map(|x| x.map(|fret| fret.to_string()))))
+            .collect();
+        let chords: Vec<(Units, String)> = Self::get_chords(bar)
+            .into_iter()
+            .map(|(pos, chord)| (pos, ChordNameUtil::format(&bar.meta, &chord)))
+            .collect();
+        let fret_widths: Vec<(Units, usize)> = frets
+            .iter()
+            .map(|(pos, texts)| {
+                let width = texts.iter().flatten().map(|x| x.len()).max().unwrap_or(1);
+                (*pos, width)
+            })
+            .collect();
+        let chord_widths: Vec<(Units, usize)> = chords
+            .iter()
+            .map(|(pos, name)| (*pos, name.chars().count()))
+            .collect();
+        let mut stretch = 1;
+        let (fret_columns, chord_columns, width) = loop {
+            let columns_per_beat = self.columns_per_beat.max(1) * stretch;
+            let force = stretch >= Self::MAX_STRETCH;
+            if let (Some((fret_columns, fret_end)), Some((chord_columns, chord_end))) = (
+                Self::calc_columns(bar, &fret_widths, columns_per_beat, force),
+                Self::calc_columns(bar, &chord_widths, columns_per_beat, force),
+            ) {
+                let width = [
+                    1 + bar.bar_beats() as usize * columns_per_beat,
+                    fret_end,
+                    chord_end,
+                ]
+                .into_iter()
+                .max()
+                .unwrap_or(1);
+                break (fret_columns, chord_columns, width);
+            }
+            stretch += 1;
+        };
+        let mut chord_line = vec![' '; width];
+        for (column, (_, name)) in chord_columns.iter().zip(chords.iter()) {
+            for (index, c) in name.chars().enumerate() {
+                chord_line[column + index] = c;
+            }
+        }
+        let strings = [0, 1, 2, 3, 4, 5].map(|string| {
+            let mut string_line = vec!['-'; width];
+            for (column, (_, texts)) in fret_columns.iter().zip(frets.iter()) {
+                if let Some(text) = &texts[string] {
+                    for (index, c) in text.chars().enumerate() {
+                        string_line[column + index] = c;
+                    }
+                }
+            }
+            string_line.into_iter().collect()
+        });
+        AsciiBar {
+            chords: chord_line.into_iter().collect(),
+            strings,
+        }
+    }
+    fn get_chords(bar: &TabBar) -> Vec<(Units, Chord)> {
+        let mut chords = Vec::new();
+        for lane in bar.lanes.values() {
+            if lane.kind != LaneKind::Chord {
+                continue;
+            }
+            for entry in lane.entries.iter() {
+                if let Some(chord) = entry.proto().as_core().and_then(|x| x.as_chord()) {
+                    chords.push((entry.in_bar_pos(), *chord));
+                }
+            }
+        }
+        chords.sort_by(|a, b| a.0.cmp(&b.0));
+        chords.dedup_by(|a, b| a.0 == b.0);
+        chords
+    }
+    /// Picks and strums resolved against the current shape, tied notes are
+    /// not repeated.
+    fn get_frets(bar: &TabBar, track: &Track) -> Vec<(Units, [Option<u8>; 6])> {
+        let meta = bar.meta.as_ref();
+        let mut frets: Vec<(Units, [Option<u8>; 6])> = Vec::new();
+        for lane in bar.lanes.values() {
+            if lane.track.id != track.id || lane.kind != LaneKind::Strings {
+                continue;
+            }
+            for entry in lane.entries.iter() {
+                if entry.duration() == Duration::Zero || entry.prev_is_tie() {
+                    continue;
+                }
+                let pick_notes: Vec<PickNote> = match entry.model.as_fretted6() {
+                    Some(FrettedEntry6::Pick(pick, _)) => pick.get_notes(),
+                    Some(FrettedEntry6::Strum(strum, _)) => {
+                        let (begin, end) = match strum.strings {
+                            StrumStrings::All => (1, 6),
+                            StrumStrings::Between(x, y) => (x.min(y), x.max(y)),
+                        };
+                        (begin..=end).map(PickNote::new_string).collect()
+                    }
+                    _ => continue,
+                };
+                let (fretboard, shape) = match bar.get_fretted_shape6(entry) {
+                    Some(x) => x,
+                    None => continue,
+                };
+                let pos = entry.in_bar_pos();
+                let index = match frets.iter().position(|x| x.0 == pos) {
+                    Some(index) => index,
+                    None => {
+                        frets.push((pos, [None; 6]));
+                        frets.len() - 1
+                    }
+                };
+                for pick_note in pick_notes {
+                    if pick_note.string == 0 || pick_note.string > 6 {
+                        continue;
+                    }
+                    if let Some((fret, _)) =
+                        fretboard.shape_pick_fret_note(&meta.scale, &meta.key, &shape, pick_note)
+                    {
+                        frets[index].1[pick_note.string as usize - 1] = Some(fret);
+                    }
+                }
+            }
+        }
+        frets.sort_by(|a, b| a.0.cmp(&b.0));
+        frets
+    }
+}
@@ -0,0 +1,421 @@
+use fehler::{throw, throws};
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+
+use notation_proto::prelude::*;
+
+use crate::chord_name_util::ChordNameUtil;
+use crate::import_util::{ImportSpan, ImportUtil, ImportValue};
+use crate::midi_import::MidiImporter;
+use crate::music_xml_util::MusicXmlUtil;
+
+#[derive(Error, Debug)]
+pub enum AsciiTabImportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("no staff found")]
+    NoStaff,
+}
+
+/// Six lines staff, the columns are relative to the first `|` of each line,
+/// chords are from the line right above the staff.
+struct AsciiStaff {
+    names: Vec<String>,
+    lines: Vec<Vec<char>>,
+    chords: Vec<(usize, String)>,
+}
+
+enum AsciiBlock {
+    Section(SectionKind),
+    Staff(AsciiStaff),
+}
+
+/// Imports plain text six lines tablature, e.g. `e|---0---|`, the durations are
+/// inferred from the column spacing, with every bar lasting for the signature,
+/// which is read from the meta header if there is one.
+///
+/// Technique letters are read into the pick notes: `h`, `p` and `t` for the
+/// attack of the next note, `b` and `r` for bending the previous one to the
+/// following fret and back, `s` for slides, `v` for vibrato and `x` for dead notes.
+#[derive(Copy, Clone, Debug)]
+pub struct AsciiTabImporter {
+    pub signature: Signature,
+    pub grid_unit: Unit,
+    pub bars_per_section: usize,
+}
+
+impl Default for AsciiTabImporter {
+    fn default() -> Self {
+        Self {
+            signature: Signature::_4_4,
+            grid_unit: Unit::Sixteenth,
+            bars_per_section: 4,
+        }
+    }
+}
+
+impl AsciiTabImporter {
+    pub const MAX_FRET: u8 = 24;
+
+    #[throws(AsciiTabImportError)]
+    pub fn import_file<P: AsRef<Path>>(&self, path: P) -> Tab {
+        let text = std::fs::read_to_string(path)?;
+        self.import(&text)?
+    }
+    #[throws(AsciiTabImportError)]
+    pub fn import(&self, text: &str) -> Tab {
+        let default_meta = TabMeta {
+            signature: self.signature,
+            ..TabMeta::default()
+        };
+        let meta = text
+            .lines()
+            .find_map(|x| Self::parse_meta_line(&default_meta, x.trim()))
+            .unwrap_or(default_meta);
+        let (blocks, capo) = Self::parse_blocks(&meta, text);
+        let bar_steps = MidiImporter::duration_steps(Units::from(meta.signature));
+        let beat_steps = std::cmp::max(
+            MidiImporter::duration_steps(Units::from(meta.signature.beat_unit)),
+            MidiImporter::STRAIGHT_STEPS * MidiImporter::TRIPLET_STEPS,
+        );
+        let grid_steps = MidiImporter::duration_steps(Units::from(self.grid_unit)).max(1);
+        let mut fretboard = None;
+        let mut picks = Vec::new();
+        let mut harmonies = Vec::new();
+        let mut starts = Vec::new();
+        let mut bar_num = 0;
+        for block in blocks.iter() {
+            match block {
+                AsciiBlock::Section(kind) => starts.push((bar_num, kind.clone())),
+                AsciiBlock::Staff(staff) => {
+                    if fretboard.is_none() {
+                        fretboard = Some(Self::calc_fretboard(&staff.names, capo));
+                    }
+                    bar_num += Self::parse_staff(
+                        &meta,
+                        staff,
+                        bar_num,
+                        bar_steps,
+                        grid_steps,
+                        &mut picks,
+                        &mut harmonies,
+                    );
+                }
+            }
+        }
+        let fretboard = match fretboard {
+            Some(fretboard) if bar_num > 0 => fretboard,
+            _ => throw!(AsciiTabImportError::NoStaff),
+        };
+        let mut tracks: Vec<Track> = Vec::new();
+        let mut layers: Vec<Vec<BarLayer>> = vec![Vec::new(); bar_num];
+        let chord_spans = ImportUtil::calc_chord_spans(harmonies, bar_num as u32 * bar_steps);
+        if !chord_spans.is_empty() {
+            let id = ImportUtil::calc_track_id("chord", &tracks);
+            let mut entries = Vec::new();
+            ImportUtil::push_bars(
+                &mut entries,
+                &mut layers,
+                &id,
+                &[chord_spans],
+                bar_steps,
+                beat_steps,
+            );
+            tracks.push(Track::new(id, TrackKind::Chord, entries));
+        }
+        let id = ImportUtil::calc_track_id("guitar", &tracks);
+        let mut entries = vec![ProtoEntry::from(FrettedEntry6::Fretboard(fretboard))];
+        ImportUtil::push_bars(
+            &mut entries,
+            &mut layers,
+            &id,
+            &[picks],
+            bar_steps,
+            beat_steps,
+        );
+        tracks.push(Track::new(id, TrackKind::Guitar, entries));
+        let (sections, form) = ImportUtil::new_sections(layers, &starts, self.bars_per_section);
+        Tab::new(Tab::new_uuid().as_str(), meta, tracks, sections, form)
+    }
+}
+
+impl AsciiTabImporter {
+    fn parse_blocks(meta: &TabMeta, text: &str) -> (Vec<AsciiBlock>, u8) {
+        let lines: Vec<&str> = text.lines().map(|x| x.trim_end()).collect();
+        let mut blocks = Vec::new();
+        let mut capo = 0;
+        let mut index = 0;
+        while index < lines.len() {
+            let staff_lines: Vec<(String, Vec<char>)> = lines[index..]
+                .iter()
+                .take(6)
+                .map_while(|x| Self::parse_staff_line(x))
+                .collect();
+            if staff_lines.len() == 6 {
+                let chords = match index.checked_sub(1).map(|x| lines[x]) {
+                    Some(line) => Self::parse_chord_line(meta, line, lines[index]),
+                    None => vec![],
+                };
+                let (names, lines) = staff_lines.into_iter().unzip();
+                blocks.push(AsciiBlock::Staff(AsciiStaff {
+                    names,
+                    lines,
+                    chords,
+                }));
+                index += 6;
+                continue;
+            }
+            let line = lines[index].trim();
+            if let Some(kind) = Self::parse_section(line) {
+                blocks.push(AsciiBlock::Section(kind));
+            } else if let Some(value) = Self::parse_capo(line) {
+                capo = value;
+            }
+            index += 1;
+        }
+        (blocks, capo)
+    }
+    /// Returns the string name, and the content from the first `|`.
+    fn parse_staff_line(line: &str) -> Option<(String, Vec<char>)> {
+        let chars: Vec<char> = line.chars().collect();
+        let bar = chars.iter().position(|x| *x == '|')?;
+        let name: String = chars[..bar].iter().collect::<String>().trim().to_string();
+        let valid_name = name.is_empty()
+            || ChordNameUtil::parse_pitch(&name)
+                .map(|x| x.1.is_empty())
+                .unwrap_or(false);
+        let content = chars[bar..].to_vec();
+        let valid_content = content.contains(&'-')
+            && content
+                .iter()
+                .all(|x| !x.is_alphabetic() || "hpbrxsvt".contains(x.to_ascii_lowercase()));
+        if valid_name && valid_content {
+            Some((name, content))
+        } else {
+            None
+        }
+    }
+    fn parse_chord_line(meta: &TabMeta, line: &str, staff_line: &str) -> Vec<(usize, String)> {
+        let offset = staff_line.chars().position(|x| x == '|').unwrap_or(0);
+        let mut chords = Vec::new();
+        let mut column: usize = 0;
+        for token in line.split(' ') {
+            if !token.is_empty() {
+                if ChordNameUtil::parse(meta, token).is_none() {
+                    return vec![];
+                }
+                chords.push((column.saturating_sub(offset), token.to_string()));
+            }
+            column += token.chars().count() + 1;
+        }
+        chords
+    }
+    /// The meta header written by the exporter, e.g.
+    /// `Key: E Dorian, Time: 3/4, Tempo: 110`, fields can be left out.
+    fn parse_meta_line(meta: &TabMeta, line: &str) -> Option<TabMeta> {
+        let mut result = *meta;
+        for field in line.split(',') {
+            let (name, value) = field.split_once(':')?;
+            let value = value.trim();
+            match name.trim().to_lowercase().as_str() {
+                "key" => {
+                    let (pitch, rest) = ChordNameUtil::parse_pitch(value)?;
+                    result.key = match pitch.sign {
+                        PitchSign::Sharp => Key::Sharp(pitch.name),
+                        PitchSign::Flat => Key::Flat(pitch.name),
+                        _ => Key::Natural(pitch.name),
+                    };
+                    result.scale = match rest.trim() {
+                        "" => Scale::Ionian,
+                        "m" => Scale::Aeolian,
+                        scale => Scale::from_ident(scale),
+                    };
+                }
+                "time" => {
+                    let (beats, beat_type) = value.split_once('/')?;
+                    let bar_beats = beats.trim().parse::<u8>().ok().filter(|x| *x > 0)?;
+                    let beat_unit = beat_type
+                        .trim()
+                        .parse::<u8>()
+                        .ok()
+                        .and_then(MusicXmlUtil::unit_from_beat_type)?;
+                    result.signature = Signature::new(beat_unit, bar_beats);
+                }
+                "tempo" => {
+                    let bpm = value.parse::<u16>().ok()?;
+                    result.tempo = Tempo::Bpm(bpm.max(1));
+                }
+                _ => return None,
+            }
+        }
+        Some(result)
+    }
+    /// Section headers are in brackets, e.g. `[Verse]`, `[Pre-Chorus]`.
+    fn parse_section(line: &str) -> Option<SectionKind> {
+        let name = line.strip_prefix('[')?.strip_suffix(']')?;
+        ImportUtil::parse_section_kind(name)
+    }
+    fn parse_capo(line: &str) -> Option<u8> {
+        let rest = line.to_lowercase();
+        let rest = rest.strip_prefix("capo")?.trim_start_matches([':', ' ']);
+        let digits: String = rest.chars().take_while(|x| x.is_ascii_digit()).collect();
+        digits.parse().ok()
+    }
+    /// The octaves of the strings are the closest ones to the standard tuning.
+    fn calc_fretboard(names: &[String], capo: u8) -> Fretboard6 {
+        let mut fretboard = GuitarUtil::new_default_fretboard();
+        for (index, name) in names.iter().enumerate().take(6) {
+            if let Some((pitch, "")) = ChordNameUtil::parse_pitch(name) {
+                let standard = fretboard.string_notes[index].0;
+                let pitch_class = Semitones::from(pitch).0;
+                let octave = ((standard - pitch_class) as f32 / 12.0).round() as i8;
+                fretboard.string_notes[index] = Semitones(pitch_class + octave * 12);
+            }
+        }
+        fretboard.capo = capo.min(fretboard.total_fret_num.saturating_sub(1) as u8);
+        fretboard
+    }
+    /// Returns the fret and its width, two digits are read as one fret if it's
+    /// not too high.
+    fn parse_fret(line: &[char], column: usize) -> Option<(u8, usize)> {
+        let first = line.get(column)?.to_digit(10)? as u8;
+        match line.get(column + 1).and_then(|x| x.to_digit(10)) {
+            Some(second) if first * 10 + (second as u8) <= Self::MAX_FRET => {
+                Some((first * 10 + second as u8, 2))
+            }
+            _ => Some((first, 1)),
+        }
+    }
+    /// Each bar of the staff is a bar of the tab, a leading `-` is ignored
+    /// for the position of the notes, returns the number of bars.
+    fn parse_staff(
+        meta: &TabMeta,
+        staff: &AsciiStaff,
+        first_bar: usize,
+        bar_steps: u32,
+        grid_steps: u32,
+        picks: &mut Vec<ImportSpan>,
+        harmonies: &mut Vec<(u32, Chord)>,
+    ) -> usize {
+        let first_line = &staff.lines[0];
+        let mut separators: Vec<usize> = first_line
+            .iter()
+            .enumerate()
+            .filter(|x| *x.1 == '|')
+            .map(|x| x.0)
+            .collect();
+        if first_line.last() != Some(&'|') {
+            separators.push(first_line.len());
+        }
+        let mut bar_index = first_bar;
+        for pair in separators.windows(2) {
+            let (start, end) = (pair[0] + 1, pair[1]);
+            if end <= start {
+                continue;
+            }
+            let lead = if staff.lines.iter().all(|x| x.get(start) == Some(&'-')) {
+                1
+            } else {
+                0
+            };
+            let width = std::cmp::max(end - start - lead, 1);
+            let bar_begin = bar_index as u32 * bar_steps;
+            let last_step = bar_steps.saturating_sub(grid_steps);
+            let calc_steps = |column: usize| -> u32 {
+                let offset = column.saturating_sub(start + lead) as f32 / width as f32;
+                let grids = (offset * bar_steps as f32 / grid_steps as f32).round() as u32;
+                bar_begin + std::cmp::min(grids * grid_steps, last_step)
+            };
+            let mut notes: BTreeMap<u32, Vec<PickNote>> = BTreeMap::new();
+            for (index, line) in staff.lines.iter().enumerate() {
+                let string = index as u8 + 1;
+                let mut column = start;
+                let mut last: Option<(u32, usize)> = None;
+                let mut pending = PickTechniques::default();
+                while column < end.min(line.len()) {
+                    if let Some((fret, len)) = Self::parse_fret(line, column) {
+                        let pick_notes = notes.entry(calc_steps(column)).or_default();
+                        pick_notes
+                            .push(PickNote::new_string_fret(string, fret).with_techniques(pending));
+                        last = Some((calc_steps(column), pick_notes.len() - 1));
+                        pending = PickTechniques::default();
+                        column += len;
+                        continue;
+                    }
+                    let letter = line[column].to_ascii_lowercase();
+                    let next = Self::parse_fret(line, column + 1);
+                    let last_note = last.and_then(|(steps, x)| notes.get_mut(&steps)?.get_mut(x));
+                    match letter {
+                        'h' => pending.attack = Some(PickAttack::HammerOn),
+                        'p' => pending.attack = Some(PickAttack::PullOff),
+                        't' => pending.attack = Some(PickAttack::Tap),
+                        'v' => {
+                            if let Some(note) = last_note {
+                                note.techniques.vibrato = true;
+                            }
+                        }
+                        's' => match (last_note, next) {
+                            (Some(note), Some((fret, _))) if note.fret >= Some(fret) => {
+                                note.techniques.slide_out = true;
+                            }
+                            _ => pending.slide_in = true,
+                        },
+                        'b' | 'r' => {
+                            // The fret after `b` or `r` is the pitch bent to, not a note.
+                            if let Some(note) = last_note {
+                                match (letter, note.fret, next) {
+                                    ('b', Some(fret), Some((target, _))) if target > fret => {
+                                        note.techniques.bend =
+                                            Some(Bend::new(target - fret, false));
+                                    }
+                                    ('r', _, _) => {
+                                        if let Some(bend) = note.techniques.bend.as_mut() {
+                                            bend.release = true;
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            column += next.map(|x| x.1).unwrap_or(0);
+                        }
+                        'x' => {
+                            let mut techniques = pending;
+                            techniques.dead_note = true;
+                            let pick_notes = notes.entry(calc_steps(column)).or_default();
+                            pick_notes.push(
+                                PickNote::new_string_fret(string, 0).with_techniques(techniques),
+                            );
+                            last = Some((calc_steps(column), pick_notes.len() - 1));
+                            pending = PickTechniques::default();
+                        }
+                        _ => {}
+                    }
+                    column += 1;
+                }
+            }
+            let begins: Vec<u32> = notes.keys().copied().collect();
+            for (index, (begin, pick_notes)) in notes.into_iter().enumerate() {
+                let end = begins
+                    .get(index + 1)
+                    .copied()
+                    .unwrap_or(bar_begin + bar_steps);
+                picks.push(ImportSpan::new(
+                    begin,
+                    end,
+                    ImportValue::Pick6(pick_notes),
+                    false,
+                ));
+            }
+            for (column, name) in staff.chords.iter() {
+                if *column + 1 >= start && *column < end {
+                    if let Some(chord) = ChordNameUtil::parse(meta, name) {
+                        harmonies.push((calc_steps(*column), chord));
+                    }
+                }
+            }
+            bar_index += 1;
+        }
+        bar_index - first_bar
+    }
+}
@@ -0,0 +1,29 @@
+use notation_proto::prelude::*;
+
+use crate::music_xml_util::MusicXmlUtil;
+
+/// Conventional chord names for the plain text formats, e.g. `Am7` or `G/B`.
+pub struct ChordNameUtil();
+
+impl ChordNameUtil {
+    pub fn format(meta: &TabMeta, chord: &Chord) -> String {
+        chord.to_text(&meta.scale, &meta.key)
+    }
+    pub fn parse_pitch(text: &str) -> Option<(Pitch, &str)> {
+        let mut chars = text.chars();
+        let step = chars.next()?.to_ascii_uppercase().to_string();
+        let rest = chars.as_str();
+        let (alter, rest) = if let Some(rest) = rest.strip_prefix('#') {
+            (1, rest)
+        } else if let Some(rest) = rest.strip_prefix('b') {
+            (-1, rest)
+        } else {
+            (0, rest)
+        };
+        MusicXmlUtil::pitch_from_step_alter(&step, alter).map(|x| (x, rest))
+    }
+    /// Parses names like `F#m7b5/C`, `Gsus4` or `Bbmaj9`.
+    pub fn parse(meta: &TabMeta, text: &str) -> Option<Chord> {
+        Chord::from_text(&meta.scale, &meta.key, text)
+    }
+}
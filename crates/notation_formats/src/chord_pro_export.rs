@@ -0,0 +1,247 @@
+use fehler::throws;
+use std::fmt::Write;
+use std::path::Path;
+use thiserror::Error;
+
+use notation_model::prelude::*;
+
+use crate::chord_name_util::ChordNameUtil;
+use crate::music_xml_util::MusicXmlUtil;
+
+#[derive(Error, Debug)]
+pub enum ChordProExportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("parse error")]
+    Parse(#[from] ParseError),
+    #[error("format error")]
+    Format(#[from] std::fmt::Error),
+}
+
+#[derive(Clone, Debug)]
+enum ChordProEvent {
+    Chord(Chord),
+    Word(String),
+}
+
+// https://www.chordpro.org/chordpro/chordpro-directives/
+/// Exports the chords and lyrics of a tab as a ChordPro song sheet, the form is
+/// expanded, so repeated sections are written out as played, `title` overrides
+/// the one of the tab.
+#[derive(Clone, Debug)]
+pub struct ChordProExporter {
+    pub title: Option<String>,
+    pub bars_per_line: usize,
+}
+
+impl Default for ChordProExporter {
+    fn default() -> Self {
+        Self {
+            title: None,
+            bars_per_line: 4,
+        }
+    }
+}
+
+impl ChordProExporter {
+    #[throws(ChordProExportError)]
+    pub fn export_file<P: AsRef<Path>>(&self, tab: &ProtoTab, path: P) {
+        std::fs::write(path, self.export(tab)?)?;
+    }
+    #[throws(ChordProExportError)]
+    pub fn export(&self, tab: &ProtoTab) -> String {
+        let tab = Tab::try_parse_arc(tab.clone(), false, None)?;
+        self.export_tab(&tab)?
+    }
+    #[throws(std::fmt::Error)]
+    pub fn export_tab(&self, tab: &Tab) -> String {
+        let mut meta = *tab.meta;
+        let mut text = String::new();
+        if let Some(title) = self.title.as_ref().or(tab.title.as_ref()) {
+            writeln!(text, "{{title: {}}}", title)?;
+        }
+        Self::write_meta(&mut text, &meta, None)?;
+        let lyrics_track = tab
+            .tracks
+            .iter()
+            .find(|x| x.kind == TrackKind::Lyrics)
+            .map(|x| x.id.clone());
+        let mut environment: Option<&'static str> = None;
+        let mut last_chord = None;
+        let mut events = Vec::new();
+        let mut bars_in_line = 0;
+        let mut line_open = false;
+        for bar in tab.bars.iter() {
+            if bar.section.kind == SectionKind::Ready {
+                continue;
+            }
+            let meta_changed = *bar.meta != meta;
+            if bar.props.bar_index == 0
+                || bars_in_line >= self.bars_per_line.max(1)
+                || meta_changed
+                || !line_open
+            {
+                self.write_line(&mut text, &meta, &mut events, &mut last_chord)?;
+                bars_in_line = 0;
+            }
+            if bar.props.bar_index == 0 {
+                if let Some(name) = environment {
+                    writeln!(text, "{{end_of_{}}}", name)?;
+                }
+                writeln!(text)?;
+                let (name, label) = match &bar.section.kind {
+                    SectionKind::Verse => ("verse", None),
+                    SectionKind::Chorus => ("chorus", None),
+                    SectionKind::Bridge => ("bridge", None),
+                    SectionKind::Custom(x) => ("verse", Some(x.clone())),
+                    x => ("verse", Some(x.to_string())),
+                };
+                match label {
+                    Some(label) => writeln!(text, "{{start_of_{}: {}}}", name, label)?,
+                    None => writeln!(text, "{{start_of_{}}}", name)?,
+                }
+                environment = Some(name);
+                last_chord = None;
+            }
+            if meta_changed {
+                Self::write_meta(&mut text, &bar.meta, Some(&meta))?;
+                meta = *bar.meta;
+            }
+            Self::add_events(&mut events, bar, lyrics_track.as_deref());
+            line_open = Self::ends_with_word(bar, lyrics_track.as_deref());
+            bars_in_line += 1;
+        }
+        self.write_line(&mut text, &meta, &mut events, &mut last_chord)?;
+        if let Some(name) = environment {
+            writeln!(text, "{{end_of_{}}}", name)?;
+        }
+        text
+    }
+}
+
+impl ChordProExporter {
+    /// Only the directives changed since the last meta are written.
+    #[throws(std::fmt::Error)]
+    fn write_meta(text: &mut String, meta: &TabMeta, last_meta: Option<&TabMeta>) {
+        if last_meta
+            .map(|x| Self::key_name(x) != Self::key_name(meta))
+            .unwrap_or(true)
+        {
+            writeln!(text, "{{key: {}}}", Self::key_name(meta))?;
+        }
+        if last_meta
+            .map(|x| x.signature != meta.signature)
+            .unwrap_or(true)
+        {
+            writeln!(
+                text,
+                "{{time: {}/{}}}",
+                meta.signature.bar_beats,
+                MusicXmlUtil::unit_to_beat_type(&meta.signature.beat_unit)
+            )?;
+        }
+        if last_meta.map(|x| x.tempo != meta.tempo).unwrap_or(true) {
+            writeln!(text, "{{tempo: {}}}", Bpm::from(meta.tempo))?;
+        }
+    }
+    /// The minor modes are written as minor keys, e.g. `Em` for E Dorian.
+    fn key_name(meta: &TabMeta) -> String {
+        let pitch = match meta.key {
+            Key::Natural(name) => Pitch::new(name, PitchSign::Natural),
+            Key::Sharp(name) => Pitch::new(name, PitchSign::Sharp),
+            Key::Flat(name) => Pitch::new(name, PitchSign::Flat),
+        };
+        let minor = match meta.scale.to_diatonic() {
+            Scale::Dorian | Scale::Phrygian | Scale::Aeolian | Scale::Locrian => "m",
+            _ => "",
+        };
+        format!("{}{}", pitch, minor)
+    }
+    fn add_events(
+        events: &mut Vec<(Units, ChordProEvent)>,
+        bar: &TabBar,
+        lyrics_track: Option<&str>,
+    ) {
+        let bar_pos = bar.tab_position().in_tab_pos;
+        for lane in bar.lanes.values() {
+            for entry in lane.entries.iter() {
+                let pos = bar_pos + entry.in_bar_pos();
+                match (lane.kind, entry.proto()) {
+                    (LaneKind::Chord, ProtoEntry::Core(CoreEntry::Chord(chord, _))) => {
+                        events.push((pos, ChordProEvent::Chord(*chord)));
+                    }
+                    (LaneKind::Lyrics, ProtoEntry::Lyric(LyricEntry::Word(word, _)))
+                        if Some(lane.track.id.as_str()) == lyrics_track
+                            && !entry.prev_is_tie()
+                            && !word.text.is_empty() =>
+                    {
+                        events.push((pos, ChordProEvent::Word(word.text.clone())));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    /// Each line starts at a new bar when imported, so the line only goes on
+    /// after bars with words till the end.
+    fn ends_with_word(bar: &TabBar, lyrics_track: Option<&str>) -> bool {
+        bar.lanes.values().any(|lane| {
+            lane.kind == LaneKind::Lyrics
+                && Some(lane.track.id.as_str()) == lyrics_track
+                && matches!(
+                    lane.entries.last().map(|x| x.proto()),
+                    Some(ProtoEntry::Lyric(LyricEntry::Word(word, _))) if !word.text.is_empty()
+                )
+        })
+    }
+    /// Chords are put in front of the next word, the repeated ones are
+    /// skipped, words ending with `-` are joined with the next syllable, or
+    /// keep the `-` at the end of the line.
+    #[throws(std::fmt::Error)]
+    fn write_line(
+        &self,
+        text: &mut String,
+        meta: &TabMeta,
+        events: &mut Vec<(Units, ChordProEvent)>,
+        last_chord: &mut Option<Chord>,
+    ) {
+        events.sort_by(|a, b| {
+            let is_word = |x: &ChordProEvent| matches!(x, ChordProEvent::Word(_));
+            a.0.cmp(&b.0).then(is_word(&a.1).cmp(&is_word(&b.1)))
+        });
+        let mut line = String::new();
+        let mut pending = String::new();
+        let mut joined = true;
+        for (_, event) in events.drain(..) {
+            match event {
+                ChordProEvent::Chord(chord) => {
+                    if *last_chord != Some(chord) {
+                        write!(pending, "[{}]", ChordNameUtil::format(meta, &chord))?;
+                        *last_chord = Some(chord);
+                    }
+                }
+                ChordProEvent::Word(word) => {
+                    if !joined {
+                        line.push(' ');
+                    }
+                    line.push_str(&pending);
+                    pending.clear();
+                    line.push_str(word.trim_end_matches('-'));
+                    joined = word.ends_with('-');
+                }
+            }
+        }
+        if joined && !line.is_empty() {
+            line.push('-');
+        }
+        if !pending.is_empty() {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&pending);
+        }
+        if !line.is_empty() {
+            writeln!(text, "{}", line)?;
+        }
+    }
+}
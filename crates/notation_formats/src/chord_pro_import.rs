@@ -0,0 +1,334 @@
+use fehler::{throw, throws};
+use std::path::Path;
+use thiserror::Error;
+
+use notation_proto::prelude::*;
+
+use crate::chord_name_util::ChordNameUtil;
+use crate::import_util::{ImportSpan, ImportUtil, ImportValue};
+use crate::midi_import::MidiImporter;
+use crate::music_xml_util::MusicXmlUtil;
+
+#[derive(Error, Debug)]
+pub enum ChordProImportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("no lyrics or chords found")]
+    NoContent,
+}
+
+#[derive(Default)]
+struct ChordProSong {
+    title: Option<String>,
+    meta: TabMeta,
+    pos: u32,
+    chords: Vec<(u32, Chord)>,
+    words: Vec<ImportSpan>,
+    starts: Vec<(usize, SectionKind)>,
+    in_section: bool,
+    in_tab: bool,
+}
+
+// https://www.chordpro.org/chordpro/chordpro-directives/
+/// Imports ChordPro song sheets, every word lasts for a beat, each line starts
+/// in a new bar, and chords without words last for a bar.
+#[derive(Copy, Clone, Debug)]
+pub struct ChordProImporter {
+    pub bars_per_section: usize,
+}
+
+impl Default for ChordProImporter {
+    fn default() -> Self {
+        Self {
+            bars_per_section: 4,
+        }
+    }
+}
+
+impl ChordProImporter {
+    #[throws(ChordProImportError)]
+    pub fn import_file<P: AsRef<Path>>(&self, path: P) -> Tab {
+        let text = std::fs::read_to_string(path)?;
+        self.import(&text)?
+    }
+    #[throws(ChordProImportError)]
+    pub fn import(&self, text: &str) -> Tab {
+        let mut song = ChordProSong::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(directive) = line.strip_prefix('{').and_then(|x| x.strip_suffix('}')) {
+                Self::parse_directive(&mut song, directive);
+            } else if !song.in_tab && !line.is_empty() && !line.starts_with('#') {
+                Self::parse_line(&mut song, line);
+            }
+        }
+        if song.chords.is_empty() && song.words.is_empty() {
+            throw!(ChordProImportError::NoContent);
+        }
+        let meta = song.meta;
+        let bar_steps = MidiImporter::duration_steps(Units::from(meta.signature));
+        let beat_steps = std::cmp::max(
+            MidiImporter::duration_steps(Units::from(meta.signature.beat_unit)),
+            MidiImporter::STRAIGHT_STEPS * MidiImporter::TRIPLET_STEPS,
+        );
+        let bar_num = song.pos.div_ceil(bar_steps.max(1)) as usize;
+        let mut tracks: Vec<Track> = Vec::new();
+        let mut layers: Vec<Vec<BarLayer>> = vec![Vec::new(); bar_num];
+        let chord_spans = ImportUtil::calc_chord_spans(song.chords, bar_num as u32 * bar_steps);
+        if !chord_spans.is_empty() {
+            let id = ImportUtil::calc_track_id("chord", &tracks);
+            let mut entries = Vec::new();
+            ImportUtil::push_bars(
+                &mut entries,
+                &mut layers,
+                &id,
+                &[chord_spans],
+                bar_steps,
+                beat_steps,
+            );
+            tracks.push(Track::new(id, TrackKind::Chord, entries));
+        }
+        if !song.words.is_empty() {
+            let id = ImportUtil::calc_track_id("lyrics", &tracks);
+            let mut entries = Vec::new();
+            ImportUtil::push_bars(
+                &mut entries,
+                &mut layers,
+                &id,
+                &[song.words],
+                bar_steps,
+                beat_steps,
+            );
+            tracks.push(Track::new(id, TrackKind::Lyrics, entries));
+        }
+        let (sections, form) =
+            ImportUtil::new_sections(layers, &song.starts, self.bars_per_section);
+        Tab::new(Tab::new_uuid().as_str(), meta, tracks, sections, form).with_title(song.title)
+    }
+}
+
+impl ChordProImporter {
+    fn bar_steps(meta: &TabMeta) -> u32 {
+        MidiImporter::duration_steps(Units::from(meta.signature)).max(1)
+    }
+    fn beat_steps(meta: &TabMeta) -> u32 {
+        MidiImporter::duration_steps(Units::from(meta.signature.beat_unit)).max(1)
+    }
+    fn start_section(song: &mut ChordProSong, kind: SectionKind) {
+        let bar_steps = Self::bar_steps(&song.meta);
+        song.pos = song.pos.div_ceil(bar_steps) * bar_steps;
+        song.starts.push(((song.pos / bar_steps) as usize, kind));
+    }
+    /// The label of the section is used for the kind if it's a known one,
+    /// e.g. `{start_of_verse: Intro}`.
+    fn parse_directive(song: &mut ChordProSong, directive: &str) {
+        let (name, value) = match directive.split_once(':') {
+            Some((name, value)) => (name.trim().to_lowercase(), value.trim()),
+            None => (directive.trim().to_lowercase(), ""),
+        };
+        let label_kind =
+            ImportUtil::parse_section_kind(value).filter(|x| !matches!(x, SectionKind::Custom(_)));
+        match name.as_str() {
+            "title" | "t" if !value.is_empty() => song.title = Some(value.to_string()),
+            "key" => {
+                if let Some((pitch, rest)) = ChordNameUtil::parse_pitch(value) {
+                    song.meta.scale = if rest.starts_with('m') {
+                        Scale::Aeolian
+                    } else {
+                        Scale::Ionian
+                    };
+                    song.meta.key = match pitch.sign {
+                        PitchSign::Sharp => Key::Sharp(pitch.name),
+                        PitchSign::Flat => Key::Flat(pitch.name),
+                        _ => Key::Natural(pitch.name),
+                    };
+                }
+            }
+            "tempo" => {
+                if let Ok(bpm) = value.parse::<u16>() {
+                    song.meta.tempo = Tempo::Bpm(bpm.max(1));
+                }
+            }
+            "time" => {
+                if let Some((beats, beat_type)) = value.split_once('/') {
+                    let bar_beats = beats.trim().parse::<u8>().ok();
+                    let beat_unit = beat_type
+                        .trim()
+                        .parse::<u8>()
+                        .ok()
+                        .and_then(MusicXmlUtil::unit_from_beat_type);
+                    if let (Some(bar_beats), Some(beat_unit)) = (bar_beats, beat_unit) {
+                        if bar_beats > 0 {
+                            song.meta.signature = Signature::new(beat_unit, bar_beats);
+                        }
+                    }
+                }
+            }
+            "start_of_verse" | "sov" => {
+                Self::start_section(song, label_kind.unwrap_or(SectionKind::Verse));
+                song.in_section = true;
+            }
+            "start_of_chorus" | "soc" => {
+                Self::start_section(song, label_kind.unwrap_or(SectionKind::Chorus));
+                song.in_section = true;
+            }
+            "start_of_bridge" | "sob" => {
+                Self::start_section(song, label_kind.unwrap_or(SectionKind::Bridge));
+                song.in_section = true;
+            }
+            "start_of_tab" | "sot" => song.in_tab = true,
+            "end_of_tab" | "eot" => song.in_tab = false,
+            x if x.starts_with("end_of_") || x == "eov" || x == "eoc" || x == "eob" => {
+                song.in_section = false;
+            }
+            _ => {}
+        }
+    }
+    /// Chords without words are put on the next word in the line.
+    fn parse_line(song: &mut ChordProSong, line: &str) {
+        let bar_steps = Self::bar_steps(&song.meta);
+        let beat_steps = Self::beat_steps(&song.meta);
+        if song.starts.last().is_some() && !song.in_section {
+            Self::start_section(song, SectionKind::Verse);
+            song.in_section = true;
+        }
+        song.pos = song.pos.div_ceil(bar_steps) * bar_steps;
+        let mut pending: Vec<Chord> = Vec::new();
+        for token in line.split_whitespace() {
+            let syllables = Self::parse_token(&song.meta, token);
+            let last = syllables.iter().rposition(|x| !x.1.is_empty());
+            for (index, (chords, text)) in syllables.into_iter().enumerate() {
+                pending.extend(chords);
+                if text.is_empty() {
+                    continue;
+                }
+                let count = pending.len() as u32;
+                for (index, chord) in pending.drain(..).enumerate() {
+                    let offset = beat_steps * index as u32 / count;
+                    song.chords.push((song.pos + offset, chord));
+                }
+                let word = if Some(index) == last || text.ends_with('-') {
+                    text
+                } else {
+                    format!("{}-", text)
+                };
+                song.words.push(ImportSpan::new(
+                    song.pos,
+                    song.pos + beat_steps,
+                    ImportValue::Word(word),
+                    false,
+                ));
+                song.pos += beat_steps;
+            }
+        }
+        for chord in pending {
+            song.pos = song.pos.div_ceil(bar_steps) * bar_steps;
+            song.chords.push((song.pos, chord));
+            song.pos += bar_steps;
+        }
+    }
+    /// Splits the token by the chords in brackets, e.g. `rose[G]ma[A]ry` is
+    /// split into three syllables, with the chords in front of them.
+    fn parse_token(meta: &TabMeta, token: &str) -> Vec<(Vec<Chord>, String)> {
+        let mut syllables = vec![(Vec::new(), String::new())];
+        let mut rest = token;
+        while let Some(begin) = rest.find('[') {
+            let end = match rest[begin..].find(']') {
+                Some(end) => begin + end,
+                None => break,
+            };
+            if let Some(last) = syllables.last_mut() {
+                last.1.push_str(&rest[..begin]);
+            }
+            if let Some(chord) = ChordNameUtil::parse(meta, &rest[begin + 1..end]) {
+                match syllables.last_mut() {
+                    Some(last) if last.1.is_empty() => last.0.push(chord),
+                    _ => syllables.push((vec![chord], String::new())),
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+        if let Some(last) = syllables.last_mut() {
+            last.1.push_str(rest);
+        }
+        syllables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chord_pro_export::ChordProExporter;
+
+    const SONG: &str = r#"{title: Scarborough Fair}
+{key: Em}
+{time: 3/4}
+{tempo: 110}
+{start_of_verse}
+[Em]Are you [D]go-ing to
+[Em]Scar-bo-rough fair
+{end_of_verse}
+{start_of_chorus}
+[G]Pars-ley [Em]sage
+{end_of_chorus}
+"#;
+
+    fn get_track(tab: &Tab, kind: TrackKind) -> &Track {
+        tab.tracks.iter().find(|x| x.kind == kind).unwrap()
+    }
+
+    #[test]
+    fn test_import() {
+        let tab = ChordProImporter::default().import(SONG).unwrap();
+        assert_eq!(tab.title.as_deref(), Some("Scarborough Fair"));
+        assert_eq!(tab.meta.key, Key::Natural(PitchName::E));
+        assert_eq!(tab.meta.scale, Scale::Aeolian);
+        assert_eq!(tab.meta.signature, Signature::new(Unit::Quarter, 3));
+        assert_eq!(tab.meta.tempo, Tempo::Bpm(110));
+        let kinds: Vec<SectionKind> = tab.sections.iter().map(|x| x.kind.clone()).collect();
+        assert_eq!(kinds, vec![SectionKind::Verse, SectionKind::Chorus]);
+        let words: Vec<String> = get_track(&tab, TrackKind::Lyrics)
+            .entries
+            .iter()
+            .filter_map(|x| match x {
+                ProtoEntry::Lyric(LyricEntry::Word(word, _)) => Some(word.text.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            words,
+            vec![
+                "Are",
+                "you",
+                "go-ing",
+                "to",
+                "Scar-bo-rough",
+                "fair",
+                "Pars-ley",
+                "sage"
+            ]
+        );
+        let chords = get_track(&tab, TrackKind::Chord)
+            .entries
+            .iter()
+            .filter(|x| x.as_core().map(|x| x.is_chord()).unwrap_or(false))
+            .count();
+        // the D is repeated in the next bar
+        assert_eq!(chords, 6);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tab = ChordProImporter::default().import(SONG).unwrap();
+        let text = ChordProExporter::default().export(&tab).unwrap();
+        let round_trip = ChordProImporter::default().import(&text).unwrap();
+        assert_eq!(round_trip.title, tab.title);
+        assert_eq!(round_trip.meta, tab.meta);
+        for kind in [TrackKind::Chord, TrackKind::Lyrics] {
+            assert_eq!(
+                get_track(&round_trip, kind).entries,
+                get_track(&tab, kind).entries
+            );
+        }
+    }
+}
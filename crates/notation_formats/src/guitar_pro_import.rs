@@ -0,0 +1,1007 @@
+use fehler::{throw, throws};
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+use notation_proto::prelude::*;
+
+use crate::import_util::{ImportSpan, ImportUtil, ImportValue};
+use crate::midi_import::MidiImporter;
+
+#[derive(Error, Debug)]
+pub enum GuitarProImportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("unsupported version")]
+    UnsupportedVersion(String),
+    #[error("no tracks found")]
+    NoTracks,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct GpMeasureHeader {
+    numerator: u8,
+    denominator: u8,
+    key: Option<(i8, u8)>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct GpNote {
+    string: u8,
+    fret: u8,
+    tied: bool,
+}
+
+#[derive(Clone, Debug)]
+struct GpBeat {
+    begin: u32,
+    end: u32,
+    notes: Vec<GpNote>,
+    shape: Option<[Option<u8>; 7]>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct GpSong {
+    tempo: i32,
+    key: i8,
+    headers: Vec<GpMeasureHeader>,
+    tracks: Vec<GpTrack>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct GpTrack {
+    name: String,
+    percussion: bool,
+    tuning: Vec<i32>,
+    fret_count: i32,
+    capo: i32,
+    voices: Vec<Vec<GpBeat>>,
+    last_frets: HashMap<u8, u8>,
+}
+
+struct GpReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    version: u16,
+}
+
+impl<'a> GpReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            version: 0,
+        }
+    }
+    fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.pos)
+    }
+    #[throws(GuitarProImportError)]
+    fn read_bytes(&mut self, len: usize) -> &'a [u8] {
+        if len > self.remaining() {
+            throw!(GuitarProImportError::UnexpectedEnd);
+        }
+        let result = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        result
+    }
+    #[throws(GuitarProImportError)]
+    fn skip(&mut self, len: usize) {
+        self.read_bytes(len)?;
+    }
+    #[throws(GuitarProImportError)]
+    fn read_u8(&mut self) -> u8 {
+        self.read_bytes(1)?[0]
+    }
+    #[throws(GuitarProImportError)]
+    fn read_i8(&mut self) -> i8 {
+        self.read_u8()? as i8
+    }
+    #[throws(GuitarProImportError)]
+    fn read_bool(&mut self) -> bool {
+        self.read_u8()? != 0
+    }
+    #[throws(GuitarProImportError)]
+    fn read_i32(&mut self) -> i32 {
+        let bytes = self.read_bytes(4)?;
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+    /// Count of items, checked against the remaining bytes, so broken files
+    /// can't make us loop for too long.
+    #[throws(GuitarProImportError)]
+    fn read_count(&mut self) -> usize {
+        let count = self.read_i32()?.max(0) as usize;
+        if count > self.remaining() {
+            throw!(GuitarProImportError::UnexpectedEnd);
+        }
+        count
+    }
+    fn to_string(bytes: &[u8]) -> String {
+        bytes.iter().map(|x| *x as char).collect()
+    }
+    /// String with the length in a byte, padded to `size` bytes.
+    #[throws(GuitarProImportError)]
+    fn read_byte_size_string(&mut self, size: usize) -> String {
+        let len = self.read_u8()? as usize;
+        let bytes = self.read_bytes(size)?;
+        Self::to_string(&bytes[..len.min(size)])
+    }
+    #[throws(GuitarProImportError)]
+    fn read_int_size_string(&mut self) -> String {
+        let len = self.read_count()?;
+        Self::to_string(self.read_bytes(len)?)
+    }
+    /// String with the total size in an int, followed by the length in a byte.
+    #[throws(GuitarProImportError)]
+    fn read_int_byte_size_string(&mut self) -> String {
+        let size = self.read_count()?;
+        if size == 0 {
+            return String::new();
+        }
+        self.read_byte_size_string(size - 1)?
+    }
+}
+
+// https://github.com/Perlence/PyGuitarPro
+impl<'a> GpReader<'a> {
+    #[throws(GuitarProImportError)]
+    fn read_version(&mut self) {
+        let version = self.read_byte_size_string(30)?;
+        let number = version
+            .strip_prefix("FICHIER GUITAR PRO v")
+            .map(|x| x.replace('.', ""))
+            .and_then(|x| x.get(0..3).and_then(|y| y.parse::<u16>().ok()));
+        match number {
+            Some(x) if (300..600).contains(&x) => self.version = x,
+            _ => throw!(GuitarProImportError::UnsupportedVersion(version)),
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_info(&mut self) {
+        let fields = if self.version >= 500 { 9 } else { 8 };
+        for _ in 0..fields {
+            self.read_int_byte_size_string()?;
+        }
+        for _ in 0..self.read_count()? {
+            self.read_int_byte_size_string()?;
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_lyrics(&mut self) {
+        self.read_i32()?;
+        for _ in 0..5 {
+            self.read_i32()?;
+            self.read_int_size_string()?;
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_page_setup(&mut self) {
+        self.skip(30)?;
+        for _ in 0..10 {
+            self.read_int_byte_size_string()?;
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_measure_header(&mut self, previous: Option<GpMeasureHeader>) -> GpMeasureHeader {
+        let mut header = previous.unwrap_or(GpMeasureHeader {
+            numerator: 4,
+            denominator: 4,
+            key: None,
+        });
+        header.key = None;
+        if previous.is_some() && self.version >= 500 {
+            self.skip(1)?;
+        }
+        let flags = self.read_u8()?;
+        if flags & 0x01 != 0 {
+            header.numerator = self.read_u8()?;
+        }
+        if flags & 0x02 != 0 {
+            header.denominator = self.read_u8()?;
+        }
+        if flags & 0x08 != 0 {
+            self.skip(1)?;
+        }
+        if flags & 0x10 != 0 && self.version < 500 {
+            self.skip(1)?;
+        }
+        if flags & 0x20 != 0 {
+            self.read_int_byte_size_string()?;
+            self.skip(4)?;
+        }
+        if flags & 0x40 != 0 {
+            header.key = Some((self.read_i8()?, self.read_u8()?));
+        }
+        if self.version >= 500 {
+            if flags & 0x10 != 0 {
+                self.skip(1)?;
+            }
+            if flags & 0x03 != 0 {
+                self.skip(4)?;
+            }
+            if flags & 0x10 == 0 {
+                self.skip(1)?;
+            }
+            self.skip(1)?;
+        }
+        header
+    }
+    #[throws(GuitarProImportError)]
+    fn read_track(&mut self, index: usize) -> GpTrack {
+        if self.version >= 500 && (index == 0 || self.version == 500) {
+            self.skip(1)?;
+        }
+        let flags = self.read_u8()?;
+        let name = self.read_byte_size_string(40)?;
+        let string_count = self.read_i32()?.clamp(0, 7) as usize;
+        let mut tuning = Vec::new();
+        for string in 0..7 {
+            let note = self.read_i32()?;
+            if string < string_count {
+                tuning.push(note);
+            }
+        }
+        let _port = self.read_i32()?;
+        let channel = self.read_i32()?;
+        let _effect_channel = self.read_i32()?;
+        let fret_count = self.read_i32()?;
+        let capo = self.read_i32()?;
+        self.skip(4)?;
+        if self.version >= 500 {
+            self.skip(41)?;
+            if self.version == 500 {
+                self.skip(3)?;
+            } else {
+                self.skip(8)?;
+                self.read_int_byte_size_string()?;
+                self.read_int_byte_size_string()?;
+            }
+        }
+        GpTrack {
+            name: name.trim().to_string(),
+            percussion: flags & 0x01 != 0 || channel == 10,
+            tuning,
+            fret_count,
+            capo,
+            ..Default::default()
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_bend(&mut self) {
+        self.skip(5)?;
+        let points = self.read_count()?;
+        self.skip(points * 9)?;
+    }
+    /// Returns the frets from the first string, absolute to the nut.
+    #[throws(GuitarProImportError)]
+    fn read_chord(&mut self) -> Option<[Option<u8>; 7]> {
+        let (fret_count, tail) = if self.version >= 500 {
+            self.skip(17)?;
+            self.read_byte_size_string(21)?;
+            self.skip(4)?;
+            (7, 32)
+        } else if self.read_bool()? {
+            if self.version >= 400 {
+                self.skip(16)?;
+                self.read_byte_size_string(21)?;
+                self.skip(4)?;
+                (7, 32)
+            } else {
+                self.skip(25)?;
+                self.read_byte_size_string(34)?;
+                (6, 36)
+            }
+        } else {
+            self.read_int_byte_size_string()?;
+            (if self.version >= 406 { 7 } else { 6 }, 0)
+        };
+        let first_fret = self.read_i32()?;
+        if tail == 0 && first_fret == 0 {
+            return None;
+        }
+        let mut frets = [None; 7];
+        for fret in frets.iter_mut().take(fret_count) {
+            let value = self.read_i32()?;
+            if value >= 0 {
+                *fret = Some(value.min(u8::MAX as i32) as u8);
+            }
+        }
+        self.skip(tail)?;
+        if frets.iter().all(|x| x.is_none()) {
+            None
+        } else {
+            Some(frets)
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_beat_effects(&mut self) {
+        if self.version >= 400 {
+            let flags1 = self.read_u8()?;
+            let flags2 = self.read_u8()?;
+            if flags1 & 0x20 != 0 {
+                self.skip(1)?;
+            }
+            if flags2 & 0x04 != 0 {
+                self.read_bend()?;
+            }
+            if flags1 & 0x40 != 0 {
+                self.skip(2)?;
+            }
+            if flags2 & 0x02 != 0 {
+                self.skip(1)?;
+            }
+        } else {
+            let flags = self.read_u8()?;
+            if flags & 0x20 != 0 {
+                self.skip(5)?;
+            }
+            if flags & 0x40 != 0 {
+                self.skip(2)?;
+            }
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_mix_table_change(&mut self) {
+        self.skip(1)?;
+        if self.version >= 500 {
+            // RSE instrument, with an extra blank byte in 5.00
+            self.skip(16)?;
+        }
+        let mut values = Vec::new();
+        for _ in 0..6 {
+            values.push(self.read_i8()?);
+        }
+        if self.version >= 500 {
+            self.read_int_byte_size_string()?;
+        }
+        let tempo = self.read_i32()?;
+        for value in values {
+            if value >= 0 {
+                self.skip(1)?;
+            }
+        }
+        if tempo >= 0 {
+            self.skip(if self.version > 500 { 2 } else { 1 })?;
+        }
+        if self.version >= 400 {
+            self.skip(1)?;
+        }
+        if self.version >= 500 {
+            self.skip(1)?;
+        }
+        if self.version > 500 {
+            self.read_int_byte_size_string()?;
+            self.read_int_byte_size_string()?;
+        }
+    }
+    #[throws(GuitarProImportError)]
+    fn read_note_effects(&mut self) {
+        let flags1 = self.read_u8()?;
+        let flags2 = if self.version >= 400 {
+            self.read_u8()?
+        } else {
+            0
+        };
+        if flags1 & 0x01 != 0 {
+            self.read_bend()?;
+        }
+        if flags1 & 0x10 != 0 {
+            self.skip(if self.version >= 500 { 5 } else { 4 })?;
+        }
+        if flags2 & 0x04 != 0 {
+            self.skip(1)?;
+        }
+        if flags2 & 0x08 != 0 {
+            self.skip(1)?;
+        }
+        if flags2 & 0x10 != 0 {
+            let harmonic = self.read_i8()?;
+            if self.version >= 500 {
+                match harmonic {
+                    2 => self.skip(3)?,
+                    3 => self.skip(1)?,
+                    _ => {}
+                }
+            }
+        }
+        if flags2 & 0x20 != 0 {
+            self.skip(2)?;
+        }
+    }
+    /// Returns the fret and the note type (1 normal, 2 tied, 3 dead).
+    #[throws(GuitarProImportError)]
+    fn read_note(&mut self) -> (i8, u8) {
+        let flags = self.read_u8()?;
+        let note_type = if flags & 0x20 != 0 {
+            self.read_u8()?
+        } else {
+            1
+        };
+        if flags & 0x01 != 0 && self.version < 500 {
+            self.skip(2)?;
+        }
+        if flags & 0x10 != 0 {
+            self.skip(1)?;
+        }
+        let fret = if flags & 0x20 != 0 {
+            self.read_i8()?
+        } else {
+            0
+        };
+        if flags & 0x80 != 0 {
+            self.skip(2)?;
+        }
+        if self.version >= 500 {
+            if flags & 0x01 != 0 {
+                self.skip(8)?;
+            }
+            self.skip(1)?;
+        }
+        if flags & 0x08 != 0 {
+            self.read_note_effects()?;
+        }
+        (fret, note_type)
+    }
+    /// Returns the length of the beat.
+    #[throws(GuitarProImportError)]
+    fn read_beat(
+        &mut self,
+        track: &mut GpTrack,
+        voice: usize,
+        bar_begin: u32,
+        pos: Rational,
+    ) -> Rational {
+        let flags = self.read_u8()?;
+        if flags & 0x40 != 0 {
+            self.skip(1)?;
+        }
+        let value = self.read_i8()?.clamp(-2, 4);
+        let mut beat_length = Rational::new(1, 1 << (value + 2));
+        if flags & 0x01 != 0 {
+            beat_length = beat_length * Rational::new(3, 2);
+        }
+        if flags & 0x20 != 0 {
+            let tuplet = self.read_i32()?;
+            let normal = match tuplet {
+                3 => 2,
+                5..=7 => 4,
+                9..=13 => 8,
+                _ => tuplet,
+            };
+            if tuplet > 0 {
+                beat_length = beat_length * Rational::new(normal as i64, tuplet as i64);
+            }
+        }
+        let shape = if flags & 0x02 != 0 {
+            self.read_chord()?
+        } else {
+            None
+        };
+        if flags & 0x04 != 0 {
+            self.read_int_byte_size_string()?;
+        }
+        if flags & 0x08 != 0 {
+            self.read_beat_effects()?;
+        }
+        if flags & 0x10 != 0 {
+            self.read_mix_table_change()?;
+        }
+        let string_flags = self.read_u8()?;
+        let mut notes = Vec::new();
+        for string in 1..=track.tuning.len() as u8 {
+            if string_flags & (1 << (7 - string)) == 0 {
+                continue;
+            }
+            let (fret, note_type) = self.read_note()?;
+            let fret = match note_type {
+                2 => track.last_frets.get(&string).copied().unwrap_or(0),
+                3 => continue,
+                _ => fret.max(0) as u8,
+            };
+            track.last_frets.insert(string, fret);
+            notes.push(GpNote {
+                string,
+                fret,
+                tied: note_type == 2,
+            });
+        }
+        if self.version >= 500 {
+            let flags2 = self.read_u8()? as u16 | (self.read_u8()? as u16) << 8;
+            if flags2 & 0x0800 != 0 {
+                self.skip(1)?;
+            }
+        }
+        let begin = bar_begin + MidiImporter::duration_steps(Units::from(pos));
+        let end = bar_begin + MidiImporter::duration_steps(Units::from(pos + beat_length));
+        if track.voices.len() <= voice {
+            track.voices.resize(voice + 1, Vec::new());
+        }
+        track.voices[voice].push(GpBeat {
+            begin,
+            end,
+            notes,
+            shape,
+        });
+        beat_length
+    }
+}
+
+impl<'a> GpReader<'a> {
+    #[throws(GuitarProImportError)]
+    fn read_song(&mut self) -> GpSong {
+        self.read_version()?;
+        self.read_info()?;
+        if self.version < 500 {
+            // triplet feel
+            self.skip(1)?;
+        }
+        if self.version >= 400 {
+            self.read_lyrics()?;
+        }
+        if self.version > 500 {
+            // RSE master effect
+            self.skip(19)?;
+        }
+        if self.version >= 500 {
+            self.read_page_setup()?;
+            self.read_int_byte_size_string()?;
+        }
+        let tempo = self.read_i32()?;
+        if self.version > 500 {
+            self.skip(1)?;
+        }
+        let key = self.read_i8()?;
+        self.skip(if self.version >= 400 { 4 } else { 3 })?;
+        // midi channels
+        self.skip(64 * 12)?;
+        if self.version >= 500 {
+            // directions and master reverb
+            self.skip(19 * 2 + 4)?;
+        }
+        let measure_count = self.read_count()?;
+        let track_count = self.read_count()?;
+        let mut headers: Vec<GpMeasureHeader> = Vec::new();
+        for _ in 0..measure_count {
+            headers.push(self.read_measure_header(headers.last().copied())?);
+        }
+        let mut tracks = Vec::new();
+        for index in 0..track_count {
+            tracks.push(self.read_track(index)?);
+        }
+        if self.version >= 500 {
+            self.skip(if self.version == 500 { 2 } else { 1 })?;
+        }
+        let bar_begins = GuitarProImporter::calc_bar_begins(&headers);
+        let voice_count = if self.version >= 500 { 2 } else { 1 };
+        for &bar_begin in bar_begins.iter().take(headers.len()) {
+            for track in tracks.iter_mut() {
+                for voice in 0..voice_count {
+                    let mut pos = Rational::ZERO;
+                    for _ in 0..self.read_count()? {
+                        pos += self.read_beat(track, voice, bar_begin, pos)?;
+                    }
+                }
+                if self.version >= 500 {
+                    // line break
+                    self.skip(1)?;
+                }
+            }
+        }
+        GpSong {
+            tempo,
+            key,
+            headers,
+            tracks,
+        }
+    }
+}
+
+/// Imports Guitar Pro 3, 4 and 5 files, 6 strings tracks are mapped to guitar
+/// tracks, 4 strings tracks to bass tracks, others into tones.
+#[derive(Copy, Clone, Debug)]
+pub struct GuitarProImporter {
+    pub bars_per_section: usize,
+}
+
+impl Default for GuitarProImporter {
+    fn default() -> Self {
+        Self {
+            bars_per_section: 4,
+        }
+    }
+}
+
+impl GuitarProImporter {
+    #[throws(GuitarProImportError)]
+    pub fn import_file<P: AsRef<Path>>(&self, path: P) -> Tab {
+        let bytes = std::fs::read(path)?;
+        self.import(&bytes)?
+    }
+    #[throws(GuitarProImportError)]
+    pub fn import(&self, bytes: &[u8]) -> Tab {
+        let song = GpReader::new(bytes).read_song()?;
+        if song.tracks.is_empty() {
+            throw!(GuitarProImportError::NoTracks);
+        }
+        let meta = Self::calc_meta(&song);
+        let bar_begins = Self::calc_bar_begins(&song.headers);
+        let signatures = Self::calc_signatures(&song.headers);
+        let beat_steps = std::cmp::max(
+            MidiImporter::duration_steps(Units::from(meta.signature.beat_unit)),
+            MidiImporter::STRAIGHT_STEPS * MidiImporter::TRIPLET_STEPS,
+        );
+        let total_steps = bar_begins.last().copied().unwrap_or(0);
+        let mut tracks: Vec<Track> = Vec::new();
+        let mut layers: Vec<Vec<BarLayer>> = vec![Vec::new(); song.headers.len()];
+        for (index, gp_track) in song.tracks.iter().enumerate() {
+            let name = if gp_track.name.is_empty() {
+                format!("track{}", index + 1)
+            } else {
+                gp_track.name.clone()
+            };
+            let id = ImportUtil::calc_track_id(&name, &tracks);
+            let mut entries = Vec::new();
+            let (kind, voices) = match (gp_track.percussion, gp_track.tuning.len()) {
+                (true, _) => (
+                    TrackKind::Drums,
+                    Self::calc_tone_spans(&meta, gp_track, true),
+                ),
+                (false, 6) => {
+                    let (total_fret_num, capo) = Self::calc_frets(gp_track);
+                    let string_notes = Self::calc_string_notes(gp_track);
+                    let fretboard = Fretboard6::new(total_fret_num, string_notes, capo);
+                    entries.push(ProtoEntry::from(FrettedEntry6::Fretboard(fretboard)));
+                    (
+                        TrackKind::Guitar,
+                        Self::calc_fretted_spans(gp_track, 6, total_steps),
+                    )
+                }
+                (false, 4) => {
+                    let (total_fret_num, capo) = Self::calc_frets(gp_track);
+                    let string_notes = Self::calc_string_notes(gp_track);
+                    let fretboard = Fretboard4::new(total_fret_num, string_notes, capo);
+                    entries.push(ProtoEntry::from(FrettedEntry4::Fretboard(fretboard)));
+                    (
+                        TrackKind::Bass,
+                        Self::calc_fretted_spans(gp_track, 4, total_steps),
+                    )
+                }
+                (false, 5) => {
+                    let (total_fret_num, capo) = Self::calc_frets(gp_track);
+                    let string_notes = Self::calc_string_notes(gp_track);
+                    let fretboard = Fretboard5::new(total_fret_num, string_notes, capo);
+                    entries.push(ProtoEntry::from(FrettedEntry5::Fretboard(fretboard)));
+                    // 5-string banjos have a high 5th string, basses don't
+                    let kind = if fretboard.string_notes[4] > fretboard.string_notes[3] {
+                        TrackKind::Banjo
+                    } else {
+                        TrackKind::Bass
+                    };
+                    (kind, Self::calc_fretted_spans(gp_track, 5, total_steps))
+                }
+                (false, 7) => {
+                    let (total_fret_num, capo) = Self::calc_frets(gp_track);
+                    let string_notes = Self::calc_string_notes(gp_track);
+                    let fretboard = Fretboard7::new(total_fret_num, string_notes, capo);
+                    entries.push(ProtoEntry::from(FrettedEntry7::Fretboard(fretboard)));
+                    (
+                        TrackKind::Guitar,
+                        Self::calc_fretted_spans(gp_track, 7, total_steps),
+                    )
+                }
+                _ => (
+                    TrackKind::Piano,
+                    Self::calc_tone_spans(&meta, gp_track, false),
+                ),
+            };
+            // the signature changes only need to be in one track
+            ImportUtil::push_measures(
+                &mut entries,
+                &mut layers,
+                &id,
+                &voices,
+                &bar_begins,
+                if tracks.is_empty() {
+                    &signatures[..]
+                } else {
+                    &[]
+                },
+                beat_steps,
+            );
+            tracks.push(Track::new(id, kind, entries));
+        }
+        let importer = MidiImporter {
+            bars_per_section: self.bars_per_section,
+        };
+        let (sections, form) = importer.new_sections(layers);
+        Tab::new(Tab::new_uuid().as_str(), meta, tracks, sections, form)
+    }
+}
+
+impl GuitarProImporter {
+    fn calc_signature(header: &GpMeasureHeader) -> Signature {
+        let beat_unit = match header.denominator {
+            1 => Unit::Whole,
+            2 => Unit::Half,
+            8 => Unit::Eighth,
+            16 => Unit::Sixteenth,
+            32 => Unit::ThirtySecondth,
+            64 => Unit::SixtyFourth,
+            _ => Unit::Quarter,
+        };
+        Signature::new(beat_unit, header.numerator.max(1))
+    }
+    fn calc_bar_steps(header: &GpMeasureHeader) -> u32 {
+        MidiImporter::duration_steps(Units::from(Self::calc_signature(header)))
+    }
+    /// The begin of each measure, followed by the end of the last one.
+    fn calc_bar_begins(headers: &[GpMeasureHeader]) -> Vec<u32> {
+        let mut bar_begins = vec![0];
+        let mut bar_begin = 0;
+        for header in headers.iter() {
+            bar_begin += Self::calc_bar_steps(header);
+            bar_begins.push(bar_begin);
+        }
+        bar_begins
+    }
+    /// The measures where the signature is different from the previous one.
+    fn calc_signatures(headers: &[GpMeasureHeader]) -> Vec<(usize, MetaEntry)> {
+        headers
+            .windows(2)
+            .enumerate()
+            .map(|(index, x)| {
+                (
+                    index + 1,
+                    Self::calc_signature(&x[0]),
+                    Self::calc_signature(&x[1]),
+                )
+            })
+            .filter(|x| x.1 != x.2)
+            .map(|x| (x.0, MetaEntry::Signature(x.2)))
+            .collect()
+    }
+    fn calc_meta(song: &GpSong) -> TabMeta {
+        let mut meta = TabMeta::default();
+        if let Some(header) = song.headers.first() {
+            meta.signature = Self::calc_signature(header);
+        }
+        let (sharps, minor) = song
+            .headers
+            .first()
+            .and_then(|x| x.key)
+            .unwrap_or((song.key, 0));
+        meta.scale = if minor == 1 {
+            Scale::Aeolian
+        } else {
+            Scale::Ionian
+        };
+        meta.key = meta.scale.calc_key_from_signature(sharps);
+        let quarters_per_minute = song.tempo.max(1) as f32;
+        let bpm = quarters_per_minute / (Units::from(meta.signature.beat_unit).0 * 4.0);
+        meta.tempo = Tempo::Bpm(bpm.round().clamp(1.0, u16::MAX as f32) as u16);
+        meta
+    }
+    fn calc_frets(track: &GpTrack) -> (usize, u8) {
+        let total_fret_num = track.fret_count.clamp(1, 36);
+        let capo = track.capo.clamp(0, total_fret_num - 1);
+        (total_fret_num as usize, capo as u8)
+    }
+    fn calc_semitones(midi_key: i32) -> Semitones {
+        Semitones((midi_key - 12).clamp(0, 127) as i8)
+    }
+    /// Tuning in guitar pro files starts from the first (highest) string.
+    fn calc_string_notes<const N: usize>(track: &GpTrack) -> [Semitones; N] {
+        let mut string_notes = [Semitones(0); N];
+        for (index, midi_key) in track.tuning.iter().take(N).enumerate() {
+            string_notes[index] = Self::calc_semitones(*midi_key);
+        }
+        string_notes
+    }
+    fn calc_voice_spans<F>(beats: &[GpBeat], mut calc_value: F) -> Vec<ImportSpan>
+    where
+        F: FnMut(&GpBeat) -> Option<ImportValue>,
+    {
+        let mut spans = Vec::new();
+        for (index, beat) in beats.iter().enumerate() {
+            let end = match beats.get(index + 1) {
+                Some(next) => std::cmp::min(beat.end, next.begin),
+                None => beat.end,
+            };
+            if beat.begin >= end || beat.notes.is_empty() {
+                continue;
+            }
+            if let Some(value) = calc_value(beat) {
+                let tied = beat.notes.iter().all(|x| x.tied);
+                spans.push(ImportSpan::new(beat.begin, end, value, tied));
+            }
+        }
+        spans
+    }
+    fn calc_tone_spans(meta: &TabMeta, track: &GpTrack, percussion: bool) -> Vec<Vec<ImportSpan>> {
+        track
+            .voices
+            .iter()
+            .map(|beats| {
+                Self::calc_voice_spans(beats, |beat| {
+                    let notes: Vec<Note> = beat
+                        .notes
+                        .iter()
+                        .filter_map(|x| {
+                            let midi_key = if percussion {
+                                x.fret as i32
+                            } else {
+                                track.tuning.get(x.string as usize - 1)? + x.fret as i32
+                            };
+                            let semitones = Self::calc_semitones(midi_key);
+                            Some(meta.scale.calc_note_from_semitones(&meta.key, semitones))
+                        })
+                        .collect();
+                    Some(ImportValue::Tone(notes))
+                })
+            })
+            .collect()
+    }
+    /// Picks are using explicit frets, the chord diagrams are added as shapes,
+    /// which last until the next diagram.
+    fn calc_fretted_spans(track: &GpTrack, strings: u8, total_steps: u32) -> Vec<Vec<ImportSpan>> {
+        let mut voices: Vec<Vec<ImportSpan>> = track
+            .voices
+            .iter()
+            .map(|beats| {
+                Self::calc_voice_spans(beats, |beat| {
+                    let notes: Vec<PickNote> = beat
+                        .notes
+                        .iter()
+                        .filter(|x| x.string <= strings)
+                        .map(|x| PickNote::new_string_fret(x.string, x.fret))
+                        .collect();
+                    match strings {
+                        4 => Some(ImportValue::Pick4(notes)),
+                        5 => Some(ImportValue::Pick5(notes)),
+                        7 => Some(ImportValue::Pick7(notes)),
+                        _ => Some(ImportValue::Pick6(notes)),
+                    }
+                })
+            })
+            .collect();
+        let mut shapes: Vec<(u32, [Option<u8>; 7])> = track
+            .voices
+            .iter()
+            .flat_map(|x| x.iter())
+            .filter_map(|x| x.shape.map(|shape| (x.begin, shape)))
+            .collect();
+        shapes.sort_by_key(|x| x.0);
+        shapes.dedup_by_key(|x| x.0);
+        let mut shape_spans = Vec::new();
+        for (index, (begin, frets)) in shapes.iter().enumerate() {
+            let end = shapes.get(index + 1).map(|x| x.0).unwrap_or(total_steps);
+            let value = match strings {
+                4 => {
+                    ImportValue::Shape4(HandShape4::from([frets[0], frets[1], frets[2], frets[3]]))
+                }
+                5 => ImportValue::Shape5(HandShape5::from([
+                    frets[0], frets[1], frets[2], frets[3], frets[4],
+                ])),
+                7 => ImportValue::Shape7(HandShape7::from(*frets)),
+                _ => ImportValue::Shape6(HandShape6::from([
+                    frets[0], frets[1], frets[2], frets[3], frets[4], frets[5],
+                ])),
+            };
+            if *begin < end {
+                shape_spans.push(ImportSpan::new(*begin, end, value, false));
+            }
+        }
+        if !shape_spans.is_empty() {
+            voices.push(shape_spans);
+        }
+        voices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music_xml_export::MusicXmlExporter;
+    use crate::music_xml_import::MusicXmlImporter;
+
+    fn push_i32(bytes: &mut Vec<u8>, value: i32) {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    fn push_string(bytes: &mut Vec<u8>, text: &str, size: usize) {
+        bytes.push(text.len() as u8);
+        bytes.extend_from_slice(text.as_bytes());
+        bytes.resize(bytes.len() + size - text.len(), 0);
+    }
+    /// Beat of (string, fret, note type) from the first string, `value` is 0
+    /// for quarter, -1 for half.
+    fn push_beat(bytes: &mut Vec<u8>, value: i8, notes: &[(u8, i8, u8)]) {
+        bytes.extend_from_slice(&[0, value as u8]);
+        bytes.push(notes.iter().fold(0, |flags, x| flags | 1 << (7 - x.0)));
+        for (_, fret, note_type) in notes.iter() {
+            bytes.extend_from_slice(&[0x20, *note_type, *fret as u8]);
+        }
+    }
+    /// A Guitar Pro 3 file with one guitar track of two 3/4 measures, in G at 90.
+    fn new_gp3() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        push_string(&mut bytes, "FICHIER GUITAR PRO v3.00", 30);
+        for _ in 0..8 {
+            push_i32(&mut bytes, 0);
+        }
+        push_i32(&mut bytes, 0);
+        bytes.push(0);
+        push_i32(&mut bytes, 90);
+        bytes.extend_from_slice(&[1, 0, 0, 0]);
+        bytes.resize(bytes.len() + 64 * 12, 0);
+        push_i32(&mut bytes, 2);
+        push_i32(&mut bytes, 1);
+        bytes.extend_from_slice(&[0x03, 3, 4]);
+        bytes.push(0);
+        bytes.push(0);
+        push_string(&mut bytes, "guitar", 40);
+        push_i32(&mut bytes, 6);
+        for midi_key in [64, 59, 55, 50, 45, 40, 0] {
+            push_i32(&mut bytes, midi_key);
+        }
+        for value in [1, 1, 2, 24, 0, 0] {
+            push_i32(&mut bytes, value);
+        }
+        push_i32(&mut bytes, 3);
+        push_beat(&mut bytes, 0, &[(6, 3, 1)]);
+        push_beat(&mut bytes, 0, &[(4, 0, 1)]);
+        push_beat(&mut bytes, 0, &[(1, 3, 1), (2, 0, 1)]);
+        push_i32(&mut bytes, 2);
+        push_beat(&mut bytes, -1, &[(5, 2, 1)]);
+        push_beat(&mut bytes, 0, &[(5, 0, 2)]);
+        bytes
+    }
+
+    fn get_picks(tab: &Tab) -> Vec<ProtoEntry> {
+        tab.tracks[0]
+            .entries
+            .iter()
+            .filter(|x| !matches!(x.as_fretted6(), Some(FrettedEntry6::Fretboard(_))))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_import_gp3() {
+        let tab = GuitarProImporter::default().import(&new_gp3()).unwrap();
+        assert_eq!(tab.meta.key, Key::Natural(PitchName::G));
+        assert_eq!(tab.meta.signature, Signature::new(Unit::Quarter, 3));
+        assert_eq!(tab.meta.tempo, Tempo::Bpm(90));
+        assert_eq!(tab.tracks.len(), 1);
+        assert_eq!(tab.tracks[0].kind, TrackKind::Guitar);
+        let pick = |notes: &[(u8, u8)], duration| {
+            let notes: Vec<PickNote> = notes
+                .iter()
+                .map(|x| PickNote::new_string_fret(x.0, x.1))
+                .collect();
+            ProtoEntry::from(FrettedEntry6::Pick(Pick::from(notes), duration))
+        };
+        let quarter = Duration::Simple(Unit::Quarter);
+        assert_eq!(
+            get_picks(&tab),
+            vec![
+                pick(&[(6, 3)], quarter),
+                pick(&[(4, 0)], quarter),
+                pick(&[(1, 3), (2, 0)], quarter),
+                pick(&[(5, 2)], Duration::Simple(Unit::Half)),
+                ProtoEntry::from(CoreEntry::Tie),
+                pick(&[(5, 2)], quarter),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tab = GuitarProImporter::default().import(&new_gp3()).unwrap();
+        let xml = MusicXmlExporter::default().export(&tab).unwrap();
+        let round_trip = MusicXmlImporter::default().import(&xml).unwrap();
+        assert_eq!(round_trip.meta, tab.meta);
+        assert_eq!(get_picks(&round_trip), get_picks(&tab));
+    }
+}
@@ -0,0 +1,246 @@
+use notation_proto::prelude::*;
+
+use crate::midi_import::MidiImporter;
+
+/// Values of the imported entries, the duration is set when the spans are
+/// split into bars.
+#[derive(Clone, Debug)]
+pub enum ImportValue {
+    Tone(Vec<Note>),
+    Pick6(Vec<PickNote>),
+    Pick4(Vec<PickNote>),
+    Pick5(Vec<PickNote>),
+    Pick7(Vec<PickNote>),
+    Shape6(HandShape6),
+    Shape4(HandShape4),
+    Shape5(HandShape5),
+    Shape7(HandShape7),
+    Word(String),
+    Chord(Chord),
+}
+
+impl ImportValue {
+    pub fn to_entry(&self, duration: Duration) -> ProtoEntry {
+        match self {
+            Self::Tone(notes) => CoreEntry::Tone(Tone::from(notes.clone()), duration).into(),
+            Self::Pick6(notes) => FrettedEntry6::Pick(Pick::from(notes.clone()), duration).into(),
+            Self::Pick4(notes) => FrettedEntry4::Pick(Pick::from(notes.clone()), duration).into(),
+            Self::Pick5(notes) => FrettedEntry5::Pick(Pick::from(notes.clone()), duration).into(),
+            Self::Pick7(notes) => FrettedEntry7::Pick(Pick::from(notes.clone()), duration).into(),
+            Self::Shape6(shape) => FrettedEntry6::Shape(*shape, duration).into(),
+            Self::Shape4(shape) => FrettedEntry4::Shape(*shape, duration).into(),
+            Self::Shape5(shape) => FrettedEntry5::Shape(*shape, duration).into(),
+            Self::Shape7(shape) => FrettedEntry7::Shape(*shape, duration).into(),
+            Self::Word(text) => LyricEntry::Word(LyricWord::from(text.clone()), duration).into(),
+            Self::Chord(chord) => CoreEntry::Chord(*chord, duration).into(),
+        }
+    }
+    /// Chords and shapes are repeated in following bars, instead of being tied.
+    pub fn is_tieable(&self) -> bool {
+        !matches!(
+            self,
+            Self::Shape6(_) | Self::Shape4(_) | Self::Shape5(_) | Self::Shape7(_) | Self::Chord(_)
+        )
+    }
+}
+
+/// Span of steps in the grid of `MidiImporter::GRID_STEPS` per whole note,
+/// from the beginning of the tab.
+#[derive(Clone, Debug)]
+pub struct ImportSpan {
+    pub begin: u32,
+    pub end: u32,
+    pub value: ImportValue,
+    pub tied: bool,
+}
+
+impl ImportSpan {
+    pub fn new(begin: u32, end: u32, value: ImportValue, tied: bool) -> Self {
+        Self {
+            begin,
+            end,
+            value,
+            tied,
+        }
+    }
+}
+
+pub struct ImportUtil();
+
+impl ImportUtil {
+    pub fn calc_track_id(name: &str, tracks: &[Track]) -> String {
+        if tracks.iter().any(|x| x.id == name) {
+            format!("{}_{}", name, tracks.len() + 1)
+        } else {
+            name.to_string()
+        }
+    }
+    /// Each voice is added as a separate slice in the bar.
+    pub fn push_bars(
+        entries: &mut Vec<ProtoEntry>,
+        layers: &mut [Vec<BarLayer>],
+        id: &str,
+        voices: &[Vec<ImportSpan>],
+        bar_steps: u32,
+        beat_steps: u32,
+    ) {
+        let bar_begins: Vec<u32> = (0..=layers.len() as u32).map(|x| x * bar_steps).collect();
+        Self::push_measures(entries, layers, id, voices, &bar_begins, &[], beat_steps);
+    }
+    /// Same as `push_bars()` for bars of different lengths, `bar_begins` has
+    /// the begin of each bar followed by the end of the last one, meta changes
+    /// are added at the beginning of the first slice of their bars.
+    pub fn push_measures(
+        entries: &mut Vec<ProtoEntry>,
+        layers: &mut [Vec<BarLayer>],
+        id: &str,
+        voices: &[Vec<ImportSpan>],
+        bar_begins: &[u32],
+        metas: &[(usize, MetaEntry)],
+        beat_steps: u32,
+    ) {
+        for (bar_index, layer) in layers.iter_mut().enumerate() {
+            let bar_begin = bar_begins[bar_index];
+            let bar_end = bar_begins[bar_index + 1];
+            let mut slices = Vec::new();
+            for (voice_index, spans) in voices.iter().enumerate() {
+                let in_bar = spans.iter().any(|x| x.begin < bar_end && x.end > bar_begin);
+                if voice_index == 0 {
+                    let first_index = entries.len();
+                    for (_, meta) in metas.iter().filter(|x| x.0 == bar_index) {
+                        entries.push(ProtoEntry::from(*meta));
+                    }
+                    Self::push_bar_spans(entries, spans, bar_begin, bar_end, beat_steps);
+                    slices.push(Slice::new(
+                        SliceBegin::Index(first_index),
+                        SliceEnd::Count(entries.len() - first_index),
+                        None,
+                    ));
+                } else if in_bar {
+                    slices.push(Self::push_bar_spans(
+                        entries, spans, bar_begin, bar_end, beat_steps,
+                    ));
+                }
+            }
+            layer.push(BarLayer::new(id.to_string(), slices));
+        }
+    }
+    /// Spans fitting a single duration are kept as notated, others are split at
+    /// beats and bars, and written as tied entries, values can't be tied are
+    /// only split if not fitting a single duration in the bar.
+    pub fn push_bar_spans(
+        entries: &mut Vec<ProtoEntry>,
+        spans: &[ImportSpan],
+        bar_begin: u32,
+        bar_end: u32,
+        beat_steps: u32,
+    ) -> Slice {
+        let first_index = entries.len();
+        let mut pos = bar_begin;
+        for span in spans.iter() {
+            let begin = std::cmp::max(span.begin, pos);
+            let end = std::cmp::min(span.end, bar_end);
+            if begin >= end {
+                continue;
+            }
+            if begin > pos {
+                MidiImporter::push_rests(entries, pos, begin, bar_begin, beat_steps);
+            }
+            let durations = match MidiImporter::calc_duration(end - begin) {
+                Some(duration)
+                    if !span.value.is_tieable() || (begin == span.begin && end == span.end) =>
+                {
+                    vec![duration]
+                }
+                _ => MidiImporter::split_span(begin, end, bar_begin, beat_steps),
+            };
+            for (index, duration) in durations.into_iter().enumerate() {
+                let tied = index > 0 || span.tied || span.begin < bar_begin;
+                if tied && span.value.is_tieable() {
+                    entries.push(ProtoEntry::from(CoreEntry::Tie));
+                }
+                entries.push(span.value.to_entry(duration));
+            }
+            pos = end;
+        }
+        if bar_end > pos {
+            MidiImporter::push_rests(entries, pos, bar_end, bar_begin, beat_steps);
+        }
+        Slice::new(
+            SliceBegin::Index(first_index),
+            SliceEnd::Count(entries.len() - first_index),
+            None,
+        )
+    }
+    /// Chords last until the next one, the same chord is repeated in
+    /// following bars.
+    pub fn calc_chord_spans(mut harmonies: Vec<(u32, Chord)>, total_steps: u32) -> Vec<ImportSpan> {
+        harmonies.sort_by_key(|x| x.0);
+        harmonies.dedup_by_key(|x| x.0);
+        let mut spans = Vec::new();
+        for index in 0..harmonies.len() {
+            let (begin, chord) = harmonies[index];
+            let end = harmonies.get(index + 1).map(|x| x.0).unwrap_or(total_steps);
+            if begin < end {
+                spans.push(ImportSpan::new(
+                    begin,
+                    end,
+                    ImportValue::Chord(chord),
+                    false,
+                ));
+            }
+        }
+        spans
+    }
+    /// Section names like `Pre-Chorus 2` are mapped to the kinds, trailing
+    /// numbers are ignored.
+    pub fn parse_section_kind(name: &str) -> Option<SectionKind> {
+        let ident: String = name
+            .trim_end_matches(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .split([' ', '-'])
+            .map(|x| {
+                let mut chars = x.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect();
+        if ident.is_empty() {
+            None
+        } else {
+            Some(SectionKind::from_ident(&ident))
+        }
+    }
+    /// Sections begin at the given bars, bars before the first one are grouped
+    /// by `bars_per_section` as in the other importers.
+    pub fn new_sections(
+        layers: Vec<Vec<BarLayer>>,
+        starts: &[(usize, SectionKind)],
+        bars_per_section: usize,
+    ) -> (Vec<Section>, Form) {
+        let first_start = starts
+            .first()
+            .map(|x| x.0.min(layers.len()))
+            .unwrap_or(layers.len());
+        let importer = MidiImporter { bars_per_section };
+        let (mut sections, _) = importer.new_sections(layers[..first_start].to_vec());
+        for (index, (begin, kind)) in starts.iter().enumerate() {
+            let end = starts
+                .get(index + 1)
+                .map(|x| x.0.min(layers.len()))
+                .unwrap_or(layers.len());
+            if *begin >= end {
+                continue;
+            }
+            let bars = layers[*begin..end].iter().cloned().map(Bar::from).collect();
+            sections.push(Section::new(
+                format!("{}", sections.len() + 1),
+                kind.clone(),
+                bars,
+            ));
+        }
+        let form = Form::from(sections.iter().map(|x| x.id.clone()).collect::<Vec<_>>());
+        (sections, form)
+    }
+}
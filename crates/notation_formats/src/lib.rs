@@ -0,0 +1,41 @@
+pub use notation_model;
+pub use notation_proto;
+
+mod import_util;
+
+pub mod ascii_tab_export;
+pub mod ascii_tab_import;
+pub mod chord_name_util;
+pub mod chord_pro_export;
+pub mod chord_pro_import;
+pub mod guitar_pro_import;
+pub mod midi_import;
+pub mod music_xml_export;
+pub mod music_xml_import;
+pub mod music_xml_util;
+pub mod scala_import;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::ascii_tab_export::{AsciiTabExportError, AsciiTabExporter};
+    #[doc(hidden)]
+    pub use crate::ascii_tab_import::{AsciiTabImportError, AsciiTabImporter};
+    #[doc(hidden)]
+    pub use crate::chord_name_util::ChordNameUtil;
+    #[doc(hidden)]
+    pub use crate::chord_pro_export::{ChordProExportError, ChordProExporter};
+    #[doc(hidden)]
+    pub use crate::chord_pro_import::{ChordProImportError, ChordProImporter};
+    #[doc(hidden)]
+    pub use crate::guitar_pro_import::{GuitarProImportError, GuitarProImporter};
+    #[doc(hidden)]
+    pub use crate::midi_import::{MidiImportError, MidiImporter};
+    #[doc(hidden)]
+    pub use crate::music_xml_export::{MusicXmlExportError, MusicXmlExporter};
+    #[doc(hidden)]
+    pub use crate::music_xml_import::{MusicXmlImportError, MusicXmlImporter};
+    #[doc(hidden)]
+    pub use crate::music_xml_util::MusicXmlUtil;
+    #[doc(hidden)]
+    pub use crate::scala_import::{ScalaImportError, ScalaImporter};
+}
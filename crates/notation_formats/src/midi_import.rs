@@ -0,0 +1,825 @@
+use fehler::{throw, throws};
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+use notation_proto::prelude::*;
+
+use crate::import_util::{ImportSpan, ImportUtil, ImportValue};
+
+#[derive(Error, Debug)]
+pub enum MidiImportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("invalid chunk")]
+    InvalidChunk(String),
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("unsupported division")]
+    UnsupportedDivision(u16),
+    #[error("no notes found")]
+    NoNotes,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct MidiNote {
+    key: u8,
+    start: u32,
+    end: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct MidiTrack {
+    name: Option<String>,
+    channel: u8,
+    program: Option<u8>,
+    notes: Vec<MidiNote>,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum MidiMeta {
+    /// Microseconds per quarter note.
+    Tempo(u32),
+    /// Beats in bar, and the power of 2 of the beat type.
+    TimeSignature(u8, u8),
+    /// Sharps (negative for flats), and 1 for minor.
+    KeySignature(i8, u8),
+}
+
+#[derive(Clone, Debug, Default)]
+struct MidiData {
+    division: u16,
+    tracks: Vec<MidiTrack>,
+    /// Meta events of all the tracks, sorted by tick.
+    metas: Vec<(u32, MidiMeta)>,
+}
+
+/// Bars in grid steps, the meta changes are moved to the beginning of their bars.
+#[derive(Clone, Debug, Default)]
+struct MidiMeasures {
+    meta: TabMeta,
+    /// The begin of each bar, followed by the end of the last one.
+    bar_begins: Vec<u32>,
+    bar_metas: Vec<TabMeta>,
+    changes: Vec<(usize, MetaEntry)>,
+}
+
+impl MidiMeasures {
+    fn bar_index(&self, steps: u32) -> usize {
+        let index = match self.bar_begins.binary_search(&steps) {
+            Ok(index) => index,
+            Err(index) => index.saturating_sub(1),
+        };
+        index.min(self.bar_metas.len().saturating_sub(1))
+    }
+    fn bar_meta(&self, steps: u32) -> TabMeta {
+        self.bar_metas
+            .get(self.bar_index(steps))
+            .copied()
+            .unwrap_or(self.meta)
+    }
+}
+
+struct MidiReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MidiReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+    fn is_end(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+    #[throws(MidiImportError)]
+    fn read_bytes(&mut self, len: usize) -> &'a [u8] {
+        if self.pos + len > self.bytes.len() {
+            throw!(MidiImportError::UnexpectedEnd);
+        }
+        let result = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        result
+    }
+    #[throws(MidiImportError)]
+    fn read_u8(&mut self) -> u8 {
+        self.read_bytes(1)?[0]
+    }
+    #[throws(MidiImportError)]
+    fn peek_u8(&self) -> u8 {
+        match self.bytes.get(self.pos) {
+            Some(x) => *x,
+            None => throw!(MidiImportError::UnexpectedEnd),
+        }
+    }
+    #[throws(MidiImportError)]
+    fn read_u16(&mut self) -> u16 {
+        let v = self.read_bytes(2)?;
+        u16::from_be_bytes([v[0], v[1]])
+    }
+    #[throws(MidiImportError)]
+    fn read_u32(&mut self) -> u32 {
+        let v = self.read_bytes(4)?;
+        u32::from_be_bytes([v[0], v[1], v[2], v[3]])
+    }
+    #[throws(MidiImportError)]
+    fn read_var_len(&mut self) -> u32 {
+        let mut value: u32 = 0;
+        for _ in 0..4 {
+            let byte = self.read_u8()?;
+            value = (value << 7) | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                return value;
+            }
+        }
+        value
+    }
+    #[throws(MidiImportError)]
+    fn read_chunk(&mut self) -> (&'a [u8], &'a [u8]) {
+        let id = self.read_bytes(4)?;
+        let len = self.read_u32()? as usize;
+        (id, self.read_bytes(len)?)
+    }
+}
+
+impl MidiData {
+    #[throws(MidiImportError)]
+    fn parse(bytes: &[u8]) -> Self {
+        let mut reader = MidiReader::new(bytes);
+        let (id, header) = reader.read_chunk()?;
+        if id != b"MThd" || header.len() < 6 {
+            throw!(MidiImportError::InvalidChunk(
+                String::from_utf8_lossy(id).to_string()
+            ));
+        }
+        let mut header = MidiReader::new(header);
+        let _format = header.read_u16()?;
+        let _track_num = header.read_u16()?;
+        let division = header.read_u16()?;
+        if division & 0x8000 != 0 || division == 0 {
+            throw!(MidiImportError::UnsupportedDivision(division));
+        }
+        let mut data = MidiData {
+            division,
+            ..Self::default()
+        };
+        while !reader.is_end() {
+            let (id, chunk) = reader.read_chunk()?;
+            if id == b"MTrk" {
+                data.parse_track(chunk)?;
+            } else {
+                println!(
+                    "MidiImporter skipping chunk: {}",
+                    String::from_utf8_lossy(id)
+                );
+            }
+        }
+        data.metas.sort_by_key(|x| x.0);
+        data
+    }
+    /// Notes are split into separate tracks by channels, so format 0 files
+    /// still get one track per instrument.
+    #[throws(MidiImportError)]
+    fn parse_track(&mut self, chunk: &[u8]) {
+        let mut reader = MidiReader::new(chunk);
+        let mut tick: u32 = 0;
+        let mut running_status: u8 = 0;
+        let mut name = None;
+        let mut tracks: Vec<MidiTrack> = Vec::new();
+        let mut playing: HashMap<(u8, u8), u32> = HashMap::new();
+        let mut programs: HashMap<u8, u8> = HashMap::new();
+        while !reader.is_end() {
+            tick = tick.saturating_add(reader.read_var_len()?);
+            let mut status = reader.peek_u8()?;
+            if status & 0x80 != 0 {
+                reader.read_u8()?;
+            } else {
+                status = running_status;
+            }
+            match status {
+                0xFF => {
+                    let meta_type = reader.read_u8()?;
+                    let len = reader.read_var_len()? as usize;
+                    let payload = reader.read_bytes(len)?;
+                    match (meta_type, payload.len()) {
+                        (0x03, _) => {
+                            name = Some(String::from_utf8_lossy(payload).trim().to_string())
+                        }
+                        (0x51, 3) => self.metas.push((
+                            tick,
+                            MidiMeta::Tempo(u32::from_be_bytes([
+                                0, payload[0], payload[1], payload[2],
+                            ])),
+                        )),
+                        (0x58, 4) => self
+                            .metas
+                            .push((tick, MidiMeta::TimeSignature(payload[0], payload[1]))),
+                        (0x59, 2) => self
+                            .metas
+                            .push((tick, MidiMeta::KeySignature(payload[0] as i8, payload[1]))),
+                        (0x2F, _) => break,
+                        _ => {}
+                    }
+                }
+                0xF0 | 0xF7 => {
+                    let len = reader.read_var_len()? as usize;
+                    reader.read_bytes(len)?;
+                }
+                0x80..=0xEF => {
+                    running_status = status;
+                    let channel = status & 0x0F;
+                    let data_1 = reader.read_u8()? & 0x7F;
+                    match status & 0xF0 {
+                        0xC0 => {
+                            programs.insert(channel, data_1);
+                        }
+                        0xD0 => {}
+                        _ => {
+                            let data_2 = reader.read_u8()? & 0x7F;
+                            let kind = status & 0xF0;
+                            if kind == 0x90 && data_2 > 0 {
+                                if let Some(start) = playing.insert((channel, data_1), tick) {
+                                    Self::add_note(&mut tracks, channel, data_1, start, tick);
+                                }
+                            } else if kind == 0x80 || kind == 0x90 {
+                                if let Some(start) = playing.remove(&(channel, data_1)) {
+                                    Self::add_note(&mut tracks, channel, data_1, start, tick);
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    println!("MidiImporter unknown status: {}", status);
+                    break;
+                }
+            }
+        }
+        for ((channel, key), start) in playing {
+            Self::add_note(&mut tracks, channel, key, start, tick);
+        }
+        for mut track in tracks {
+            track.name = name.clone();
+            track.program = programs.get(&track.channel).cloned();
+            track.notes.sort_by_key(|x| (x.start, x.key));
+            self.tracks.push(track);
+        }
+    }
+    fn add_note(tracks: &mut Vec<MidiTrack>, channel: u8, key: u8, start: u32, end: u32) {
+        let note = MidiNote { key, start, end };
+        match tracks.iter_mut().find(|x| x.channel == channel) {
+            Some(track) => track.notes.push(note),
+            None => tracks.push(MidiTrack {
+                channel,
+                notes: vec![note],
+                ..MidiTrack::default()
+            }),
+        }
+    }
+}
+
+/// Imports a standard midi file as a tab, all positions are in grid steps,
+/// which is 1/96 of a whole note, so both 32nd and triplet 16th can be
+/// quantized exactly. Tempo, time and key signature changes are added as meta
+/// entries at the beginning of their bars.
+#[derive(Copy, Clone, Debug)]
+pub struct MidiImporter {
+    pub bars_per_section: usize,
+}
+
+impl Default for MidiImporter {
+    fn default() -> Self {
+        Self {
+            bars_per_section: 4,
+        }
+    }
+}
+
+impl MidiImporter {
+    pub const GRID_STEPS: u32 = 96;
+    pub const STRAIGHT_STEPS: u32 = 3;
+    pub const TRIPLET_STEPS: u32 = 4;
+    pub const DRUMS_CHANNEL: u8 = 9;
+    pub const DEFAULT_MICROS_PER_QUARTER: u32 = 500_000;
+
+    #[throws(MidiImportError)]
+    pub fn import_file<P: AsRef<Path>>(&self, path: P) -> Tab {
+        let bytes = std::fs::read(path)?;
+        self.import(&bytes)?
+    }
+    #[throws(MidiImportError)]
+    pub fn import(&self, bytes: &[u8]) -> Tab {
+        let data = MidiData::parse(bytes)?;
+        if data.tracks.iter().all(|x| x.notes.is_empty()) {
+            throw!(MidiImportError::NoNotes);
+        }
+        let ticks_per_step = data.division as f32 * 4.0 / Self::GRID_STEPS as f32;
+        let end_ticks = data
+            .tracks
+            .iter()
+            .flat_map(|x| x.notes.iter().map(|x| x.end))
+            .max()
+            .unwrap_or(0);
+        let measures = Self::calc_measures(
+            &data,
+            (end_ticks as f32 / ticks_per_step).ceil() as u32,
+            ticks_per_step,
+        );
+        let meta = measures.meta;
+        let beat_steps = std::cmp::max(
+            Self::duration_steps(Units::from(meta.signature.beat_unit)),
+            Self::STRAIGHT_STEPS * Self::TRIPLET_STEPS,
+        );
+        let mut spans = Vec::new();
+        let mut total_steps = 0;
+        for track in data.tracks.iter().filter(|x| !x.notes.is_empty()) {
+            let notes = Self::quantize_notes(&track.notes, ticks_per_step, &measures, beat_steps);
+            if let Some(last) = notes.iter().map(|x| x.end).max() {
+                total_steps = std::cmp::max(total_steps, last);
+            }
+            spans.push((track, Self::calc_spans(&measures, &notes)));
+        }
+        let bar_num = measures.bar_begins[..measures.bar_metas.len()]
+            .iter()
+            .filter(|x| **x < total_steps)
+            .count();
+        let mut tracks = Vec::new();
+        let mut layers: Vec<Vec<BarLayer>> = vec![Vec::new(); bar_num];
+        for (index, (midi_track, voices)) in spans.into_iter().enumerate() {
+            let id = Self::calc_track_id(midi_track, index, &tracks);
+            let mut entries = Vec::new();
+            // the meta changes only need to be in one track
+            ImportUtil::push_measures(
+                &mut entries,
+                &mut layers,
+                &id,
+                &voices,
+                &measures.bar_begins,
+                if tracks.is_empty() {
+                    &measures.changes[..]
+                } else {
+                    &[]
+                },
+                beat_steps,
+            );
+            tracks.push(Track::new(id, Self::calc_track_kind(midi_track), entries));
+        }
+        let (sections, form) = self.new_sections(layers);
+        Tab::new(Tab::new_uuid().as_str(), meta, tracks, sections, form)
+    }
+}
+
+impl MidiImporter {
+    /// Bars are grouped into sections of `bars_per_section` bars, played in order.
+    pub(crate) fn new_sections(&self, layers: Vec<Vec<BarLayer>>) -> (Vec<Section>, Form) {
+        let mut sections = Vec::new();
+        let bars_per_section = std::cmp::max(self.bars_per_section, 1);
+        for (index, chunk) in layers.chunks(bars_per_section).enumerate() {
+            let bars = chunk.iter().cloned().map(Bar::from).collect();
+            sections.push(Section::new(
+                format!("{}", index + 1),
+                SectionKind::Verse,
+                bars,
+            ));
+        }
+        let form = Form::from(sections.iter().map(|x| x.id.clone()).collect::<Vec<_>>());
+        (sections, form)
+    }
+    /// The first tempo, time and key signature of the file, and the tempo in
+    /// microseconds per quarter.
+    fn calc_meta(data: &MidiData) -> (TabMeta, u32) {
+        let mut meta = TabMeta::default();
+        let mut micros_per_quarter = Self::DEFAULT_MICROS_PER_QUARTER;
+        let firsts = [
+            data.metas
+                .iter()
+                .find(|x| matches!(x.1, MidiMeta::TimeSignature(..))),
+            data.metas
+                .iter()
+                .find(|x| matches!(x.1, MidiMeta::Tempo(..))),
+            data.metas
+                .iter()
+                .find(|x| matches!(x.1, MidiMeta::KeySignature(..))),
+        ];
+        for (_, midi_meta) in firsts.into_iter().flatten() {
+            Self::apply_meta(&mut meta, &mut micros_per_quarter, midi_meta);
+        }
+        meta.tempo = Self::calc_tempo(&meta, micros_per_quarter);
+        (meta, micros_per_quarter)
+    }
+    fn apply_meta(meta: &mut TabMeta, micros_per_quarter: &mut u32, midi_meta: &MidiMeta) {
+        match *midi_meta {
+            MidiMeta::Tempo(micros) => *micros_per_quarter = micros.max(1),
+            MidiMeta::TimeSignature(bar_beats, denominator) => {
+                let beat_unit = match denominator {
+                    0 => Unit::Whole,
+                    1 => Unit::Half,
+                    2 => Unit::Quarter,
+                    3 => Unit::Eighth,
+                    4 => Unit::Sixteenth,
+                    5 => Unit::ThirtySecondth,
+                    _ => Unit::SixtyFourth,
+                };
+                if bar_beats > 0 {
+                    meta.signature = Signature::new(beat_unit, bar_beats);
+                }
+            }
+            MidiMeta::KeySignature(sharps, minor) => {
+                meta.scale = if minor == 1 {
+                    Scale::Aeolian
+                } else {
+                    Scale::Ionian
+                };
+                meta.key = meta.scale.calc_key_from_signature(sharps);
+            }
+        }
+    }
+    /// Tempo is in the beats of the signature, not in quarters.
+    fn calc_tempo(meta: &TabMeta, micros_per_quarter: u32) -> Tempo {
+        let quarters_per_minute = 60_000_000.0 / micros_per_quarter.max(1) as f32;
+        let bpm = quarters_per_minute / (Units::from(meta.signature.beat_unit).0 * 4.0);
+        Tempo::Bpm(bpm.round().clamp(1.0, u16::MAX as f32) as u16)
+    }
+    fn calc_meta_entries(from: &TabMeta, to: &TabMeta) -> Vec<MetaEntry> {
+        let mut result = Vec::new();
+        if from.signature != to.signature {
+            result.push(MetaEntry::Signature(to.signature));
+        }
+        if from.tempo != to.tempo {
+            result.push(MetaEntry::Tempo(to.tempo));
+        }
+        if from.key != to.key {
+            result.push(MetaEntry::Key(to.key));
+        }
+        if from.scale != to.scale {
+            result.push(MetaEntry::Scale(to.scale));
+        }
+        result
+    }
+    /// Bars are added until `end_steps`, the meta events inside a bar are
+    /// applied from its beginning.
+    fn calc_measures(data: &MidiData, end_steps: u32, ticks_per_step: f32) -> MidiMeasures {
+        let (meta, mut micros_per_quarter) = Self::calc_meta(data);
+        let mut measures = MidiMeasures {
+            meta,
+            bar_begins: vec![0],
+            ..MidiMeasures::default()
+        };
+        let mut bar_meta = meta;
+        let mut bar_begin = 0;
+        let mut metas = data.metas.iter().peekable();
+        while bar_begin <= end_steps {
+            let bar_end = bar_begin + Self::duration_steps(Units::from(bar_meta.signature)).max(1);
+            let mut next_meta = bar_meta;
+            while let Some((_, midi_meta)) =
+                metas.next_if(|x| ((x.0 as f32 / ticks_per_step).round() as u32) < bar_end)
+            {
+                Self::apply_meta(&mut next_meta, &mut micros_per_quarter, midi_meta);
+                next_meta.tempo = Self::calc_tempo(&next_meta, micros_per_quarter);
+            }
+            for entry in Self::calc_meta_entries(&bar_meta, &next_meta) {
+                measures.changes.push((measures.bar_metas.len(), entry));
+            }
+            bar_meta = next_meta;
+            bar_begin += Self::duration_steps(Units::from(bar_meta.signature)).max(1);
+            measures.bar_metas.push(bar_meta);
+            measures.bar_begins.push(bar_begin);
+        }
+        measures
+    }
+    fn calc_track_id(track: &MidiTrack, index: usize, tracks: &[Track]) -> String {
+        let name = track
+            .name
+            .clone()
+            .filter(|x| !x.is_empty())
+            .unwrap_or(format!("track{}", index + 1));
+        ImportUtil::calc_track_id(&name, tracks)
+    }
+    // https://en.wikipedia.org/wiki/General_MIDI#Program_change_events
+    fn calc_track_kind(track: &MidiTrack) -> TrackKind {
+        if track.channel == Self::DRUMS_CHANNEL {
+            return TrackKind::Drums;
+        }
+        match track.program.unwrap_or(0) {
+            0..=7 => TrackKind::Piano,
+            24..=31 => TrackKind::Guitar,
+            32..=39 => TrackKind::Bass,
+            _ => TrackKind::Vocal,
+        }
+    }
+    pub(crate) fn duration_steps(units: Units) -> u32 {
+        (units.0 * Self::GRID_STEPS as f32).round() as u32
+    }
+    /// Pick straight or triplet grid for each beat, whichever is closer to the
+    /// played positions.
+    fn quantize_notes(
+        notes: &[MidiNote],
+        ticks_per_step: f32,
+        measures: &MidiMeasures,
+        beat_steps: u32,
+    ) -> Vec<MidiNote> {
+        let to_steps = |tick: u32| tick as f32 / ticks_per_step;
+        let beat_of = |steps: f32| {
+            let bar = measures.bar_index(steps.floor() as u32);
+            let in_bar = steps - measures.bar_begins[bar] as f32;
+            (bar, (in_bar.max(0.0) / beat_steps as f32).floor() as u32)
+        };
+        let mut errors: HashMap<(usize, u32), (f32, f32)> = HashMap::new();
+        for note in notes.iter() {
+            for steps in [to_steps(note.start), to_steps(note.end)] {
+                let error = errors.entry(beat_of(steps)).or_insert((0.0, 0.0));
+                let straight = Self::STRAIGHT_STEPS as f32;
+                let triplet = Self::TRIPLET_STEPS as f32;
+                error.0 += (steps - (steps / straight).round() * straight).abs();
+                error.1 += (steps - (steps / triplet).round() * triplet).abs();
+            }
+        }
+        let snap = |tick: u32| {
+            let steps = to_steps(tick);
+            let (bar, beat) = beat_of(steps);
+            let grid = match errors.get(&(bar, beat)) {
+                Some((straight, triplet)) if triplet < straight => Self::TRIPLET_STEPS,
+                _ => Self::STRAIGHT_STEPS,
+            };
+            let beat_begin = measures.bar_begins[bar] + beat * beat_steps;
+            let in_beat = ((steps - beat_begin as f32) / grid as f32).round() as u32 * grid;
+            let bar_end = measures.bar_begins[bar + 1];
+            std::cmp::min(beat_begin + std::cmp::min(in_beat, beat_steps), bar_end)
+        };
+        notes
+            .iter()
+            .map(|note| {
+                let start = snap(note.start);
+                let end = std::cmp::max(snap(note.end), start + Self::STRAIGHT_STEPS);
+                MidiNote {
+                    key: note.key,
+                    start,
+                    end,
+                }
+            })
+            .collect()
+    }
+    /// Notes starting and ending together are merged into one tone, tones
+    /// overlapping the previous ones are put into other voices, so sustained
+    /// notes keep their lengths, the notes are spelled in the key of their bars.
+    fn calc_spans(measures: &MidiMeasures, notes: &[MidiNote]) -> Vec<Vec<ImportSpan>> {
+        let mut tones: Vec<(u32, u32, Vec<Note>)> = Vec::new();
+        for note in notes.iter() {
+            let meta = measures.bar_meta(note.start);
+            let semitones = Semitones(note.key as i8 - 12);
+            let note_value = meta.scale.calc_note_from_semitones(&meta.key, semitones);
+            match tones
+                .iter_mut()
+                .rev()
+                .take_while(|x| x.0 == note.start)
+                .find(|x| x.1 == note.end)
+            {
+                Some(tone) => {
+                    if !tone.2.contains(&note_value) {
+                        tone.2.push(note_value);
+                    }
+                }
+                None => tones.push((note.start, note.end, vec![note_value])),
+            }
+        }
+        let mut voices: Vec<Vec<ImportSpan>> = Vec::new();
+        for (begin, end, notes) in tones {
+            let span = ImportSpan::new(begin, end, ImportValue::Tone(notes), false);
+            match voices
+                .iter_mut()
+                .find(|x| x.last().map(|x| x.end <= begin).unwrap_or(true))
+            {
+                Some(voice) => voice.push(span),
+                None => voices.push(vec![span]),
+            }
+        }
+        voices
+    }
+    pub(crate) fn calc_duration(steps: u32) -> Option<Duration> {
+        match steps {
+            96 => Some(Duration::Simple(Unit::Whole)),
+            72 => Some(Duration::Dotted(Unit::Half)),
+            48 => Some(Duration::Simple(Unit::Half)),
+            36 => Some(Duration::Dotted(Unit::Quarter)),
+            32 => Some(Duration::Triplet(Unit::Half)),
+            24 => Some(Duration::Simple(Unit::Quarter)),
+            18 => Some(Duration::Dotted(Unit::Eighth)),
+            16 => Some(Duration::Triplet(Unit::Quarter)),
+            12 => Some(Duration::Simple(Unit::Eighth)),
+            9 => Some(Duration::Dotted(Unit::Sixteenth)),
+            8 => Some(Duration::Triplet(Unit::Eighth)),
+            6 => Some(Duration::Simple(Unit::Sixteenth)),
+            4 => Some(Duration::Triplet(Unit::Sixteenth)),
+            3 => Some(Duration::Simple(Unit::ThirtySecondth)),
+            2 => Some(Duration::Triplet(Unit::ThirtySecondth)),
+            1 => Some(Duration::Triplet(Unit::SixtyFourth)),
+            _ => None,
+        }
+    }
+    /// The durations are summed up to the steps exactly, the single step is a
+    /// triplet 64th.
+    fn split_steps(steps: u32) -> Vec<Duration> {
+        let candidates: &[u32] = if steps.is_multiple_of(24) {
+            &[96, 72, 48, 24]
+        } else if steps.is_multiple_of(Self::STRAIGHT_STEPS) {
+            &[18, 12, 9, 6, 3]
+        } else if steps.is_multiple_of(Self::TRIPLET_STEPS) {
+            &[32, 16, 8, 4]
+        } else {
+            &[96, 72, 48, 36, 32, 24, 18, 16, 12, 9, 8, 6, 4, 3, 2, 1]
+        };
+        let mut result = Vec::new();
+        let mut left = steps;
+        for candidate in candidates.iter() {
+            while left >= *candidate {
+                if let Some(duration) = Self::calc_duration(*candidate) {
+                    result.push(duration);
+                }
+                left -= candidate;
+            }
+        }
+        result
+    }
+    /// Split at the beats, so the durations inside a bar are readable.
+    pub(crate) fn split_span(
+        begin: u32,
+        end: u32,
+        bar_begin: u32,
+        beat_steps: u32,
+    ) -> Vec<Duration> {
+        let mut result = Vec::new();
+        let mut pos = begin;
+        while pos < end {
+            let in_bar = pos - bar_begin;
+            let next = if !in_bar.is_multiple_of(beat_steps) {
+                std::cmp::min(bar_begin + (in_bar / beat_steps + 1) * beat_steps, end)
+            } else if end - pos >= beat_steps {
+                pos + (end - pos) / beat_steps * beat_steps
+            } else {
+                end
+            };
+            result.append(&mut Self::split_steps(next - pos));
+            pos = next;
+        }
+        result
+    }
+    pub(crate) fn push_rests(
+        entries: &mut Vec<ProtoEntry>,
+        begin: u32,
+        end: u32,
+        bar_begin: u32,
+        beat_steps: u32,
+    ) {
+        for duration in Self::split_span(begin, end, bar_begin, beat_steps) {
+            entries.push(ProtoEntry::from(CoreEntry::Rest(duration)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_midi(division: u16, events: &[(u32, &[u8])]) -> Vec<u8> {
+        let mut track = Vec::new();
+        for (delta, event) in events.iter() {
+            let mut bytes = vec![(delta & 0x7F) as u8];
+            let mut value = delta >> 7;
+            while value > 0 {
+                bytes.insert(0, (value & 0x7F) as u8 | 0x80);
+                value >>= 7;
+            }
+            track.extend(bytes);
+            track.extend_from_slice(event);
+        }
+        track.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend_from_slice(&6u32.to_be_bytes());
+        bytes.extend_from_slice(&[0, 0, 0, 1]);
+        bytes.extend_from_slice(&division.to_be_bytes());
+        bytes.extend_from_slice(b"MTrk");
+        bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        bytes.extend(track);
+        bytes
+    }
+
+    #[test]
+    fn test_calc_duration() {
+        for steps in 1..=MidiImporter::GRID_STEPS {
+            if let Some(duration) = MidiImporter::calc_duration(steps) {
+                assert_eq!(
+                    Rational::from(duration),
+                    Rational::new(steps as i64, MidiImporter::GRID_STEPS as i64)
+                );
+            }
+        }
+        assert_eq!(
+            MidiImporter::calc_duration(24),
+            Some(Duration::Simple(Unit::Quarter))
+        );
+        assert_eq!(
+            MidiImporter::calc_duration(1),
+            Some(Duration::Triplet(Unit::SixtyFourth))
+        );
+        assert_eq!(MidiImporter::calc_duration(5), None);
+    }
+
+    #[test]
+    fn test_split_steps() {
+        for steps in 1..=MidiImporter::GRID_STEPS * 2 {
+            let length: Rational = MidiImporter::split_steps(steps)
+                .into_iter()
+                .map(Rational::from)
+                .sum();
+            assert_eq!(
+                length,
+                Rational::new(steps as i64, MidiImporter::GRID_STEPS as i64)
+            );
+        }
+        assert_eq!(
+            MidiImporter::split_steps(25),
+            vec![
+                Duration::Simple(Unit::Quarter),
+                Duration::Triplet(Unit::SixtyFourth)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quantize_notes() {
+        let measures = MidiMeasures {
+            bar_begins: vec![0, 96],
+            bar_metas: vec![TabMeta::default()],
+            ..MidiMeasures::default()
+        };
+        let note = |start, end| MidiNote {
+            key: 60,
+            start,
+            end,
+        };
+        // 20 ticks per step, a sloppy eighth, then triplet eighths in the next beat
+        let notes = vec![
+            note(5, 230),
+            note(245, 480),
+            note(481, 640),
+            note(645, 800),
+            note(810, 955),
+        ];
+        let steps: Vec<(u32, u32)> = MidiImporter::quantize_notes(&notes, 20.0, &measures, 24)
+            .iter()
+            .map(|x| (x.start, x.end))
+            .collect();
+        assert_eq!(steps, vec![(0, 12), (12, 24), (24, 32), (32, 40), (40, 48)]);
+    }
+
+    #[test]
+    fn test_overlapping_notes() {
+        let measures = MidiMeasures {
+            bar_begins: vec![0, 96],
+            bar_metas: vec![TabMeta::default()],
+            ..MidiMeasures::default()
+        };
+        let note = |key, start, end| MidiNote { key, start, end };
+        let notes = vec![note(48, 0, 96), note(60, 0, 24), note(64, 24, 48)];
+        let voices: Vec<Vec<(u32, u32)>> = MidiImporter::calc_spans(&measures, &notes)
+            .iter()
+            .map(|x| x.iter().map(|x| (x.begin, x.end)).collect())
+            .collect();
+        assert_eq!(voices, vec![vec![(0, 96)], vec![(0, 24), (24, 48)]]);
+    }
+
+    #[test]
+    fn test_meta_changes() {
+        // quarter notes in 4/4 at 120 bpm, 3/4 at 60 bpm from the second bar
+        let bytes = new_midi(
+            4,
+            &[
+                (0, &[0xFF, 0x58, 0x04, 4, 2, 24, 8]),
+                (0, &[0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20]),
+                (0, &[0x90, 60, 100]),
+                (16, &[0x80, 60, 0]),
+                (0, &[0xFF, 0x58, 0x04, 3, 2, 24, 8]),
+                (0, &[0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40]),
+                (0, &[0x90, 62, 100]),
+                (12, &[0x80, 62, 0]),
+            ],
+        );
+        let tab = MidiImporter::default().import(&bytes).unwrap();
+        assert_eq!(tab.meta.signature, Signature::new(Unit::Quarter, 4));
+        assert_eq!(tab.meta.tempo, Tempo::Bpm(120));
+        let entries: Vec<MetaEntry> = tab.tracks[0]
+            .entries
+            .iter()
+            .filter_map(|x| x.as_meta().copied())
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                MetaEntry::Signature(Signature::new(Unit::Quarter, 3)),
+                MetaEntry::Tempo(Tempo::Bpm(60)),
+            ]
+        );
+        let bars: usize = tab.sections.iter().map(|x| x.bars.len()).sum();
+        assert_eq!(bars, 2);
+    }
+}
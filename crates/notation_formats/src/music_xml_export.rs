@@ -0,0 +1,766 @@
+use fehler::throws;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+
+use notation_model::prelude::*;
+
+use crate::music_xml_util::MusicXmlUtil;
+
+#[derive(Error, Debug)]
+pub enum MusicXmlExportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("parse error")]
+    Parse(#[from] ParseError),
+    #[error("format error")]
+    Format(#[from] std::fmt::Error),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum PartKind {
+    Pitched,
+    Tablature,
+}
+
+struct Part {
+    id: String,
+    track: Arc<Track>,
+    kind: PartKind,
+    with_chords: bool,
+    with_lyrics: bool,
+}
+
+/// Note with (string, fret) for tablature parts.
+type XmlPitch = (Note, Option<(u8, u8)>);
+
+#[derive(Clone, Debug)]
+struct XmlNote {
+    pos: Rational,
+    length: Rational,
+    duration: Duration,
+    notes: Vec<XmlPitch>,
+    tie_start: bool,
+    tie_stop: bool,
+}
+
+/// Exports a tab into MusicXML (partwise), the form is expanded, so repeated
+/// sections are written out as played.
+#[derive(Copy, Clone, Debug)]
+pub struct MusicXmlExporter {
+    pub divisions: u32,
+}
+
+impl Default for MusicXmlExporter {
+    fn default() -> Self {
+        Self {
+            divisions: Self::DEFAULT_DIVISIONS,
+        }
+    }
+}
+
+impl MusicXmlExporter {
+    pub const DEFAULT_DIVISIONS: u32 = 24;
+
+    #[throws(MusicXmlExportError)]
+    pub fn export_file<P: AsRef<Path>>(&self, tab: &ProtoTab, path: P) {
+        std::fs::write(path, self.export(tab)?)?;
+    }
+    #[throws(MusicXmlExportError)]
+    pub fn export(&self, tab: &ProtoTab) -> String {
+        let tab = Tab::try_parse_arc(tab.clone(), false, None)?;
+        self.fit_divisions(&tab).export_tab(&tab)?
+    }
+    /// The divisions are raised to fit all the durations in the tab, e.g.
+    /// 120 for quintuplet 16ths, so every note ends on a whole division.
+    pub fn fit_divisions(&self, tab: &Tab) -> Self {
+        let gcd = |mut a: u32, mut b: u32| {
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            a
+        };
+        let mut divisions = self.divisions.max(1);
+        for bar in tab.bars.iter() {
+            for lane in bar.lanes.values() {
+                for entry in lane.entries.iter() {
+                    let quarters = Rational::from(entry.duration()) * Rational::from(4);
+                    let denom = quarters.denom() as u32;
+                    divisions = divisions / gcd(divisions, denom) * denom;
+                }
+            }
+        }
+        Self { divisions }
+    }
+    #[throws(std::fmt::Error)]
+    pub fn export_tab(&self, tab: &Tab) -> String {
+        let parts = Self::get_parts(tab);
+        let mut xml = String::new();
+        writeln!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+        )?;
+        writeln!(
+            xml,
+            r#"<!DOCTYPE score-partwise PUBLIC "-//Recordare//DTD MusicXML 4.0 Partwise//EN" "http://www.musicxml.org/dtds/partwise.dtd">"#
+        )?;
+        writeln!(xml, r#"<score-partwise version="4.0">"#)?;
+        writeln!(xml, "  <part-list>")?;
+        for part in parts.iter() {
+            writeln!(xml, r#"    <score-part id="{}">"#, part.id)?;
+            writeln!(
+                xml,
+                "      <part-name>{}</part-name>",
+                MusicXmlUtil::escape(&part.track.id)
+            )?;
+            writeln!(xml, "    </score-part>")?;
+        }
+        writeln!(xml, "  </part-list>")?;
+        for (index, part) in parts.iter().enumerate() {
+            writeln!(xml, r#"  <part id="{}">"#, part.id)?;
+            let mut last_meta: Option<&TabMeta> = None;
+            for bar in tab.bars.iter() {
+                self.write_measure(&mut xml, bar, last_meta, part, index == 0)?;
+                last_meta = Some(&bar.meta);
+            }
+            writeln!(xml, "  </part>")?;
+        }
+        writeln!(xml, "</score-partwise>")?;
+        xml
+    }
+}
+
+impl MusicXmlExporter {
+    fn get_parts(tab: &Tab) -> Vec<Part> {
+        let mut parts: Vec<Part> = Vec::new();
+        for track in tab.tracks.iter() {
+            let kind = match track.kind {
+                TrackKind::Vocal | TrackKind::Piano | TrackKind::Synth | TrackKind::Bass => {
+                    PartKind::Pitched
+                }
+                TrackKind::Guitar if track.get_fretboard6().is_some() => PartKind::Tablature,
+                _ => continue,
+            };
+            parts.push(Part {
+                id: format!("P{}", parts.len() + 1),
+                track: track.clone(),
+                kind,
+                with_chords: false,
+                with_lyrics: false,
+            });
+        }
+        if let Some(part) = parts.get_mut(0) {
+            part.with_chords = true;
+        }
+        if let Some(part) = parts.iter_mut().find(|x| x.track.kind == TrackKind::Vocal) {
+            part.with_lyrics = true;
+        }
+        parts
+    }
+    fn calc_divisions(&self, length: Rational) -> u32 {
+        (length.to_f32() * 4.0 * self.divisions as f32).round() as u32
+    }
+    #[throws(std::fmt::Error)]
+    fn write_key(&self, xml: &mut String, meta: &TabMeta) {
+        writeln!(xml, "        <key>")?;
+        writeln!(
+            xml,
+            "          <fifths>{}</fifths>",
+            meta.scale.calc_key_signature(&meta.key)
+        )?;
+        let mode = match meta.scale.to_diatonic() {
+            Scale::Ionian => "major".to_owned(),
+            Scale::Aeolian => "minor".to_owned(),
+            scale => scale.to_ident().to_lowercase(),
+        };
+        writeln!(xml, "          <mode>{}</mode>", mode)?;
+        writeln!(xml, "        </key>")?;
+    }
+    #[throws(std::fmt::Error)]
+    fn write_time(&self, xml: &mut String, meta: &TabMeta) {
+        writeln!(xml, "        <time>")?;
+        writeln!(xml, "          <beats>{}</beats>", meta.signature.bar_beats)?;
+        writeln!(
+            xml,
+            "          <beat-type>{}</beat-type>",
+            MusicXmlUtil::unit_to_beat_type(&meta.signature.beat_unit)
+        )?;
+        writeln!(xml, "        </time>")?;
+    }
+    #[throws(std::fmt::Error)]
+    fn write_attributes(&self, xml: &mut String, meta: &TabMeta, part: &Part) {
+        writeln!(xml, "      <attributes>")?;
+        writeln!(xml, "        <divisions>{}</divisions>", self.divisions)?;
+        self.write_key(xml, meta)?;
+        self.write_time(xml, meta)?;
+        match part.kind {
+            PartKind::Pitched => {
+                let (sign, line) = if part.track.kind == TrackKind::Bass {
+                    ("F", 4)
+                } else {
+                    ("G", 2)
+                };
+                writeln!(
+                    xml,
+                    "        <clef><sign>{}</sign><line>{}</line></clef>",
+                    sign, line
+                )?;
+            }
+            PartKind::Tablature => {
+                writeln!(xml, "        <clef><sign>TAB</sign><line>5</line></clef>")?;
+                if let Some(fretboard) = part.track.get_fretboard6() {
+                    let string_num = fretboard.string_num();
+                    writeln!(xml, "        <staff-details>")?;
+                    writeln!(xml, "          <staff-lines>{}</staff-lines>", string_num)?;
+                    for (index, semitones) in fretboard.string_notes.iter().enumerate().rev() {
+                        let note = meta.scale.calc_note_from_semitones(&meta.key, *semitones);
+                        writeln!(
+                            xml,
+                            r#"          <staff-tuning line="{}">"#,
+                            string_num - index
+                        )?;
+                        writeln!(
+                            xml,
+                            "            <tuning-step>{}</tuning-step>",
+                            note.pitch.name
+                        )?;
+                        let alter = MusicXmlUtil::pitch_alter(&note.pitch);
+                        if alter != 0 {
+                            writeln!(xml, "            <tuning-alter>{}</tuning-alter>", alter)?;
+                        }
+                        writeln!(
+                            xml,
+                            "            <tuning-octave>{}</tuning-octave>",
+                            MusicXmlUtil::note_octave(&note)
+                        )?;
+                        writeln!(xml, "          </staff-tuning>")?;
+                    }
+                    if fretboard.capo > 0 {
+                        writeln!(xml, "          <capo>{}</capo>", fretboard.capo)?;
+                    }
+                    writeln!(xml, "        </staff-details>")?;
+                }
+            }
+        }
+        writeln!(xml, "      </attributes>")?;
+    }
+    /// Only the key and time changed since the last bar are written.
+    #[throws(std::fmt::Error)]
+    fn write_attributes_change(&self, xml: &mut String, meta: &TabMeta, last_meta: &TabMeta) {
+        let key_changed = meta.key != last_meta.key || meta.scale != last_meta.scale;
+        let time_changed = meta.signature != last_meta.signature;
+        if !key_changed && !time_changed {
+            return;
+        }
+        writeln!(xml, "      <attributes>")?;
+        if key_changed {
+            self.write_key(xml, meta)?;
+        }
+        if time_changed {
+            self.write_time(xml, meta)?;
+        }
+        writeln!(xml, "      </attributes>")?;
+    }
+    #[throws(std::fmt::Error)]
+    fn write_tempo(&self, xml: &mut String, meta: &TabMeta) {
+        let bpm = Bpm::from(meta.tempo);
+        let beat_unit = meta.signature.beat_unit;
+        let quarters_per_minute = bpm as f32 * Units::from(beat_unit).0 * 4.0;
+        writeln!(xml, r#"      <direction placement="above">"#)?;
+        writeln!(xml, "        <direction-type>")?;
+        writeln!(xml, "          <metronome>")?;
+        writeln!(
+            xml,
+            "            <beat-unit>{}</beat-unit>",
+            MusicXmlUtil::unit_to_type(&beat_unit)
+        )?;
+        writeln!(xml, "            <per-minute>{}</per-minute>", bpm)?;
+        writeln!(xml, "          </metronome>")?;
+        writeln!(xml, "        </direction-type>")?;
+        writeln!(xml, r#"        <sound tempo="{}"/>"#, quarters_per_minute)?;
+        writeln!(xml, "      </direction>")?;
+    }
+    #[throws(std::fmt::Error)]
+    fn write_measure(
+        &self,
+        xml: &mut String,
+        bar: &Arc<TabBar>,
+        last_meta: Option<&TabMeta>,
+        part: &Part,
+        is_first_part: bool,
+    ) {
+        writeln!(xml, r#"    <measure number="{}">"#, bar.props.bar_number)?;
+        let meta = bar.meta.as_ref();
+        match last_meta {
+            None => {
+                self.write_attributes(xml, meta, part)?;
+                if is_first_part {
+                    self.write_tempo(xml, meta)?;
+                }
+            }
+            Some(last_meta) => {
+                self.write_attributes_change(xml, meta, last_meta)?;
+                if is_first_part && meta.tempo != last_meta.tempo {
+                    self.write_tempo(xml, meta)?;
+                }
+            }
+        }
+        let chords = if part.with_chords {
+            Self::get_chords(bar)
+        } else {
+            vec![]
+        };
+        let lyrics = if part.with_lyrics {
+            self.get_lyrics(bar)
+        } else {
+            HashMap::new()
+        };
+        let notes = match part.kind {
+            PartKind::Pitched => Self::get_tone_notes(bar, part),
+            PartKind::Tablature => Self::get_pick_notes(bar, part),
+        };
+        let voices = Self::split_voices(notes);
+        let bar_length = bar.meta.bar_length();
+        let bar_divisions = self.calc_divisions(bar_length);
+        let mut chord_index = 0;
+        if voices.is_empty() {
+            self.write_chords(
+                xml,
+                meta,
+                &chords,
+                &mut chord_index,
+                Rational::ZERO,
+                bar_length,
+            )?;
+            writeln!(xml, "      <note>")?;
+            writeln!(xml, r#"        <rest measure="yes"/>"#)?;
+            writeln!(xml, "        <duration>{}</duration>", bar_divisions)?;
+            writeln!(xml, "        <voice>1</voice>")?;
+            writeln!(xml, "      </note>")?;
+        }
+        for (voice_index, voice) in voices.iter().enumerate() {
+            let voice_number = voice_index + 1;
+            if voice_index > 0 {
+                writeln!(
+                    xml,
+                    "      <backup><duration>{}</duration></backup>",
+                    bar_divisions
+                )?;
+            }
+            let mut pos = Rational::ZERO;
+            for note in voice.iter() {
+                if note.pos > pos {
+                    if voice_index == 0 {
+                        self.write_chords(xml, meta, &chords, &mut chord_index, pos, note.pos)?;
+                    }
+                    self.write_forward(xml, note.pos - pos)?;
+                }
+                if voice_index == 0 {
+                    self.write_chords(
+                        xml,
+                        meta,
+                        &chords,
+                        &mut chord_index,
+                        note.pos,
+                        note.pos + note.length,
+                    )?;
+                }
+                let lyric = if voice_index == 0 && !note.tie_stop {
+                    lyrics.get(&self.calc_divisions(note.pos))
+                } else {
+                    None
+                };
+                self.write_note(xml, note, voice_number, lyric)?;
+                pos = note.pos + note.length;
+            }
+            if bar_length > pos {
+                if voice_index == 0 {
+                    self.write_chords(xml, meta, &chords, &mut chord_index, pos, bar_length)?;
+                }
+                self.write_forward(xml, bar_length - pos)?;
+            }
+        }
+        writeln!(xml, "    </measure>")?;
+    }
+    #[throws(std::fmt::Error)]
+    fn write_forward(&self, xml: &mut String, length: Rational) {
+        writeln!(
+            xml,
+            "      <forward><duration>{}</duration></forward>",
+            self.calc_divisions(length)
+        )?;
+    }
+    #[throws(std::fmt::Error)]
+    fn write_chords(
+        &self,
+        xml: &mut String,
+        meta: &TabMeta,
+        chords: &[(Rational, Chord)],
+        chord_index: &mut usize,
+        begin: Rational,
+        end: Rational,
+    ) {
+        while let Some((pos, chord)) = chords.get(*chord_index) {
+            if *pos >= end {
+                break;
+            }
+            *chord_index += 1;
+            let root = meta.scale.calc_pitch(&meta.key, &chord.root);
+            let (kind, degrees) = MusicXmlUtil::chord_kind(&chord.intervals);
+            writeln!(xml, "      <harmony>")?;
+            writeln!(xml, "        <root>")?;
+            writeln!(xml, "          <root-step>{}</root-step>", root.name)?;
+            let alter = MusicXmlUtil::pitch_alter(&root);
+            if alter != 0 {
+                writeln!(xml, "          <root-alter>{}</root-alter>", alter)?;
+            }
+            writeln!(xml, "        </root>")?;
+            writeln!(xml, "        <kind>{}</kind>", kind)?;
+            if let Some(bass) = chord.bass {
+                let bass = meta
+                    .scale
+                    .calc_pitch(&meta.key, &bass.syllable_on_root(&chord.root));
+                writeln!(xml, "        <bass>")?;
+                writeln!(xml, "          <bass-step>{}</bass-step>", bass.name)?;
+                let alter = MusicXmlUtil::pitch_alter(&bass);
+                if alter != 0 {
+                    writeln!(xml, "          <bass-alter>{}</bass-alter>", alter)?;
+                }
+                writeln!(xml, "        </bass>")?;
+            }
+            for (value, alter) in degrees {
+                writeln!(xml, "        <degree>")?;
+                writeln!(xml, "          <degree-value>{}</degree-value>", value)?;
+                writeln!(xml, "          <degree-alter>{}</degree-alter>", alter)?;
+                writeln!(xml, "          <degree-type>add</degree-type>")?;
+                writeln!(xml, "        </degree>")?;
+            }
+            let offset = *pos - begin;
+            if offset > Rational::ZERO {
+                writeln!(
+                    xml,
+                    "        <offset>{}</offset>",
+                    self.calc_divisions(offset)
+                )?;
+            }
+            writeln!(xml, "      </harmony>")?;
+        }
+    }
+    #[throws(std::fmt::Error)]
+    fn write_note(
+        &self,
+        xml: &mut String,
+        note: &XmlNote,
+        voice: usize,
+        lyric: Option<&(String, &'static str)>,
+    ) {
+        let divisions = self.calc_divisions(note.length);
+        let rests = vec![None];
+        let notes: Vec<Option<&XmlPitch>> = if note.notes.is_empty() {
+            rests
+        } else {
+            note.notes.iter().map(Some).collect()
+        };
+        for (index, value) in notes.into_iter().enumerate() {
+            writeln!(xml, "      <note>")?;
+            if index > 0 {
+                writeln!(xml, "        <chord/>")?;
+            }
+            match value {
+                Some((pitch_note, _)) => {
+                    writeln!(xml, "        <pitch>")?;
+                    writeln!(xml, "          <step>{}</step>", pitch_note.pitch.name)?;
+                    let alter = MusicXmlUtil::pitch_alter(&pitch_note.pitch);
+                    if alter != 0 {
+                        writeln!(xml, "          <alter>{}</alter>", alter)?;
+                    }
+                    writeln!(
+                        xml,
+                        "          <octave>{}</octave>",
+                        MusicXmlUtil::note_octave(pitch_note)
+                    )?;
+                    writeln!(xml, "        </pitch>")?;
+                }
+                None => writeln!(xml, "        <rest/>")?,
+            }
+            writeln!(xml, "        <duration>{}</duration>", divisions)?;
+            if value.is_some() {
+                if note.tie_stop {
+                    writeln!(xml, r#"        <tie type="stop"/>"#)?;
+                }
+                if note.tie_start {
+                    writeln!(xml, r#"        <tie type="start"/>"#)?;
+                }
+            }
+            writeln!(xml, "        <voice>{}</voice>", voice)?;
+            self.write_duration_type(xml, &note.duration)?;
+            let fret = value.and_then(|x| x.1);
+            if value.is_some() && (note.tie_start || note.tie_stop || fret.is_some()) {
+                writeln!(xml, "        <notations>")?;
+                if note.tie_stop {
+                    writeln!(xml, r#"          <tied type="stop"/>"#)?;
+                }
+                if note.tie_start {
+                    writeln!(xml, r#"          <tied type="start"/>"#)?;
+                }
+                if let Some((string, fret)) = fret {
+                    writeln!(xml, "          <technical>")?;
+                    writeln!(xml, "            <string>{}</string>", string)?;
+                    writeln!(xml, "            <fret>{}</fret>", fret)?;
+                    writeln!(xml, "          </technical>")?;
+                }
+                writeln!(xml, "        </notations>")?;
+            }
+            if index == 0 {
+                if let Some((text, syllabic)) = lyric {
+                    writeln!(xml, r#"        <lyric number="1">"#)?;
+                    writeln!(xml, "          <syllabic>{}</syllabic>", syllabic)?;
+                    writeln!(xml, "          <text>{}</text>", MusicXmlUtil::escape(text))?;
+                    writeln!(xml, "        </lyric>")?;
+                }
+            }
+            writeln!(xml, "      </note>")?;
+        }
+    }
+    #[throws(std::fmt::Error)]
+    fn write_duration_type(&self, xml: &mut String, duration: &Duration) {
+        let unit = match duration.unit() {
+            Some(unit) => unit,
+            None => return,
+        };
+        writeln!(
+            xml,
+            "        <type>{}</type>",
+            MusicXmlUtil::unit_to_type(&unit)
+        )?;
+        for _ in 0..duration.dots() {
+            writeln!(xml, "        <dot/>")?;
+        }
+        if let Some((actual, normal)) = duration.tuplet() {
+            writeln!(xml, "        <time-modification>")?;
+            writeln!(xml, "          <actual-notes>{}</actual-notes>", actual)?;
+            writeln!(xml, "          <normal-notes>{}</normal-notes>", normal)?;
+            writeln!(xml, "        </time-modification>")?;
+        }
+    }
+}
+
+impl MusicXmlExporter {
+    fn get_chords(bar: &TabBar) -> Vec<(Rational, Chord)> {
+        let mut chords = Vec::new();
+        for lane in bar.lanes.values() {
+            if lane.kind != LaneKind::Chord {
+                continue;
+            }
+            for entry in lane.entries.iter() {
+                if let Some(chord) = entry.proto().as_core().and_then(|x| x.as_chord()) {
+                    chords.push((entry.in_bar_offset(), *chord));
+                }
+            }
+        }
+        chords.sort_by_key(|x| x.0);
+        chords
+    }
+    /// Lyrics are matched to the notes by the in bar position, and the
+    /// words ending with `-` are treated as syllables of the next one.
+    fn get_lyrics(&self, bar: &TabBar) -> HashMap<u32, (String, &'static str)> {
+        let mut lyrics = HashMap::new();
+        for lane in bar.lanes.values() {
+            if lane.kind != LaneKind::Lyrics {
+                continue;
+            }
+            for entry in lane.entries.iter() {
+                if let ProtoEntry::Lyric(LyricEntry::Word(word, _)) = entry.proto() {
+                    if entry.prev_is_tie() {
+                        continue;
+                    }
+                    let prev_hyphen = entry
+                        .model
+                        .prev()
+                        .and_then(|x| match x.proto.as_ref() {
+                            ProtoEntry::Lyric(LyricEntry::Word(w, _)) => {
+                                Some(w.text.ends_with('-'))
+                            }
+                            _ => None,
+                        })
+                        .unwrap_or(false);
+                    let hyphen = word.text.ends_with('-');
+                    let syllabic = match (prev_hyphen, hyphen) {
+                        (false, false) => "single",
+                        (false, true) => "begin",
+                        (true, true) => "middle",
+                        (true, false) => "end",
+                    };
+                    let text = word.text.trim_end_matches('-').to_string();
+                    lyrics.insert(self.calc_divisions(entry.in_bar_offset()), (text, syllabic));
+                }
+            }
+        }
+        lyrics
+    }
+    fn new_note(entry: &LaneEntry, notes: Vec<XmlPitch>) -> XmlNote {
+        XmlNote {
+            pos: entry.in_bar_offset(),
+            length: Rational::from(entry.duration()),
+            duration: entry.duration(),
+            notes,
+            tie_start: entry.model.get_tied_next().is_some(),
+            tie_stop: entry.prev_is_tie(),
+        }
+    }
+    fn get_tone_notes(bar: &TabBar, part: &Part) -> Vec<XmlNote> {
+        let mut notes = Vec::new();
+        for lane in bar.lanes.values() {
+            if lane.track.id != part.track.id {
+                continue;
+            }
+            for entry in lane.entries.iter() {
+                if entry.duration() == Duration::Zero {
+                    continue;
+                }
+                let tone_notes = match entry.proto() {
+                    ProtoEntry::Core(CoreEntry::Tone(tone, _)) => {
+                        tone.get_notes().into_iter().map(|x| (x, None)).collect()
+                    }
+                    ProtoEntry::Core(CoreEntry::Rest(_)) => vec![],
+                    _ => continue,
+                };
+                notes.push(Self::new_note(entry, tone_notes));
+            }
+        }
+        notes
+    }
+    fn get_pick_notes(bar: &TabBar, part: &Part) -> Vec<XmlNote> {
+        let meta = bar.meta.as_ref();
+        let mut notes = Vec::new();
+        for lane in bar.lanes.values() {
+            if lane.track.id != part.track.id || lane.kind != LaneKind::Strings {
+                continue;
+            }
+            for entry in lane.entries.iter() {
+                if entry.duration() == Duration::Zero {
+                    continue;
+                }
+                let pick_notes: Vec<PickNote> = match entry.model.as_fretted6() {
+                    Some(FrettedEntry6::Pick(pick, _)) => pick.get_notes(),
+                    Some(FrettedEntry6::Strum(strum, _)) => {
+                        let (begin, end) = match strum.strings {
+                            StrumStrings::All => (1, 6),
+                            StrumStrings::Between(x, y) => (x.min(y), x.max(y)),
+                        };
+                        (begin..=end).map(PickNote::new_string).collect()
+                    }
+                    _ => match entry.proto() {
+                        ProtoEntry::Core(CoreEntry::Rest(_)) => vec![],
+                        _ => continue,
+                    },
+                };
+                let fret_notes = match bar.get_fretted_shape6(entry) {
+                    Some((fretboard, shape)) => pick_notes
+                        .into_iter()
+                        .filter_map(|x| {
+                            fretboard
+                                .shape_pick_fret_note(&meta.scale, &meta.key, &shape, x)
+                                .map(|(fret, note)| (note, Some((x.string, fret))))
+                        })
+                        .collect(),
+                    None => vec![],
+                };
+                notes.push(Self::new_note(entry, fret_notes));
+            }
+        }
+        notes
+    }
+    /// Overlapping entries, e.g. from multiple slices in the same bar, are
+    /// written as separate voices.
+    fn split_voices(mut notes: Vec<XmlNote>) -> Vec<Vec<XmlNote>> {
+        notes.sort_by_key(|a| a.pos);
+        let mut voices: Vec<Vec<XmlNote>> = Vec::new();
+        for note in notes {
+            let voice = voices.iter_mut().find(|x| {
+                x.last()
+                    .map(|last| last.pos + last.length <= note.pos)
+                    .unwrap_or(true)
+            });
+            match voice {
+                Some(voice) => voice.push(note),
+                None => voices.push(vec![note]),
+            }
+        }
+        voices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import_util::{ImportSpan, ImportUtil, ImportValue};
+    use crate::midi_import::MidiImporter;
+    use crate::music_xml_import::MusicXmlImporter;
+
+    /// Quarter and triplet eighths with lyrics in 2/4, then a half note.
+    fn new_vocal_tab() -> ProtoTab {
+        let meta = TabMeta {
+            signature: Signature::new(Unit::Quarter, 2),
+            tempo: Tempo::Bpm(100),
+            ..TabMeta::default()
+        };
+        let spans: Vec<(u32, u32, u8, &str)> = vec![
+            (0, 24, 60, "Are"),
+            (24, 32, 62, "you"),
+            (32, 40, 64, "go-"),
+            (40, 48, 65, "ing"),
+            (48, 96, 67, "there"),
+        ];
+        let note = |key: u8| {
+            meta.scale
+                .calc_note_from_semitones(&meta.key, Semitones(key as i8 - 12))
+        };
+        let tones: Vec<ImportSpan> = spans
+            .iter()
+            .map(|x| ImportSpan::new(x.0, x.1, ImportValue::Tone(vec![note(x.2)]), false))
+            .collect();
+        let words: Vec<ImportSpan> = spans
+            .iter()
+            .map(|x| ImportSpan::new(x.0, x.1, ImportValue::Word(x.3.to_string()), false))
+            .collect();
+        let mut layers: Vec<Vec<ProtoBarLayer>> = vec![Vec::new(); 2];
+        let mut tracks = Vec::new();
+        for (id, kind, spans) in [
+            ("vocal", TrackKind::Vocal, tones),
+            ("lyrics", TrackKind::Lyrics, words),
+        ] {
+            let mut entries = Vec::new();
+            ImportUtil::push_bars(&mut entries, &mut layers, id, &[spans], 48, 24);
+            tracks.push(ProtoTrack::new(id.to_string(), kind, entries));
+        }
+        let (sections, form) = MidiImporter::default().new_sections(layers);
+        ProtoTab::new(ProtoTab::new_uuid().as_str(), meta, tracks, sections, form)
+    }
+
+    #[test]
+    fn test_export_triplets_and_lyrics() {
+        let xml = MusicXmlExporter::default()
+            .export(&new_vocal_tab())
+            .unwrap();
+        assert!(xml.contains("<divisions>24</divisions>"));
+        assert!(xml.contains("<time-modification>"));
+        assert!(xml.contains("<text>go</text>"));
+        assert!(xml.contains("<syllabic>begin</syllabic>"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tab = new_vocal_tab();
+        let xml = MusicXmlExporter::default().export(&tab).unwrap();
+        let round_trip = MusicXmlImporter::default().import(&xml).unwrap();
+        assert_eq!(round_trip.meta, tab.meta);
+        assert_eq!(round_trip.tracks.len(), tab.tracks.len());
+        for (track, expected) in round_trip.tracks.iter().zip(tab.tracks.iter()) {
+            assert_eq!(track.kind, expected.kind);
+            assert_eq!(track.entries, expected.entries);
+        }
+    }
+}
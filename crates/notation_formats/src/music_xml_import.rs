@@ -0,0 +1,622 @@
+use fehler::{throw, throws};
+use roxmltree::{Document, Node, ParsingOptions};
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+
+use notation_proto::prelude::*;
+
+use crate::import_util::{ImportSpan, ImportUtil, ImportValue};
+use crate::midi_import::MidiImporter;
+use crate::music_xml_util::MusicXmlUtil;
+
+#[derive(Error, Debug)]
+pub enum MusicXmlImportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("xml error")]
+    Xml(#[from] roxmltree::Error),
+    #[error("unsupported document")]
+    UnsupportedDocument(String),
+    #[error("no parts found")]
+    NoParts,
+}
+
+#[derive(Clone, Debug)]
+struct XmlEvent {
+    begin: u32,
+    end: u32,
+    notes: Vec<Note>,
+    picks: Vec<PickNote>,
+    tied: bool,
+    lyric: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct XmlPart {
+    name: String,
+    program: Option<u8>,
+    tab_staves: Vec<String>,
+    staff_lines: Option<usize>,
+    fretboard: Option<Fretboard6>,
+    voices: BTreeMap<String, Vec<XmlEvent>>,
+    measure_num: usize,
+}
+
+/// Imports MusicXML (partwise) files, staves are mapped to tone tracks, TAB staves
+/// to guitar tracks, harmonies to a chord track and lyrics to lyrics tracks.
+#[derive(Copy, Clone, Debug)]
+pub struct MusicXmlImporter {
+    pub bars_per_section: usize,
+}
+
+impl Default for MusicXmlImporter {
+    fn default() -> Self {
+        Self {
+            bars_per_section: 4,
+        }
+    }
+}
+
+impl MusicXmlImporter {
+    pub const QUARTER_STEPS: u32 = MidiImporter::GRID_STEPS / 4;
+
+    #[throws(MusicXmlImportError)]
+    pub fn import_file<P: AsRef<Path>>(&self, path: P) -> Tab {
+        let text = std::fs::read_to_string(path)?;
+        self.import(&text)?
+    }
+    #[throws(MusicXmlImportError)]
+    pub fn import(&self, text: &str) -> Tab {
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let doc = Document::parse_with_options(text, options)?;
+        let root = doc.root_element();
+        if root.tag_name().name() != "score-partwise" {
+            throw!(MusicXmlImportError::UnsupportedDocument(
+                root.tag_name().name().to_string()
+            ));
+        }
+        let meta = Self::calc_meta(&root);
+        let bar_steps = MidiImporter::duration_steps(Units::from(meta.signature));
+        let beat_steps = std::cmp::max(
+            MidiImporter::duration_steps(Units::from(meta.signature.beat_unit)),
+            MidiImporter::STRAIGHT_STEPS * MidiImporter::TRIPLET_STEPS,
+        );
+        let mut parts = Vec::new();
+        let mut harmonies = Vec::new();
+        for node in root.children().filter(|x| x.has_tag_name("part")) {
+            let name = node
+                .attribute("id")
+                .and_then(|id| Self::get_part_name(&root, id))
+                .unwrap_or_default();
+            parts.push(Self::parse_part(
+                &meta,
+                &node,
+                name,
+                bar_steps,
+                &mut harmonies,
+            ));
+        }
+        if parts.is_empty() {
+            throw!(MusicXmlImportError::NoParts);
+        }
+        let bar_num = parts.iter().map(|x| x.measure_num).max().unwrap_or(0);
+        let mut tracks: Vec<Track> = Vec::new();
+        let mut layers: Vec<Vec<BarLayer>> = vec![Vec::new(); bar_num];
+        let chord_spans = ImportUtil::calc_chord_spans(harmonies, bar_num as u32 * bar_steps);
+        if !chord_spans.is_empty() {
+            let id = ImportUtil::calc_track_id("chord", &tracks);
+            let mut entries = Vec::new();
+            ImportUtil::push_bars(
+                &mut entries,
+                &mut layers,
+                &id,
+                &[chord_spans],
+                bar_steps,
+                beat_steps,
+            );
+            tracks.push(Track::new(id, TrackKind::Chord, entries));
+        }
+        for (index, part) in parts.iter().enumerate() {
+            let name = if part.name.is_empty() {
+                format!("part{}", index + 1)
+            } else {
+                part.name.clone()
+            };
+            let id = ImportUtil::calc_track_id(&name, &tracks);
+            let tablature = !part.tab_staves.is_empty() && part.staff_lines.unwrap_or(6) == 6;
+            let mut entries = Vec::new();
+            let kind = if tablature {
+                let fretboard = part
+                    .fretboard
+                    .unwrap_or_else(GuitarUtil::new_default_fretboard);
+                entries.push(ProtoEntry::from(FrettedEntry6::Fretboard(fretboard)));
+                TrackKind::Guitar
+            } else {
+                Self::calc_track_kind(part)
+            };
+            let voice_spans: Vec<Vec<ImportSpan>> = part
+                .voices
+                .values()
+                .map(|x| Self::calc_voice_spans(x, tablature))
+                .collect();
+            ImportUtil::push_bars(
+                &mut entries,
+                &mut layers,
+                &id,
+                &voice_spans,
+                bar_steps,
+                beat_steps,
+            );
+            tracks.push(Track::new(id, kind, entries));
+            let lyric_spans = Self::calc_lyric_spans(part);
+            if !lyric_spans.is_empty() {
+                let id = ImportUtil::calc_track_id("lyrics", &tracks);
+                let mut entries = Vec::new();
+                ImportUtil::push_bars(
+                    &mut entries,
+                    &mut layers,
+                    &id,
+                    &[lyric_spans],
+                    bar_steps,
+                    beat_steps,
+                );
+                tracks.push(Track::new(id, TrackKind::Lyrics, entries));
+            }
+        }
+        let importer = MidiImporter {
+            bars_per_section: self.bars_per_section,
+        };
+        let (sections, form) = importer.new_sections(layers);
+        Tab::new(Tab::new_uuid().as_str(), meta, tracks, sections, form)
+    }
+}
+
+impl MusicXmlImporter {
+    fn child<'a, 'input>(node: &Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+        node.children().find(|x| x.has_tag_name(tag))
+    }
+    fn child_text<'a>(node: &Node<'a, '_>, tag: &str) -> Option<&'a str> {
+        Self::child(node, tag)
+            .and_then(|x| x.text())
+            .map(|x| x.trim())
+    }
+    fn child_value(node: &Node, tag: &str) -> Option<f32> {
+        Self::child_text(node, tag).and_then(|x| x.parse::<f32>().ok())
+    }
+    fn to_steps(value: f32, divisions: f32) -> u32 {
+        (value.max(0.0) * Self::QUARTER_STEPS as f32 / divisions).round() as u32
+    }
+    fn get_part_name(root: &Node, id: &str) -> Option<String> {
+        root.descendants()
+            .find(|x| x.has_tag_name("score-part") && x.attribute("id") == Some(id))
+            .and_then(|x| Self::child_text(&x, "part-name"))
+            .map(|x| x.to_string())
+    }
+    fn parse_pitch(node: &Node, prefix: &str) -> Option<Pitch> {
+        let step = Self::child_text(node, &format!("{}step", prefix))?;
+        let alter = Self::child_value(node, &format!("{}alter", prefix)).unwrap_or(0.0);
+        MusicXmlUtil::pitch_from_step_alter(step, alter.round() as i8)
+    }
+    fn parse_note(meta: &TabMeta, node: &Node) -> Option<Note> {
+        let pitch = Self::parse_pitch(node, "")?;
+        let octave = Self::child_value(node, "octave")?.clamp(-1.0, 9.0) as i8;
+        let octave = Octave::from(Semitones(octave * 12));
+        Some(meta.scale.calc_note_from_pitch(&meta.key, &pitch, &octave))
+    }
+}
+
+impl MusicXmlImporter {
+    fn calc_meta(root: &Node) -> TabMeta {
+        let mut meta = TabMeta::default();
+        if let Some(time) = root.descendants().find(|x| x.has_tag_name("time")) {
+            let bar_beats = Self::child_text(&time, "beats").and_then(|x| x.parse::<u8>().ok());
+            let beat_unit = Self::child_text(&time, "beat-type")
+                .and_then(|x| x.parse::<u8>().ok())
+                .and_then(MusicXmlUtil::unit_from_beat_type);
+            if let (Some(bar_beats), Some(beat_unit)) = (bar_beats, beat_unit) {
+                if bar_beats > 0 {
+                    meta.signature = Signature::new(beat_unit, bar_beats);
+                }
+            }
+        }
+        if let Some(key) = root.descendants().find(|x| x.has_tag_name("key")) {
+            if let Some(fifths) = Self::child_value(&key, "fifths") {
+                if let Some(mode) = Self::child_text(&key, "mode") {
+                    let mut ident = mode.to_string();
+                    if let Some(first) = ident.get_mut(0..1) {
+                        first.make_ascii_uppercase();
+                    }
+                    meta.scale = Scale::from_ident(&ident);
+                }
+                meta.key = meta.scale.calc_key_from_signature(fifths as i8);
+            }
+        }
+        let tempo = root
+            .descendants()
+            .filter(|x| x.has_tag_name("sound"))
+            .find_map(|x| x.attribute("tempo").and_then(|x| x.parse::<f32>().ok()));
+        if let Some(quarters_per_minute) = tempo {
+            let bpm = quarters_per_minute / (Units::from(meta.signature.beat_unit).0 * 4.0);
+            meta.tempo = Tempo::Bpm(bpm.round().clamp(1.0, u16::MAX as f32) as u16);
+        }
+        meta
+    }
+    // https://en.wikipedia.org/wiki/General_MIDI#Program_change_events
+    fn calc_track_kind(part: &XmlPart) -> TrackKind {
+        match part.program.unwrap_or(0) {
+            1..=8 => TrackKind::Piano,
+            _ => TrackKind::Vocal,
+        }
+    }
+    fn parse_attributes(part: &mut XmlPart, node: &Node, divisions: &mut f32) {
+        if let Some(value) = Self::child_value(node, "divisions") {
+            if value > 0.0 {
+                *divisions = value;
+            }
+        }
+        for clef in node.children().filter(|x| x.has_tag_name("clef")) {
+            if Self::child_text(&clef, "sign") == Some("TAB") {
+                let staff = clef.attribute("number").unwrap_or("1").to_string();
+                if !part.tab_staves.contains(&staff) {
+                    part.tab_staves.push(staff);
+                }
+            }
+        }
+        if let Some(details) = Self::child(node, "staff-details") {
+            let lines = Self::child_value(&details, "staff-lines").map(|x| x as usize);
+            if lines.is_some() {
+                part.staff_lines = lines;
+            }
+            let mut fretboard = part
+                .fretboard
+                .unwrap_or_else(GuitarUtil::new_default_fretboard);
+            let string_num = part.staff_lines.unwrap_or(6);
+            for tuning in details
+                .children()
+                .filter(|x| x.has_tag_name("staff-tuning"))
+            {
+                let line = tuning
+                    .attribute("line")
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .unwrap_or(0);
+                let pitch = Self::parse_pitch(&tuning, "tuning-");
+                let octave =
+                    Self::child_value(&tuning, "tuning-octave").map(|x| x.clamp(-1.0, 9.0));
+                if let (Some(pitch), Some(octave)) = (pitch, octave) {
+                    if line >= 1 && line <= string_num && string_num <= 6 {
+                        fretboard.string_notes[string_num - line] =
+                            MusicXmlUtil::note_semitones(&pitch, octave as i8);
+                    }
+                }
+            }
+            if let Some(capo) = Self::child_value(&details, "capo") {
+                fretboard.capo = capo as u8;
+            }
+            part.fretboard = Some(fretboard);
+        }
+    }
+    fn parse_harmony(meta: &TabMeta, node: &Node) -> Option<Chord> {
+        let kind = Self::child_text(node, "kind").unwrap_or("major");
+        if kind == "none" {
+            return None;
+        }
+        let root = Self::parse_pitch(&Self::child(node, "root")?, "root-")?;
+        let degrees: Vec<(u8, i8, String)> = node
+            .children()
+            .filter(|x| x.has_tag_name("degree"))
+            .filter_map(|x| {
+                let value = Self::child_value(&x, "degree-value")?;
+                let alter = Self::child_value(&x, "degree-alter").unwrap_or(0.0);
+                let degree_type = Self::child_text(&x, "degree-type").unwrap_or("add");
+                Some((value as u8, alter.round() as i8, degree_type.to_string()))
+            })
+            .collect();
+        let intervals = MusicXmlUtil::chord_intervals(kind, &degrees);
+        let bass = Self::child(node, "bass")
+            .and_then(|x| Self::parse_pitch(&x, "bass-"))
+            .map(|bass| {
+                let semitones = Semitones::from(bass).0 - Semitones::from(root).0;
+                Interval::from(Semitones(semitones.rem_euclid(12)))
+            });
+        let syllable = meta.scale.calc_syllable(&meta.key, &root);
+        Some(Chord::new(syllable, intervals, bass))
+    }
+    fn parse_lyric(node: &Node) -> Option<String> {
+        let lyric = Self::child(node, "lyric")?;
+        let text = Self::child_text(&lyric, "text")?;
+        Some(match Self::child_text(&lyric, "syllabic") {
+            Some("begin") | Some("middle") => format!("{}-", text),
+            _ => text.to_string(),
+        })
+    }
+    fn parse_part(
+        meta: &TabMeta,
+        node: &Node,
+        name: String,
+        bar_steps: u32,
+        harmonies: &mut Vec<(u32, Chord)>,
+    ) -> XmlPart {
+        let mut part = XmlPart {
+            name,
+            program: node
+                .document()
+                .descendants()
+                .find(|x| x.has_tag_name("score-part") && x.attribute("id") == node.attribute("id"))
+                .and_then(|x| x.descendants().find(|y| y.has_tag_name("midi-program")))
+                .and_then(|x| x.text())
+                .and_then(|x| x.trim().parse::<u8>().ok()),
+            ..Default::default()
+        };
+        let mut divisions = 1.0;
+        for (index, measure) in node
+            .children()
+            .filter(|x| x.has_tag_name("measure"))
+            .enumerate()
+        {
+            part.measure_num = index + 1;
+            let bar_begin = index as u32 * bar_steps;
+            let mut pos = 0.0f32;
+            let mut last_begin = 0.0f32;
+            for child in measure.children().filter(|x| x.is_element()) {
+                let duration = Self::child_value(&child, "duration").unwrap_or(0.0);
+                match child.tag_name().name() {
+                    "attributes" => Self::parse_attributes(&mut part, &child, &mut divisions),
+                    "backup" => pos = (pos - duration).max(0.0),
+                    "forward" => pos += duration,
+                    "harmony" => {
+                        let offset = Self::child_value(&child, "offset").unwrap_or(0.0);
+                        if let Some(chord) = Self::parse_harmony(meta, &child) {
+                            let begin = bar_begin + Self::to_steps(pos + offset, divisions);
+                            harmonies.push((begin, chord));
+                        }
+                    }
+                    "note" => {
+                        if Self::child(&child, "grace").is_some() {
+                            continue;
+                        }
+                        let begin = if Self::child(&child, "chord").is_some() {
+                            last_begin
+                        } else {
+                            last_begin = pos;
+                            pos += duration;
+                            last_begin
+                        };
+                        let staff = Self::child_text(&child, "staff").unwrap_or("1");
+                        if Self::child(&child, "cue").is_some()
+                            || !part.tab_staves.is_empty()
+                                && !part.tab_staves.iter().any(|x| x == staff)
+                        {
+                            continue;
+                        }
+                        let voice = Self::child_text(&child, "voice").unwrap_or("1");
+                        Self::add_event(
+                            meta,
+                            &mut part,
+                            &child,
+                            voice,
+                            bar_begin + Self::to_steps(begin, divisions),
+                            bar_begin + Self::to_steps(begin + duration, divisions),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        part
+    }
+    fn add_event(
+        meta: &TabMeta,
+        part: &mut XmlPart,
+        node: &Node,
+        voice: &str,
+        begin: u32,
+        end: u32,
+    ) {
+        let note = Self::child(node, "pitch").and_then(|x| Self::parse_note(meta, &x));
+        let pick = Self::child(node, "notations")
+            .and_then(|x| Self::child(&x, "technical"))
+            .and_then(|x| {
+                let string = Self::child_value(&x, "string")? as u8;
+                let fret = Self::child_value(&x, "fret")? as u8;
+                Some(PickNote::new_string_fret(string, fret))
+            });
+        let tied = node
+            .children()
+            .any(|x| x.has_tag_name("tie") && x.attribute("type") == Some("stop"));
+        let events = part.voices.entry(voice.to_string()).or_default();
+        let event = match events.last_mut() {
+            Some(last) if last.begin == begin && Self::child(node, "chord").is_some() => last,
+            _ => {
+                events.push(XmlEvent {
+                    begin,
+                    end,
+                    notes: vec![],
+                    picks: vec![],
+                    tied,
+                    lyric: Self::parse_lyric(node),
+                });
+                events.last_mut().unwrap()
+            }
+        };
+        if let Some(note) = note {
+            event.notes.push(note);
+        }
+        if let Some(pick) = pick {
+            event.picks.push(pick);
+        }
+    }
+}
+
+impl MusicXmlImporter {
+    fn calc_voice_spans(events: &[XmlEvent], tablature: bool) -> Vec<ImportSpan> {
+        let mut spans = Vec::new();
+        for (index, event) in events.iter().enumerate() {
+            let value = if tablature {
+                if event.picks.is_empty() {
+                    continue;
+                }
+                ImportValue::Pick6(event.picks.clone())
+            } else {
+                if event.notes.is_empty() {
+                    continue;
+                }
+                ImportValue::Tone(event.notes.clone())
+            };
+            let end = match events.get(index + 1) {
+                Some(next) => std::cmp::min(event.end, next.begin),
+                None => event.end,
+            };
+            if event.begin < end {
+                spans.push(ImportSpan::new(event.begin, end, value, event.tied));
+            }
+        }
+        spans
+    }
+    /// Lyrics are taken from the first voice with lyrics, each word lasts until
+    /// the end of its tied notes.
+    fn calc_lyric_spans(part: &XmlPart) -> Vec<ImportSpan> {
+        let events = match part
+            .voices
+            .values()
+            .find(|x| x.iter().any(|y| y.lyric.is_some()))
+        {
+            Some(x) => x,
+            None => return vec![],
+        };
+        let mut spans: Vec<ImportSpan> = Vec::new();
+        for event in events.iter() {
+            match (&event.lyric, spans.last_mut()) {
+                (Some(text), _) => spans.push(ImportSpan::new(
+                    event.begin,
+                    event.end,
+                    ImportValue::Word(text.clone()),
+                    false,
+                )),
+                (None, Some(last)) if event.tied && last.end == event.begin => {
+                    last.end = event.end;
+                }
+                _ => {}
+            }
+        }
+        for index in 1..spans.len() {
+            if spans[index - 1].end > spans[index].begin {
+                spans[index - 1].end = spans[index].begin;
+            }
+        }
+        spans.retain(|x| x.begin < x.end);
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music_xml_export::MusicXmlExporter;
+
+    const GUITAR_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<score-partwise version="4.0">
+  <part-list>
+    <score-part id="P1"><part-name>guitar</part-name></score-part>
+  </part-list>
+  <part id="P1">
+    <measure number="1">
+      <attributes>
+        <divisions>2</divisions>
+        <key><fifths>1</fifths><mode>major</mode></key>
+        <time><beats>3</beats><beat-type>4</beat-type></time>
+        <clef><sign>TAB</sign><line>5</line></clef>
+        <staff-details><staff-lines>6</staff-lines></staff-details>
+      </attributes>
+      <sound tempo="90"/>
+      <harmony><root><root-step>G</root-step></root><kind>major</kind></harmony>
+      <note>
+        <pitch><step>G</step><octave>2</octave></pitch>
+        <duration>2</duration><voice>1</voice><type>quarter</type>
+        <notations><technical><string>6</string><fret>3</fret></technical></notations>
+      </note>
+      <note>
+        <pitch><step>D</step><octave>3</octave></pitch>
+        <duration>2</duration><voice>1</voice><type>quarter</type>
+        <notations><technical><string>4</string><fret>0</fret></technical></notations>
+      </note>
+      <note>
+        <pitch><step>B</step><octave>3</octave></pitch>
+        <duration>1</duration><voice>1</voice><type>eighth</type>
+        <notations><technical><string>2</string><fret>0</fret></technical></notations>
+      </note>
+      <note>
+        <chord/>
+        <pitch><step>G</step><octave>4</octave></pitch>
+        <duration>1</duration><voice>1</voice><type>eighth</type>
+        <notations><technical><string>1</string><fret>3</fret></technical></notations>
+      </note>
+      <note>
+        <rest/>
+        <duration>1</duration><voice>1</voice><type>eighth</type>
+      </note>
+    </measure>
+  </part>
+</score-partwise>
+"#;
+
+    fn get_track(tab: &Tab, kind: TrackKind) -> &Track {
+        tab.tracks.iter().find(|x| x.kind == kind).unwrap()
+    }
+
+    #[test]
+    fn test_import_tablature() {
+        let tab = MusicXmlImporter::default().import(GUITAR_XML).unwrap();
+        assert_eq!(tab.meta.key, Key::Natural(PitchName::G));
+        assert_eq!(tab.meta.scale, Scale::Ionian);
+        assert_eq!(tab.meta.signature, Signature::new(Unit::Quarter, 3));
+        assert_eq!(tab.meta.tempo, Tempo::Bpm(90));
+        let picks: Vec<(Vec<PickNote>, Duration)> = get_track(&tab, TrackKind::Guitar)
+            .entries
+            .iter()
+            .filter_map(|x| match x.as_fretted6() {
+                Some(FrettedEntry6::Pick(pick, duration)) => Some((pick.get_notes(), *duration)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            picks,
+            vec![
+                (
+                    vec![PickNote::new_string_fret(6, 3)],
+                    Duration::Simple(Unit::Quarter)
+                ),
+                (
+                    vec![PickNote::new_string_fret(4, 0)],
+                    Duration::Simple(Unit::Quarter)
+                ),
+                (
+                    vec![
+                        PickNote::new_string_fret(2, 0),
+                        PickNote::new_string_fret(1, 3)
+                    ],
+                    Duration::Simple(Unit::Eighth)
+                ),
+            ]
+        );
+        let chords = get_track(&tab, TrackKind::Chord).entries.len();
+        assert_eq!(chords, 1);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tab = MusicXmlImporter::default().import(GUITAR_XML).unwrap();
+        let xml = MusicXmlExporter::default().export(&tab).unwrap();
+        let round_trip = MusicXmlImporter::default().import(&xml).unwrap();
+        assert_eq!(round_trip.meta, tab.meta);
+        for kind in [TrackKind::Guitar, TrackKind::Chord] {
+            assert_eq!(
+                get_track(&round_trip, kind).entries,
+                get_track(&tab, kind).entries
+            );
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use notation_proto::prelude::*;
+
+// https://www.w3.org/2021/06/musicxml40/
+pub struct MusicXmlUtil();
+
+impl MusicXmlUtil {
+    pub fn escape(text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => result.push_str("&amp;"),
+                '<' => result.push_str("&lt;"),
+                '>' => result.push_str("&gt;"),
+                '"' => result.push_str("&quot;"),
+                '\'' => result.push_str("&apos;"),
+                _ => result.push(c),
+            }
+        }
+        result
+    }
+    pub fn unit_to_type(unit: &Unit) -> &'static str {
+        match unit {
+            Unit::Whole => "whole",
+            Unit::Half => "half",
+            Unit::Quarter => "quarter",
+            Unit::Eighth => "eighth",
+            Unit::Sixteenth => "16th",
+            Unit::ThirtySecondth => "32nd",
+            Unit::SixtyFourth => "64th",
+        }
+    }
+    pub fn unit_from_type(text: &str) -> Option<Unit> {
+        match text {
+            "whole" => Some(Unit::Whole),
+            "half" => Some(Unit::Half),
+            "quarter" => Some(Unit::Quarter),
+            "eighth" => Some(Unit::Eighth),
+            "16th" => Some(Unit::Sixteenth),
+            "32nd" => Some(Unit::ThirtySecondth),
+            "64th" => Some(Unit::SixtyFourth),
+            _ => None,
+        }
+    }
+    pub fn unit_to_beat_type(unit: &Unit) -> u8 {
+        unit.to_denom()
+    }
+    pub fn unit_from_beat_type(beat_type: u8) -> Option<Unit> {
+        Unit::from_denom(beat_type as u32)
+    }
+    pub fn pitch_alter(pitch: &Pitch) -> i8 {
+        match pitch.sign {
+            PitchSign::DoubleFlat => -2,
+            PitchSign::Flat => -1,
+            PitchSign::Natural => 0,
+            PitchSign::Sharp => 1,
+            PitchSign::DoubleSharp => 2,
+        }
+    }
+    pub fn pitch_from_step_alter(step: &str, alter: i8) -> Option<Pitch> {
+        let name = match step {
+            "C" => PitchName::C,
+            "D" => PitchName::D,
+            "E" => PitchName::E,
+            "F" => PitchName::F,
+            "G" => PitchName::G,
+            "A" => PitchName::A,
+            "B" => PitchName::B,
+            _ => return None,
+        };
+        let sign = match alter {
+            -2 => PitchSign::DoubleFlat,
+            -1 => PitchSign::Flat,
+            1 => PitchSign::Sharp,
+            2 => PitchSign::DoubleSharp,
+            _ => PitchSign::Natural,
+        };
+        Some(Pitch { name, sign })
+    }
+    /// The octave in MusicXML is for the written step, e.g. B#3 is the same as C4.
+    pub fn note_octave(note: &Note) -> i8 {
+        let natural = Pitch {
+            name: note.pitch.name,
+            sign: PitchSign::Natural,
+        };
+        let natural_semitones = Semitones::from(*note).0 - Self::pitch_alter(&note.pitch);
+        (natural_semitones - Semitones::from(natural).0).div_euclid(12)
+    }
+    pub fn note_semitones(pitch: &Pitch, octave: i8) -> Semitones {
+        let natural = Pitch {
+            name: pitch.name,
+            sign: PitchSign::Natural,
+        };
+        Semitones(octave * 12 + Semitones::from(natural).0 + Self::pitch_alter(pitch))
+    }
+    fn interval_semitones(intervals: &Intervals) -> Vec<i8> {
+        let mut result: Vec<i8> = intervals
+            .get_intervals()
+            .iter()
+            .map(|x| Semitones::from(*x).0 % 12)
+            .filter(|x| *x != 0)
+            .collect();
+        result.sort();
+        result.dedup();
+        result
+    }
+    pub const CHORD_KINDS: [(&'static str, &'static [i8]); 16] = [
+        ("major", &[4, 7]),
+        ("minor", &[3, 7]),
+        ("augmented", &[4, 8]),
+        ("diminished", &[3, 6]),
+        ("dominant", &[4, 7, 10]),
+        ("major-seventh", &[4, 7, 11]),
+        ("minor-seventh", &[3, 7, 10]),
+        ("diminished-seventh", &[3, 6, 9]),
+        ("augmented-seventh", &[4, 8, 10]),
+        ("half-diminished", &[3, 6, 10]),
+        ("major-minor", &[3, 7, 11]),
+        ("major-sixth", &[4, 7, 9]),
+        ("minor-sixth", &[3, 7, 9]),
+        ("suspended-second", &[2, 7]),
+        ("suspended-fourth", &[5, 7]),
+        ("power", &[7]),
+    ];
+    fn degree_of_semitones(semitones: i8) -> (u8, i8) {
+        match semitones {
+            1 => (9, -1),
+            2 => (9, 0),
+            3 => (9, 1),
+            5 => (11, 0),
+            6 => (11, 1),
+            8 => (13, -1),
+            9 => (13, 0),
+            10 => (7, -1),
+            11 => (7, 0),
+            _ => (5, semitones - 7),
+        }
+    }
+    /// Returns the kind, and extra degrees as (value, alter), the kind is the
+    /// largest one contained in the intervals.
+    pub fn chord_kind(intervals: &Intervals) -> (&'static str, Vec<(u8, i8)>) {
+        let semitones = Self::interval_semitones(intervals);
+        let (kind, kind_semitones) = Self::CHORD_KINDS
+            .iter()
+            .filter(|(_, x)| x.iter().all(|v| semitones.contains(v)))
+            .max_by_key(|(_, x)| x.len())
+            .copied()
+            .unwrap_or(Self::CHORD_KINDS[0]);
+        let degrees = semitones
+            .iter()
+            .filter(|x| !kind_semitones.contains(*x))
+            .map(|x| Self::degree_of_semitones(*x))
+            .collect();
+        (kind, degrees)
+    }
+    pub fn chord_intervals(kind: &str, degrees: &[(u8, i8, String)]) -> Intervals {
+        let mut semitones: Vec<i8> = Self::CHORD_KINDS
+            .iter()
+            .find(|(x, _)| *x == kind)
+            .map(|(_, v)| v.to_vec())
+            .unwrap_or_else(|| match kind {
+                "major-ninth" => vec![4, 7, 11, 2],
+                "minor-ninth" => vec![3, 7, 10, 2],
+                "dominant-ninth" => vec![4, 7, 10, 2],
+                "dominant-11th" => vec![4, 7, 10, 5],
+                "dominant-13th" => vec![4, 7, 10, 9],
+                "none" => vec![],
+                _ => vec![4, 7],
+            });
+        for (value, alter, degree_type) in degrees.iter() {
+            let natural = match value % 7 {
+                2 => 2,
+                3 => 4,
+                4 => 5,
+                5 => 7,
+                6 => 9,
+                0 => 11,
+                _ => 0,
+            };
+            let target = (natural + alter).rem_euclid(12);
+            match degree_type.as_str() {
+                "subtract" => semitones.retain(|x| *x != natural),
+                "alter" => {
+                    semitones.retain(|x| *x != natural);
+                    semitones.push(target);
+                }
+                _ => semitones.push(target),
+            }
+        }
+        semitones.sort();
+        semitones.dedup();
+        let intervals: Vec<Interval> = semitones
+            .into_iter()
+            .filter(|x| *x != 0)
+            .map(|x| match x {
+                6 if kind.contains("diminished") => Interval::Diminished5th,
+                8 if kind.contains("augmented") => Interval::Augmented5th,
+                9 if kind == "diminished-seventh" => Interval::Diminished7th,
+                _ => Interval::from(Semitones(x)),
+            })
+            .take(6)
+            .collect();
+        if intervals.is_empty() {
+            Intervals::Monad
+        } else {
+            intervals.into()
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use fehler::{throw, throws};
+use std::path::Path;
+use thiserror::Error;
+
+use notation_proto::prelude::*;
+
+#[derive(Error, Debug)]
+pub enum ScalaImportError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("unexpected end of file, missing {0}")]
+    UnexpectedEnd(&'static str),
+    #[error("invalid {0} at line {1}: {2}")]
+    InvalidValue(&'static str, usize, String),
+}
+
+// http://www.huygens-fokker.org/scala/scl_format.html
+/// Imports Scala scale (`.scl`) and keyboard mapping (`.kbm`) files, lines
+/// starting with `!` are comments.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ScalaImporter {}
+
+impl ScalaImporter {
+    #[throws(ScalaImportError)]
+    pub fn import_file<P: AsRef<Path>>(&self, scl: P, kbm: Option<P>) -> ScalaTuning {
+        let mut tuning = self.import_scl(&std::fs::read_to_string(scl)?)?;
+        if let Some(kbm) = kbm {
+            tuning.mapping = self.import_kbm(&std::fs::read_to_string(kbm)?)?;
+        }
+        tuning
+    }
+    /// The description is the first line that is not a comment, can be empty.
+    #[throws(ScalaImportError)]
+    pub fn import_scl(&self, text: &str) -> ScalaTuning {
+        let mut lines = Self::value_lines(text, false);
+        let (_, description) = lines
+            .next()
+            .ok_or(ScalaImportError::UnexpectedEnd("description"))?;
+        let count = Self::next_value::<usize>(&mut lines, "note count")?;
+        let mut cents = Vec::new();
+        for _ in 0..count {
+            let (index, line) = lines
+                .next()
+                .ok_or(ScalaImportError::UnexpectedEnd("pitch"))?;
+            match Self::parse_pitch(line) {
+                Some(x) => cents.push(x),
+                None => throw!(ScalaImportError::InvalidValue(
+                    "pitch",
+                    index + 1,
+                    line.to_owned()
+                )),
+            }
+        }
+        ScalaTuning::new(
+            description.trim().to_owned(),
+            cents,
+            KeyboardMapping::default(),
+        )
+    }
+    #[throws(ScalaImportError)]
+    pub fn import_kbm(&self, text: &str) -> KeyboardMapping {
+        let mut lines = Self::value_lines(text, true);
+        let size = Self::next_value::<usize>(&mut lines, "map size")?;
+        let first_key = Self::next_value::<u8>(&mut lines, "first key")?;
+        let last_key = Self::next_value::<u8>(&mut lines, "last key")?;
+        let middle_key = Self::next_value::<u8>(&mut lines, "middle key")?;
+        let reference_key = Self::next_value::<u8>(&mut lines, "reference key")?;
+        let reference_frequency = Self::next_value::<f32>(&mut lines, "reference frequency")?;
+        let octave_degree = Self::next_value::<usize>(&mut lines, "octave degree")?;
+        let mut degrees = Vec::new();
+        for _ in 0..size {
+            // missing entries at the end are unmapped
+            match lines.next() {
+                Some((_, line)) if line.starts_with('x') => degrees.push(None),
+                Some((index, line)) => match Self::first_token(line).parse::<usize>() {
+                    Ok(x) => degrees.push(Some(x)),
+                    Err(_) => {
+                        throw!(ScalaImportError::InvalidValue(
+                            "degree",
+                            index + 1,
+                            line.to_owned()
+                        ))
+                    }
+                },
+                None => degrees.push(None),
+            }
+        }
+        KeyboardMapping {
+            first_key,
+            last_key,
+            middle_key,
+            reference_key,
+            reference_frequency: Some(reference_frequency),
+            octave_degree,
+            degrees,
+        }
+    }
+}
+
+impl ScalaImporter {
+    fn value_lines(text: &str, skip_empty: bool) -> impl Iterator<Item = (usize, &str)> {
+        text.lines()
+            .enumerate()
+            .filter(move |(_, x)| !(x.starts_with('!') || skip_empty && x.trim().is_empty()))
+    }
+    fn first_token(line: &str) -> &str {
+        line.split_whitespace().next().unwrap_or("")
+    }
+    #[throws(ScalaImportError)]
+    fn next_value<'a, T: std::str::FromStr>(
+        lines: &mut impl Iterator<Item = (usize, &'a str)>,
+        name: &'static str,
+    ) -> T {
+        let (index, line) = lines.next().ok_or(ScalaImportError::UnexpectedEnd(name))?;
+        match Self::first_token(line).parse::<T>() {
+            Ok(x) => x,
+            Err(_) => throw!(ScalaImportError::InvalidValue(
+                name,
+                index + 1,
+                line.to_owned()
+            )),
+        }
+    }
+    /// Values with a period are in cents, otherwise they are ratios, e.g. `3/2` or `2`.
+    fn parse_pitch(line: &str) -> Option<f32> {
+        let token = Self::first_token(line);
+        if token.contains('.') {
+            return token.parse::<f32>().ok();
+        }
+        let (numerator, denominator) = match token.split_once('/') {
+            Some((n, d)) => (n.parse::<f32>().ok()?, d.parse::<f32>().ok()?),
+            None => (token.parse::<f32>().ok()?, 1.0),
+        };
+        if numerator <= 0.0 || denominator <= 0.0 {
+            return None;
+        }
+        Some(1200.0 * (numerator / denominator).log2())
+    }
+}
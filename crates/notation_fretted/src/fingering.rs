@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{Finger, PickNote};
+
+use super::prelude::{Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8};
+use notation_core::prelude::{Key, Scale, Semitones, Tone};
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct FingeringSettings {
+    /// Frets between the index finger and the highest fretted note.
+    pub max_stretch: u8,
+    /// Play the notes on open strings when possible.
+    pub prefer_open: bool,
+}
+
+impl Default for FingeringSettings {
+    fn default() -> Self {
+        Self {
+            max_stretch: 3,
+            prefer_open: false,
+        }
+    }
+}
+
+/// Strings and frets of the notes of a tone, with the cost of the hand shape.
+#[derive(Clone, Debug)]
+struct FingeringCandidate {
+    notes: Vec<(u8, u8)>,
+    cost: u32,
+}
+
+impl FingeringCandidate {
+    /// Lowest and highest fretted frets, `None` if all strings are open.
+    fn calc_fret_range(notes: &[(u8, u8)]) -> Option<(u8, u8)> {
+        let frets = notes.iter().map(|x| x.1).filter(|x| *x > 0);
+        Some((frets.clone().min()?, frets.max()?))
+    }
+    fn fret_range(&self) -> Option<(u8, u8)> {
+        Self::calc_fret_range(&self.notes)
+    }
+}
+
+/// Position is the fret of the index finger, 0 for not decided yet.
+#[derive(Copy, Clone, Debug)]
+struct FingeringState {
+    candidate: usize,
+    position: u8,
+    cost: u32,
+    prev: Option<usize>,
+}
+
+pub struct FingeringSolver();
+
+impl FingeringSolver {
+    pub const MAX_CANDIDATES: usize = 24;
+    pub const SKIP_COST: u32 = 100;
+    pub const FRET_COST: u32 = 2;
+    pub const OPEN_COST: u32 = 3;
+    pub const STRETCH_COST: u32 = 1;
+    pub const MOVE_COST: u32 = 3;
+    /// Added for every 4 frets up the neck, so lower positions are preferred.
+    pub const POSITION_COST: u32 = 1;
+
+    fn calc_note_cost(settings: &FingeringSettings, fret: u8) -> u32 {
+        if fret > 0 {
+            Self::FRET_COST
+        } else if settings.prefer_open {
+            0
+        } else {
+            Self::OPEN_COST
+        }
+    }
+    /// Each note can be skipped with a high cost, so tones with more notes
+    /// than strings, or out of the fretboard, are still played partly.
+    fn add_candidates(
+        settings: &FingeringSettings,
+        note_positions: &[Vec<(u8, u8)>],
+        notes: &mut Vec<(u8, u8)>,
+        cost: u32,
+        candidates: &mut Vec<FingeringCandidate>,
+    ) {
+        let (positions, rest) = match note_positions.split_first() {
+            Some(x) => x,
+            None => {
+                let mut candidate = FingeringCandidate {
+                    notes: notes.clone(),
+                    cost,
+                };
+                if let Some((min, max)) = candidate.fret_range() {
+                    candidate.cost += (max - min) as u32 * Self::STRETCH_COST;
+                }
+                candidates.push(candidate);
+                return;
+            }
+        };
+        for (string, fret) in positions.iter() {
+            if notes.iter().any(|x| x.0 == *string) {
+                continue;
+            }
+            notes.push((*string, *fret));
+            let fit = match FingeringCandidate::calc_fret_range(notes) {
+                Some((min, max)) => max - min <= settings.max_stretch,
+                None => true,
+            };
+            if fit {
+                let note_cost = Self::calc_note_cost(settings, *fret);
+                Self::add_candidates(settings, rest, notes, cost + note_cost, candidates);
+            }
+            notes.pop();
+        }
+        Self::add_candidates(settings, rest, notes, cost + Self::SKIP_COST, candidates);
+    }
+    fn calc_candidates(
+        settings: &FingeringSettings,
+        note_positions: &[Vec<(u8, u8)>],
+    ) -> Vec<FingeringCandidate> {
+        let mut candidates = Vec::new();
+        Self::add_candidates(settings, note_positions, &mut vec![], 0, &mut candidates);
+        candidates.sort_by_key(|x| x.cost);
+        candidates.truncate(Self::MAX_CANDIDATES);
+        candidates
+    }
+    fn calc_move_cost(from: u8, to: u8) -> u32 {
+        if from == 0 || to == 0 {
+            0
+        } else {
+            (from as i16 - to as i16).unsigned_abs() as u32 * Self::MOVE_COST
+        }
+    }
+    fn calc_finger(position: u8, fret: u8) -> Option<Finger> {
+        if fret == 0 || position == 0 {
+            None
+        } else {
+            Some(match fret.saturating_sub(position) {
+                0 => Finger::Index,
+                1 => Finger::Middle,
+                2 => Finger::Ring,
+                _ => Finger::Pinky,
+            })
+        }
+    }
+    /// Returns the pick notes of each tone, with the positions of the notes
+    /// on the fretboard as `(string, fret)`, the hand only moves when the
+    /// notes are out of the stretch of the current position, or when it's
+    /// much cheaper to play in another one.
+    pub fn solve(
+        settings: &FingeringSettings,
+        fret_num: usize,
+        tones: &[Vec<Vec<(u8, u8)>>],
+    ) -> Vec<Vec<PickNote>> {
+        let mut candidates = Vec::new();
+        let mut states: Vec<Vec<FingeringState>> = Vec::new();
+        for note_positions in tones.iter() {
+            let tone_candidates = Self::calc_candidates(settings, note_positions);
+            let mut best_prev: Vec<Option<(u32, Option<usize>)>> = vec![None; fret_num + 1];
+            match states.last() {
+                Some(prev_states) => {
+                    for (index, state) in prev_states.iter().enumerate() {
+                        let best = &mut best_prev[state.position as usize];
+                        if best.is_none() || best.unwrap().0 > state.cost {
+                            *best = Some((state.cost, Some(index)));
+                        }
+                    }
+                }
+                None => best_prev[0] = Some((0, None)),
+            }
+            let mut tone_states = Vec::new();
+            for (candidate_index, candidate) in tone_candidates.iter().enumerate() {
+                match candidate.fret_range() {
+                    Some((min, max)) => {
+                        let lowest = std::cmp::max(1, max.saturating_sub(settings.max_stretch));
+                        for position in lowest..=min {
+                            let prev = best_prev
+                                .iter()
+                                .enumerate()
+                                .filter_map(|(prev_position, x)| {
+                                    x.map(|(cost, prev)| {
+                                        let move_cost =
+                                            Self::calc_move_cost(prev_position as u8, position);
+                                        (cost + move_cost, prev)
+                                    })
+                                })
+                                .min_by_key(|x| x.0);
+                            if let Some((cost, prev)) = prev {
+                                tone_states.push(FingeringState {
+                                    candidate: candidate_index,
+                                    position,
+                                    cost: cost
+                                        + candidate.cost
+                                        + position as u32 / 4 * Self::POSITION_COST,
+                                    prev,
+                                });
+                            }
+                        }
+                    }
+                    None => {
+                        for (position, x) in best_prev.iter().enumerate() {
+                            if let Some((cost, prev)) = x {
+                                tone_states.push(FingeringState {
+                                    candidate: candidate_index,
+                                    position: position as u8,
+                                    cost: cost + candidate.cost,
+                                    prev: *prev,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            candidates.push(tone_candidates);
+            states.push(tone_states);
+        }
+        let mut result = vec![vec![]; tones.len()];
+        let mut current = states
+            .last()
+            .and_then(|x| x.iter().enumerate().min_by_key(|(_, state)| state.cost))
+            .map(|x| x.0);
+        for tone_index in (0..tones.len()).rev() {
+            let state = match current {
+                Some(index) => states[tone_index][index],
+                None => break,
+            };
+            let candidate = &candidates[tone_index][state.candidate];
+            let mut notes: Vec<PickNote> = candidate
+                .notes
+                .iter()
+                .map(|(string, fret)| {
+                    let finger = Self::calc_finger(state.position, *fret);
+                    PickNote::new(*string, Some(*fret), finger, None, None)
+                })
+                .collect();
+            notes.sort_by_key(|x| x.string);
+            result[tone_index] = notes;
+            current = state.prev;
+        }
+        result
+    }
+}
+
+macro_rules! impl_fingering {
+    ($type:ident) => {
+        impl $type {
+            /// Strings and frets sounding the note, capo included.
+            pub fn calc_note_positions(
+                &self,
+                scale: &Scale,
+                key: &Key,
+                note: Semitones,
+            ) -> Vec<(u8, u8)> {
+                let mut positions = vec![];
+                for string in 1..=self.string_num() as u8 {
+                    for fret in 0..self.fret_num() as u8 {
+                        if let Some(fretted) = self.fretted_note(scale, key, string, fret) {
+                            if Semitones::from(fretted) == note {
+                                positions.push((string, fret));
+                            }
+                        }
+                    }
+                }
+                positions
+            }
+            /// Pick notes of the tones with the least hand movement, notes can't
+            /// be played on the fretboard are left out.
+            pub fn calc_fingering(
+                &self,
+                scale: &Scale,
+                key: &Key,
+                tones: &[Tone],
+                settings: &FingeringSettings,
+            ) -> Vec<Vec<PickNote>> {
+                let tones: Vec<Vec<Vec<(u8, u8)>>> = tones
+                    .iter()
+                    .map(|tone| {
+                        tone.get_notes()
+                            .into_iter()
+                            .map(|note| self.calc_note_positions(scale, key, Semitones::from(note)))
+                            .collect()
+                    })
+                    .collect();
+                FingeringSolver::solve(settings, self.fret_num(), &tones)
+            }
+        }
+    };
+}
+
+impl_fingering!(Fretboard6);
+impl_fingering!(Fretboard4);
+impl_fingering!(Fretboard5);
+impl_fingering!(Fretboard7);
+impl_fingering!(Fretboard8);
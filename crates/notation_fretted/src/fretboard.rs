@@ -4,9 +4,13 @@ use std::fmt::Display;
 use crate::pick::PickNote;
 use crate::prelude::Pick;
 
-use super::prelude::{HandShape4, HandShape6};
+use super::prelude::{HandShape4, HandShape5, HandShape6, HandShape7, HandShape8};
 use notation_core::prelude::{Note, Semitones, Tone, Scale, Key};
 
+fn default_courses<const N: usize>() -> [Option<Semitones>; N] {
+    [None; N]
+}
+
 macro_rules! impl_fretboard {
     ($type:ident, $strings:literal, $hand_shape:ident) => {
         #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -15,6 +19,10 @@ macro_rules! impl_fretboard {
             #[serde(with = "serde_arrays")]
             pub string_notes: [Semitones; $strings],
             pub capo: u8,
+            /// Offset of the paired string for doubled courses, e.g. 12 for the
+            /// octave strings of a 12-string guitar, 0 for mandolin unisons.
+            #[serde(with = "serde_arrays", default = "default_courses")]
+            pub courses: [Option<Semitones>; $strings],
         }
         impl Display for $type {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -32,11 +40,35 @@ macro_rules! impl_fretboard {
                     total_fret_num,
                     string_notes,
                     capo,
+                    courses: [None; $strings],
+                }
+            }
+            pub fn with_courses(&self, courses: [Option<Semitones>; $strings]) -> Self {
+                Self { courses, ..*self }
+            }
+            pub fn is_doubled(&self) -> bool {
+                self.courses.iter().any(|x| x.is_some())
+            }
+            /// string is 1-based.
+            pub fn course_offset(&self, string: u8) -> Option<Semitones> {
+                if string == 0 || string as usize > self.courses.len() {
+                    None
+                } else {
+                    self.courses[(string - 1) as usize]
                 }
             }
             pub fn with_capo(&self, capo: u8) -> Self {
                 Self { capo, ..*self }
             }
+            /// Returns `None` if the capo would be moved off the fretboard.
+            pub fn transpose_capo(&self, semitones: i8) -> Option<Self> {
+                let capo = self.capo as i16 + semitones as i16;
+                if capo < 0 || capo as usize >= self.total_fret_num {
+                    None
+                } else {
+                    Some(self.with_capo(capo as u8))
+                }
+            }
             pub fn fretted_note(&self, scale: &Scale, key: &Key, string: u8, fret: u8) -> Option<Note> {
                 if fret as usize >= self.fret_num() {
                     None
@@ -76,6 +108,13 @@ macro_rules! impl_fretboard {
                     .string_fret_with_barre(string)
                     .and_then(|fret| self.fretted_note(scale, key, string, fret))
             }
+            /// Notes of the strings that are not muted, from the last string to the first.
+            pub fn shape_notes(&self, scale: &Scale, key: &Key, shape: &$hand_shape) -> Vec<Note> {
+                (1..=$strings)
+                    .rev()
+                    .filter_map(|string| self.shape_note(scale, key, shape, string))
+                    .collect()
+            }
             pub fn shape_fret_note(&self, scale: &Scale, key: &Key, shape: &$hand_shape, string: u8) -> Option<(u8, Note)> {
                 shape
                     .string_fret_with_barre(string)
@@ -86,21 +125,26 @@ macro_rules! impl_fretboard {
                 shape: &$hand_shape,
                 pick_note: PickNote,
             ) -> Option<Note> {
-                match pick_note.fret {
-                    Some(fret) => self.fretted_note(scale, key, pick_note.string, fret),
-                    None => self.shape_note(scale, key, shape, pick_note.string),
-                }
+                self.shape_pick_fret_note(scale, key, shape, pick_note)
+                    .map(|x| x.1)
             }
+            /// The note is the sounding one for harmonics, the fret is as played.
             pub fn shape_pick_fret_note(
                 &self, scale: &Scale, key: &Key,
                 shape: &$hand_shape,
                 pick_note: PickNote,
             ) -> Option<(u8, Note)> {
-                match pick_note.fret {
+                let fret_note = match pick_note.fret {
                     Some(fret) => self
                         .fretted_note(scale, key, pick_note.string, fret)
                         .map(|note| (fret, note)),
                     None => self.shape_fret_note(scale, key, shape, pick_note.string),
+                };
+                match pick_note.techniques.harmonic {
+                    Some(harmonic) => fret_note.map(|(fret, note)| {
+                        (fret, note.transpose(scale, key, harmonic.calc_offset(fret)))
+                    }),
+                    None => fret_note,
                 }
             }
             pub fn pick_tone(&self, scale: &Scale, key: &Key, shape: &$hand_shape, pick: &Pick) -> Tone {
@@ -111,9 +155,29 @@ macro_rules! impl_fretboard {
                     .collect();
                 notes.into()
             }
+            /// Notes of the pick with the paired strings of doubled courses added,
+            /// which can be more than a `Tone` holds, unison pairs are skipped.
+            pub fn pick_sounding_notes(&self, scale: &Scale, key: &Key, shape: &$hand_shape, pick: &Pick) -> Vec<Note> {
+                let mut notes = vec![];
+                for pick_note in pick.get_notes() {
+                    if let Some(note) = self.shape_pick_note(scale, key, shape, pick_note) {
+                        notes.push(note);
+                        if let Some(offset) = self.course_offset(pick_note.string) {
+                            if offset.0 != 0 {
+                                let semitones = Semitones::from(note) + offset;
+                                notes.push(scale.calc_note_from_semitones(key, semitones));
+                            }
+                        }
+                    }
+                }
+                notes
+            }
         }
     };
 }
 
 impl_fretboard!(Fretboard6, 6, HandShape6);
 impl_fretboard!(Fretboard4, 4, HandShape4);
+impl_fretboard!(Fretboard5, 5, HandShape5);
+impl_fretboard!(Fretboard7, 7, HandShape7);
+impl_fretboard!(Fretboard8, 8, HandShape8);
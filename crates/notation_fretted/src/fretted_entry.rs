@@ -1,10 +1,26 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-use super::prelude::{Fretboard4, Fretboard6, HandShape4, HandShape6};
+use super::prelude::{
+    Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8, HandShape4, HandShape5, HandShape6,
+    HandShape7, HandShape8,
+};
 use crate::prelude::{Pick, Strum};
 use notation_core::prelude::{Duration, Entry, EntryPassMode};
 
+/// How fretted tracks follow a transposition, moving the capo keeps all the
+/// shapes and picks, otherwise the frets are rewritten.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum FrettedTranspose {
+    Capo,
+    Frets,
+}
+impl Display for FrettedTranspose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 macro_rules! impl_entry {
     ($type:ident, $strings:literal, $hand_shape:ident, $fretboard:ident) => {
         #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -42,6 +58,34 @@ macro_rules! impl_entry {
                     $type::Fretboard(_) => EntryPassMode::Immediate,
                 }
             }
+            /// `fret_num` is of the transposed fretboard, shapes and picks return
+            /// `None` without it, or if the capo or any fret would be out of it.
+            pub fn transpose(
+                &self,
+                semitones: i8,
+                mode: FrettedTranspose,
+                fret_num: Option<usize>,
+            ) -> Option<Self> {
+                match (self, mode) {
+                    (Self::Fretboard(x), FrettedTranspose::Capo) => {
+                        x.transpose_capo(semitones).map(Self::Fretboard)
+                    }
+                    (Self::Fretboard(_), FrettedTranspose::Frets) => Some(*self),
+                    (Self::Shape(x, y), FrettedTranspose::Frets) => x
+                        .transpose_frets(semitones, fret_num?)
+                        .map(|x| Self::Shape(x, *y)),
+                    (Self::Pick(x, y), FrettedTranspose::Frets) => x
+                        .transpose_frets(semitones, fret_num?)
+                        .map(|x| Self::Pick(x, *y)),
+                    (Self::Shape(x, _), FrettedTranspose::Capo) => fret_num
+                        .filter(|n| (x.max_fret_with_barre() as usize) < *n)
+                        .map(|_| *self),
+                    (Self::Pick(x, _), FrettedTranspose::Capo) => fret_num
+                        .filter(|n| (x.max_fret() as usize) < *n)
+                        .map(|_| *self),
+                    (Self::Strum(_, _), _) => Some(*self),
+                }
+            }
         }
 
         impl $type {
@@ -134,3 +178,6 @@ macro_rules! impl_entry {
 
 impl_entry!(FrettedEntry6, 6, HandShape6, Fretboard6);
 impl_entry!(FrettedEntry4, 4, HandShape4, Fretboard4);
+impl_entry!(FrettedEntry5, 5, HandShape5, Fretboard5);
+impl_entry!(FrettedEntry7, 7, HandShape7, Fretboard7);
+impl_entry!(FrettedEntry8, 8, HandShape8, Fretboard8);
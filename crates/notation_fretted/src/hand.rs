@@ -106,6 +106,30 @@ macro_rules! impl_hand_shape {
             pub fn max_fret_with_barre(&self) -> u8 {
                 self.max_fret() + self.barre()
             }
+            /// Moves the barre if there is one, otherwise all fretted strings,
+            /// returns `None` if any fret would be below the nut or not below
+            /// `fret_num`.
+            pub fn transpose_frets(&self, semitones: i8, fret_num: usize) -> Option<Self> {
+                let shift = |fret: u8| {
+                    u8::try_from(fret as i16 + semitones as i16)
+                        .ok()
+                        .filter(|x| (*x as usize) < fret_num)
+                };
+                match self.barre {
+                    Some(barre) => shift(barre)
+                        .map(|barre| Self::new_barre(barre, self.frets, self.fingers))
+                        .filter(|x| (x.max_fret_with_barre() as usize) < fret_num),
+                    None => {
+                        let mut frets = self.frets;
+                        for fret in frets.iter_mut() {
+                            if let Some(x) = fret {
+                                *fret = Some(shift(*x)?);
+                            }
+                        }
+                        Some(Self { frets, ..*self })
+                    }
+                }
+            }
         }
 
         impl From<([Option<u8>; $strings], [Option<Finger>; $strings])> for $type {
@@ -136,3 +160,6 @@ macro_rules! impl_hand_shape {
 
 impl_hand_shape!(HandShape6, 6);
 impl_hand_shape!(HandShape4, 4);
+impl_hand_shape!(HandShape5, 5);
+impl_hand_shape!(HandShape7, 7);
+impl_hand_shape!(HandShape8, 8);
@@ -1,18 +1,29 @@
+pub mod fingering;
 pub mod fretboard;
 pub mod fretted_entry;
 pub mod hand;
 pub mod pick;
 pub mod strum;
+pub mod technique;
+pub mod voicing;
 
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::fretboard::{Fretboard4, Fretboard6};
+    pub use crate::fingering::{FingeringSettings, FingeringSolver};
     #[doc(hidden)]
-    pub use crate::fretted_entry::{FrettedEntry4, FrettedEntry6};
+    pub use crate::fretboard::{Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8};
     #[doc(hidden)]
-    pub use crate::hand::{Finger, HandShape4, HandShape6};
+    pub use crate::fretted_entry::{
+        FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8, FrettedTranspose,
+    };
+    #[doc(hidden)]
+    pub use crate::hand::{Finger, HandShape4, HandShape5, HandShape6, HandShape7, HandShape8};
     #[doc(hidden)]
     pub use crate::pick::{Pick, PickNote};
     #[doc(hidden)]
-    pub use crate::strum::Strum;
+    pub use crate::strum::{Strum, StrumDirection, StrumStrings};
+    #[doc(hidden)]
+    pub use crate::technique::{Bend, Harmonic, PickAttack, PickTechniques};
+    #[doc(hidden)]
+    pub use crate::voicing::{VoicingGenerator, VoicingSettings};
 }
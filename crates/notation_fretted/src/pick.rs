@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::prelude::Finger;
 use crate::strum::StrumDirection;
+use crate::technique::PickTechniques;
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub struct PickNote {
@@ -12,12 +13,14 @@ pub struct PickNote {
     pub fret_finger: Option<Finger>,
     pub pick_finger: Option<Finger>,
     pub pick_direction: Option<StrumDirection>,
+    #[serde(default)]
+    pub techniques: PickTechniques,
 }
 impl Display for PickNote {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}{}{}{}{}",
+            "{}{}{}{}{}{}",
             self.string,
             self.fret
                 .map(|x| format!("@{}", x))
@@ -31,6 +34,7 @@ impl Display for PickNote {
             self.pick_direction
                 .map(|x| format!("*{}", x))
                 .unwrap_or("".to_string()),
+            self.techniques,
         )
     }
 }
@@ -48,6 +52,13 @@ impl PickNote {
             fret_finger,
             pick_finger,
             pick_direction,
+            techniques: PickTechniques::default(),
+        }
+    }
+    pub fn with_techniques(&self, techniques: PickTechniques) -> Self {
+        Self {
+            techniques,
+            ..*self
         }
     }
     pub fn new_string(string: u8) -> Self {
@@ -56,6 +67,20 @@ impl PickNote {
     pub fn new_string_fret(string: u8, fret: u8) -> Self {
         Self::new(string, Some(fret), None, None, None)
     }
+    /// Notes without fret follow the hand shape, so they are kept as is,
+    /// returns `None` if the fret would be below the nut or not below `fret_num`.
+    pub fn transpose_fret(&self, semitones: i8, fret_num: usize) -> Option<Self> {
+        match self.fret {
+            Some(fret) => u8::try_from(fret as i16 + semitones as i16)
+                .ok()
+                .filter(|x| (*x as usize) < fret_num)
+                .map(|fret| Self {
+                    fret: Some(fret),
+                    ..*self
+                }),
+            None => Some(*self),
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -67,6 +92,25 @@ pub enum Pick {
     Tetra(PickNote, PickNote, PickNote, PickNote),
     Penta(PickNote, PickNote, PickNote, PickNote, PickNote),
     Hexa(PickNote, PickNote, PickNote, PickNote, PickNote, PickNote),
+    Hepta(
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+    ),
+    Octa(
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+    ),
 }
 
 impl Display for Pick {
@@ -85,6 +129,20 @@ impl Display for Pick {
             Pick::Hexa(p1, p2, p3, p4, p5, p6) => {
                 write!(f, "<Pick>({}, {}, {}, {}, {}, {})", p1, p2, p3, p4, p5, p6)
             }
+            Pick::Hepta(p1, p2, p3, p4, p5, p6, p7) => {
+                write!(
+                    f,
+                    "<Pick>({}, {}, {}, {}, {}, {}, {})",
+                    p1, p2, p3, p4, p5, p6, p7
+                )
+            }
+            Pick::Octa(p1, p2, p3, p4, p5, p6, p7, p8) => {
+                write!(
+                    f,
+                    "<Pick>({}, {}, {}, {}, {}, {}, {}, {})",
+                    p1, p2, p3, p4, p5, p6, p7, p8
+                )
+            }
         }
     }
 }
@@ -131,6 +189,60 @@ impl From<(PickNote, PickNote, PickNote, PickNote, PickNote, PickNote)> for Pick
     }
 }
 
+impl
+    From<(
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+    )> for Pick
+{
+    fn from(
+        v: (
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+        ),
+    ) -> Self {
+        Self::Hepta(v.0, v.1, v.2, v.3, v.4, v.5, v.6)
+    }
+}
+
+impl
+    From<(
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+        PickNote,
+    )> for Pick
+{
+    fn from(
+        v: (
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+            PickNote,
+        ),
+    ) -> Self {
+        Self::Octa(v.0, v.1, v.2, v.3, v.4, v.5, v.6, v.7)
+    }
+}
+
 impl From<Vec<PickNote>> for Pick {
     fn from(v: Vec<PickNote>) -> Self {
         match v.len() {
@@ -141,9 +253,11 @@ impl From<Vec<PickNote>> for Pick {
             4 => Self::from((v[0], v[1], v[2], v[3])),
             5 => Self::from((v[0], v[1], v[2], v[3], v[4])),
             6 => Self::from((v[0], v[1], v[2], v[3], v[4], v[5])),
+            7 => Self::from((v[0], v[1], v[2], v[3], v[4], v[5], v[6])),
+            8 => Self::from((v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7])),
             _ => {
-                println!("PickNote lost: {}", v.len() - 6);
-                Self::from((v[0], v[1], v[2], v[3], v[4], v[5]))
+                println!("PickNote lost: {}", v.len() - 8);
+                Self::from((v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]))
             }
         }
     }
@@ -159,6 +273,8 @@ impl Pick {
             Self::Tetra(p1, p2, p3, p4) => vec![p1, p2, p3, p4],
             Self::Penta(p1, p2, p3, p4, p5) => vec![p1, p2, p3, p4, p5],
             Self::Hexa(p1, p2, p3, p4, p5, p6) => vec![p1, p2, p3, p4, p5, p6],
+            Self::Hepta(p1, p2, p3, p4, p5, p6, p7) => vec![p1, p2, p3, p4, p5, p6, p7],
+            Self::Octa(p1, p2, p3, p4, p5, p6, p7, p8) => vec![p1, p2, p3, p4, p5, p6, p7, p8],
         }
     }
     pub fn get_pick_note(&self, string: u8) -> Option<PickNote> {
@@ -181,6 +297,13 @@ impl Pick {
         }
         max
     }
+    pub fn transpose_frets(&self, semitones: i8, fret_num: usize) -> Option<Self> {
+        self.get_notes()
+            .iter()
+            .map(|x| x.transpose_fret(semitones, fret_num))
+            .collect::<Option<Vec<PickNote>>>()
+            .map(Self::from)
+    }
 }
 
 impl From<Pick> for Vec<PickNote> {
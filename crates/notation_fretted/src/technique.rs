@@ -0,0 +1,319 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use notation_core::prelude::Semitones;
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Bend {
+    pub semitones: u8,
+    /// Bend back to the fretted note before the end.
+    pub release: bool,
+}
+impl Display for Bend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "b{}{}",
+            self.semitones,
+            if self.release { "r" } else { "" }
+        )
+    }
+}
+impl Bend {
+    pub fn new(semitones: u8, release: bool) -> Self {
+        Self { semitones, release }
+    }
+}
+
+/// How the note is sounded other than picked.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum PickAttack {
+    HammerOn,
+    PullOff,
+    Tap,
+}
+impl Display for PickAttack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PickAttack::HammerOn => "h",
+                PickAttack::PullOff => "p",
+                PickAttack::Tap => "t",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum Harmonic {
+    /// Touched at the fret of the pick note, over the open string.
+    Natural,
+    /// Touched the frets above the fretted note, usually 12.
+    Artificial(u8),
+}
+impl Display for Harmonic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Harmonic::Natural => write!(f, "nh"),
+            Harmonic::Artificial(12) => write!(f, "ah"),
+            Harmonic::Artificial(x) => write!(f, "ah{}", x),
+        }
+    }
+}
+impl Harmonic {
+    /// Pitch of the harmonic above the string touched at the node, the nodes
+    /// not on these frets are treated as the octave.
+    pub fn calc_node_semitones(node: u8) -> Semitones {
+        Semitones(match node {
+            5 | 24 => 24,
+            7 | 19 => 19,
+            4 | 9 | 16 => 28,
+            3 => 31,
+            _ => 12,
+        })
+    }
+    /// Offset from the fretted note to the sounding one.
+    pub fn calc_offset(&self, fret: u8) -> Semitones {
+        match self {
+            Harmonic::Natural => Self::calc_node_semitones(fret) - Semitones(fret as i8),
+            Harmonic::Artificial(node) => Self::calc_node_semitones(*node),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug, Default)]
+pub struct PickTechniques {
+    pub bend: Option<Bend>,
+    /// Slide into the note from below.
+    pub slide_in: bool,
+    /// Slide down from the note at the end.
+    pub slide_out: bool,
+    pub attack: Option<PickAttack>,
+    pub vibrato: bool,
+    pub harmonic: Option<Harmonic>,
+    pub palm_mute: bool,
+    pub dead_note: bool,
+    pub let_ring: bool,
+}
+impl Display for PickTechniques {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for text in self.to_texts() {
+            write!(f, ":{}", text)?;
+        }
+        Ok(())
+    }
+}
+impl PickTechniques {
+    pub const SLIDE_CENTS: f32 = 200.0;
+    pub const VIBRATO_CENTS: f32 = 30.0;
+    pub const VIBRATO_CYCLES: f32 = 4.0;
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+    /// Same as the DSL syntax, e.g. `b2r` for a whole step bend and release.
+    pub fn to_texts(&self) -> Vec<String> {
+        let mut texts = vec![];
+        if let Some(attack) = self.attack {
+            texts.push(attack.to_string());
+        }
+        if self.slide_in {
+            texts.push("si".to_owned());
+        }
+        if let Some(bend) = self.bend {
+            texts.push(bend.to_string());
+        }
+        if self.vibrato {
+            texts.push("v".to_owned());
+        }
+        if self.slide_out {
+            texts.push("so".to_owned());
+        }
+        if let Some(harmonic) = self.harmonic {
+            texts.push(harmonic.to_string());
+        }
+        if self.palm_mute {
+            texts.push("pm".to_owned());
+        }
+        if self.dead_note {
+            texts.push("x".to_owned());
+        }
+        if self.let_ring {
+            texts.push("lr".to_owned());
+        }
+        texts
+    }
+    /// Returns `false` if the text is not a technique.
+    pub fn add_text(&mut self, text: &str) -> bool {
+        match text {
+            "h" => self.attack = Some(PickAttack::HammerOn),
+            "p" => self.attack = Some(PickAttack::PullOff),
+            "t" => self.attack = Some(PickAttack::Tap),
+            "si" => self.slide_in = true,
+            "so" => self.slide_out = true,
+            "v" => self.vibrato = true,
+            "nh" => self.harmonic = Some(Harmonic::Natural),
+            "ah" => self.harmonic = Some(Harmonic::Artificial(12)),
+            "pm" => self.palm_mute = true,
+            "x" => self.dead_note = true,
+            "lr" => self.let_ring = true,
+            _ => {
+                if let Some(node) = text.strip_prefix("ah") {
+                    match node.parse::<u8>() {
+                        Ok(node) => self.harmonic = Some(Harmonic::Artificial(node)),
+                        Err(_) => return false,
+                    }
+                } else if let Some(bend) = text.strip_prefix('b') {
+                    let (semitones, release) = match bend.strip_suffix('r') {
+                        Some(x) => (x, true),
+                        None => (bend, false),
+                    };
+                    match semitones.parse::<u8>() {
+                        Ok(semitones) if semitones > 0 => {
+                            self.bend = Some(Bend::new(semitones, release))
+                        }
+                        _ => return false,
+                    }
+                } else {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+    pub fn has_pitch_change(&self) -> bool {
+        self.bend.is_some() || self.vibrato || self.slide_in || self.slide_out
+    }
+    /// Pitch change at the ratio of the note duration, bends take the first
+    /// quarter, and are released in the third one.
+    pub fn calc_bend_cents(&self, ratio: f32) -> f32 {
+        let mut cents = 0.0;
+        if self.slide_in && ratio < 0.25 {
+            cents -= Self::SLIDE_CENTS * (1.0 - ratio / 0.25);
+        }
+        if let Some(bend) = self.bend {
+            let up = (ratio / 0.25).min(1.0);
+            let down = if bend.release {
+                ((ratio - 0.5) / 0.25).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            cents += bend.semitones as f32 * 100.0 * (up - down);
+        }
+        if self.vibrato {
+            let phase = ratio * Self::VIBRATO_CYCLES * std::f32::consts::TAU;
+            cents += Self::VIBRATO_CENTS * phase.sin();
+        }
+        if self.slide_out && ratio > 0.75 {
+            cents -= Self::SLIDE_CENTS * (ratio - 0.75) / 0.25;
+        }
+        cents
+    }
+    /// Hammer-ons and pull-offs are not picked, so are softer.
+    pub fn calc_velocity_factor(&self) -> f32 {
+        let mut factor = match self.attack {
+            Some(PickAttack::HammerOn) | Some(PickAttack::PullOff) => 0.7,
+            Some(PickAttack::Tap) => 0.85,
+            None => 1.0,
+        };
+        if self.palm_mute {
+            factor *= 0.7;
+        }
+        if self.dead_note {
+            factor *= 0.5;
+        }
+        if self.harmonic.is_some() {
+            factor *= 0.8;
+        }
+        factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_texts(texts: &[&str]) -> PickTechniques {
+        let mut techniques = PickTechniques::default();
+        for text in texts {
+            assert!(techniques.add_text(text), "not a technique: {}", text);
+        }
+        techniques
+    }
+
+    #[test]
+    fn test_add_text() {
+        let techniques = parse_texts(&["h", "si", "b2r", "v", "so", "ah", "pm", "x", "lr"]);
+        assert_eq!(techniques.attack, Some(PickAttack::HammerOn));
+        assert_eq!(techniques.bend, Some(Bend::new(2, true)));
+        assert_eq!(techniques.harmonic, Some(Harmonic::Artificial(12)));
+        assert!(techniques.slide_in && techniques.slide_out && techniques.vibrato);
+        assert!(techniques.palm_mute && techniques.dead_note && techniques.let_ring);
+        assert_eq!(
+            techniques.to_texts(),
+            vec!["h", "si", "b2r", "v", "so", "ah", "pm", "x", "lr"]
+        );
+
+        let techniques = parse_texts(&["p", "b1", "ah7"]);
+        assert_eq!(techniques.attack, Some(PickAttack::PullOff));
+        assert_eq!(techniques.bend, Some(Bend::new(1, false)));
+        assert_eq!(techniques.harmonic, Some(Harmonic::Artificial(7)));
+        assert_eq!(parse_texts(&["t", "nh"]).to_string(), ":t:nh");
+
+        let mut techniques = PickTechniques::default();
+        for text in ["", "b", "b0", "br", "bx", "ahx", "s", "hp"] {
+            assert!(!techniques.add_text(text), "technique: {}", text);
+        }
+        assert!(techniques.is_empty());
+    }
+
+    #[test]
+    fn test_calc_bend_cents() {
+        assert_eq!(PickTechniques::default().calc_bend_cents(0.5), 0.0);
+
+        let bend = parse_texts(&["b2"]);
+        assert_eq!(bend.calc_bend_cents(0.0), 0.0);
+        assert_eq!(bend.calc_bend_cents(0.125), 100.0);
+        assert_eq!(bend.calc_bend_cents(0.25), 200.0);
+        assert_eq!(bend.calc_bend_cents(1.0), 200.0);
+
+        let release = parse_texts(&["b1r"]);
+        assert_eq!(release.calc_bend_cents(0.5), 100.0);
+        assert_eq!(release.calc_bend_cents(0.625), 50.0);
+        assert_eq!(release.calc_bend_cents(0.75), 0.0);
+        assert_eq!(release.calc_bend_cents(1.0), 0.0);
+
+        let slide_in = parse_texts(&["si"]);
+        assert_eq!(slide_in.calc_bend_cents(0.0), -PickTechniques::SLIDE_CENTS);
+        assert_eq!(slide_in.calc_bend_cents(0.25), 0.0);
+        let slide_out = parse_texts(&["so"]);
+        assert_eq!(slide_out.calc_bend_cents(0.75), 0.0);
+        assert_eq!(slide_out.calc_bend_cents(1.0), -PickTechniques::SLIDE_CENTS);
+
+        let vibrato = parse_texts(&["v"]);
+        let quarter_cycle = 0.25 / PickTechniques::VIBRATO_CYCLES;
+        assert!(vibrato.calc_bend_cents(0.0).abs() < 0.001);
+        assert!(
+            (vibrato.calc_bend_cents(quarter_cycle) - PickTechniques::VIBRATO_CENTS).abs() < 0.001
+        );
+    }
+
+    #[test]
+    fn test_harmonic_offset() {
+        assert_eq!(Harmonic::calc_node_semitones(12), Semitones(12));
+        assert_eq!(Harmonic::calc_node_semitones(7), Semitones(19));
+        assert_eq!(Harmonic::calc_node_semitones(5), Semitones(24));
+        assert_eq!(Harmonic::calc_node_semitones(4), Semitones(28));
+        assert_eq!(Harmonic::calc_node_semitones(3), Semitones(31));
+        assert_eq!(Harmonic::calc_node_semitones(2), Semitones(12));
+
+        assert_eq!(Harmonic::Natural.calc_offset(12), Semitones(0));
+        assert_eq!(Harmonic::Natural.calc_offset(7), Semitones(12));
+        assert_eq!(Harmonic::Natural.calc_offset(5), Semitones(19));
+        assert_eq!(Harmonic::Natural.calc_offset(19), Semitones(0));
+        assert_eq!(Harmonic::Artificial(12).calc_offset(3), Semitones(12));
+        assert_eq!(Harmonic::Artificial(7).calc_offset(3), Semitones(19));
+    }
+}
@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::Finger;
+
+use super::prelude::{
+    Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8, HandShape4, HandShape5, HandShape6,
+    HandShape7, HandShape8,
+};
+use notation_core::prelude::{Chord, Key, Scale, Semitones};
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct VoicingSettings {
+    /// Frets between the lowest and the highest fretted notes.
+    pub max_span: u8,
+    /// Fingers can be used, a barre takes one.
+    pub max_fingers: u8,
+}
+
+impl Default for VoicingSettings {
+    fn default() -> Self {
+        Self {
+            max_span: 3,
+            max_fingers: 4,
+        }
+    }
+}
+
+/// A chord tone on a string, the offset is from the root of the chord, in
+/// semitones within an octave.
+#[derive(Copy, Clone, Debug)]
+struct VoicingNote {
+    fret: u8,
+    offset: i8,
+    semitones: Semitones,
+}
+
+/// Frets are relative to the barre, same as in the hand shapes.
+#[derive(Clone, Debug)]
+struct VoicingCandidate {
+    barre: u8,
+    frets: Vec<Option<u8>>,
+    fingers: Vec<Option<Finger>>,
+    cost: u32,
+}
+
+pub struct VoicingGenerator();
+
+impl VoicingGenerator {
+    pub const MAX_VOICINGS: usize = 12;
+    /// Lowest fretted note of the voicings, counted from the capo.
+    pub const MAX_POSITION: u8 = 12;
+    pub const SPAN_COST: u32 = 1;
+    pub const FINGER_COST: u32 = 1;
+    pub const BARRE_COST: u32 = 2;
+    /// Added for every 2 frets up the neck, so open chords are preferred.
+    pub const POSITION_COST: u32 = 1;
+    pub const MUTE_COST: u32 = 1;
+    pub const INNER_MUTE_COST: u32 = 6;
+    pub const OMIT_FIFTH_COST: u32 = 3;
+
+    fn calc_offset(root: Semitones, note: Semitones) -> i8 {
+        (note - root).0.rem_euclid(12)
+    }
+    fn calc_chord_offsets(chord: &Chord) -> Vec<i8> {
+        let intervals = chord.intervals.get_intervals();
+        let mut offsets = vec![0];
+        for interval in intervals.iter().chain(chord.bass.iter()) {
+            let offset = Semitones::from(*interval).0.rem_euclid(12);
+            if !offsets.contains(&offset) {
+                offsets.push(offset);
+            }
+        }
+        offsets
+    }
+    /// Offsets of the root, the intervals and the bass, the perfect fifth is
+    /// not included since it can be left out.
+    fn calc_required_offsets(chord: &Chord) -> Vec<i8> {
+        Self::calc_chord_offsets(chord)
+            .into_iter()
+            .filter(|x| *x != 7)
+            .collect()
+    }
+    fn calc_fifth_required(chord: &Chord) -> bool {
+        // Power chords and triads sound empty without it.
+        Self::calc_chord_offsets(chord).contains(&7) && chord.intervals.get_intervals().len() < 3
+    }
+    fn calc_fret_range(notes: &[Option<VoicingNote>]) -> Option<(u8, u8)> {
+        let frets = notes.iter().flatten().map(|x| x.fret).filter(|x| *x > 0);
+        Some((frets.clone().min()?, frets.max()?))
+    }
+    fn add_candidates(
+        settings: &VoicingSettings,
+        string_notes: &[Vec<VoicingNote>],
+        notes: &mut Vec<Option<VoicingNote>>,
+        candidates: &mut Vec<Vec<Option<VoicingNote>>>,
+    ) {
+        let (options, rest) = match string_notes.split_first() {
+            Some(x) => x,
+            None => {
+                candidates.push(notes.clone());
+                return;
+            }
+        };
+        for note in options.iter() {
+            notes.push(Some(*note));
+            let fit = match Self::calc_fret_range(notes) {
+                Some((min, max)) => max - min <= settings.max_span && min <= Self::MAX_POSITION,
+                None => true,
+            };
+            if fit {
+                Self::add_candidates(settings, rest, notes, candidates);
+            }
+            notes.pop();
+        }
+        notes.push(None);
+        Self::add_candidates(settings, rest, notes, candidates);
+        notes.pop();
+    }
+    /// Fingers the fretted notes from the lowest fret, and from the lower
+    /// strings on the same fret, returns `None` if there are not enough
+    /// fingers, even with a barre.
+    fn calc_candidate(
+        settings: &VoicingSettings,
+        notes: &[Option<VoicingNote>],
+    ) -> Option<VoicingCandidate> {
+        let mut fretted: Vec<(usize, u8)> = notes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, x)| x.filter(|x| x.fret > 0).map(|x| (index, x.fret)))
+            .collect();
+        fretted.sort_by_key(|(index, fret)| (*fret, std::cmp::Reverse(*index)));
+        let min = fretted.first().map(|x| x.1).unwrap_or(0);
+        let has_open = notes.iter().flatten().any(|x| x.fret == 0);
+        let barre = if fretted.len() <= settings.max_fingers as usize {
+            0
+        } else if !has_open && min > 0 {
+            fretted.retain(|x| x.1 > min);
+            if fretted.len() + 1 > settings.max_fingers as usize {
+                return None;
+            }
+            min
+        } else {
+            return None;
+        };
+        let mut fingers: Vec<Option<Finger>> = notes
+            .iter()
+            .map(|x| match x {
+                Some(x) if barre > 0 && x.fret == barre => Some(Finger::Index),
+                _ => None,
+            })
+            .collect();
+        let mut next_fingers = [Finger::Index, Finger::Middle, Finger::Ring, Finger::Pinky]
+            .into_iter()
+            .skip(if barre > 0 { 1 } else { 0 });
+        for (index, _) in fretted.iter() {
+            fingers[*index] = Some(next_fingers.next()?);
+        }
+        let frets = notes.iter().map(|x| x.map(|x| x.fret - barre)).collect();
+        let finger_num = fretted.len() as u32 + if barre > 0 { 1 } else { 0 };
+        Some(VoicingCandidate {
+            barre,
+            frets,
+            fingers,
+            cost: finger_num * Self::FINGER_COST + if barre > 0 { Self::BARRE_COST } else { 0 },
+        })
+    }
+    /// The notes are of the strings from the first one, each with all the
+    /// frets playing a chord tone, voicings need to have the bass as the
+    /// lowest note, and all chord tones except the fifth of larger chords.
+    fn generate(
+        settings: &VoicingSettings,
+        chord: &Chord,
+        string_notes: &[Vec<VoicingNote>],
+    ) -> Vec<VoicingCandidate> {
+        let required = Self::calc_required_offsets(chord);
+        let fifth_required = Self::calc_fifth_required(chord);
+        let bass = chord
+            .bass
+            .map(|x| Semitones::from(x).0.rem_euclid(12))
+            .unwrap_or(0);
+        let string_num = string_notes.len();
+        let min_sounding =
+            std::cmp::min(string_num, std::cmp::max(3, string_num.saturating_sub(2)));
+        let mut candidates = Vec::new();
+        Self::add_candidates(settings, string_notes, &mut vec![], &mut candidates);
+        let mut voicings: Vec<VoicingCandidate> = candidates
+            .iter()
+            .filter_map(|notes| {
+                let sounding: Vec<VoicingNote> = notes.iter().flatten().copied().collect();
+                if sounding.len() < min_sounding {
+                    return None;
+                }
+                if sounding.iter().min_by_key(|x| x.semitones)?.offset != bass {
+                    return None;
+                }
+                if required
+                    .iter()
+                    .any(|x| !sounding.iter().any(|y| y.offset == *x))
+                {
+                    return None;
+                }
+                let has_fifth = sounding.iter().any(|x| x.offset == 7);
+                if fifth_required && !has_fifth {
+                    return None;
+                }
+                let mut voicing = Self::calc_candidate(settings, notes)?;
+                let first = notes.iter().position(|x| x.is_some())?;
+                let last = notes.iter().rposition(|x| x.is_some())?;
+                for (index, note) in notes.iter().enumerate() {
+                    if note.is_none() {
+                        voicing.cost += if index > first && index < last {
+                            Self::INNER_MUTE_COST
+                        } else {
+                            Self::MUTE_COST
+                        };
+                    }
+                }
+                if let Some((min, max)) = Self::calc_fret_range(notes) {
+                    voicing.cost += (max - min) as u32 * Self::SPAN_COST;
+                    voicing.cost += min as u32 / 2 * Self::POSITION_COST;
+                }
+                if !has_fifth && !required.contains(&7) {
+                    voicing.cost += Self::OMIT_FIFTH_COST;
+                }
+                Some(voicing)
+            })
+            .collect();
+        voicings.sort_by_key(|x| x.cost);
+        voicings.truncate(Self::MAX_VOICINGS);
+        voicings
+    }
+}
+
+macro_rules! impl_voicing {
+    ($type:ident, $strings:literal, $hand_shape:ident) => {
+        impl $type {
+            /// Playable shapes of the chord, best first, an empty list if the
+            /// chord can't be played on the fretboard.
+            pub fn calc_voicings(
+                &self,
+                scale: &Scale,
+                key: &Key,
+                chord: &Chord,
+                settings: &VoicingSettings,
+            ) -> Vec<$hand_shape> {
+                let root = Semitones::from(chord.root);
+                let offsets = VoicingGenerator::calc_chord_offsets(chord);
+                let string_notes: Vec<Vec<VoicingNote>> = (1..=$strings as u8)
+                    .map(|string| {
+                        (0..self.fret_num() as u8)
+                            .filter_map(|fret| {
+                                let note = self.fretted_note(scale, key, string, fret)?;
+                                let offset = VoicingGenerator::calc_offset(
+                                    root,
+                                    Semitones::from(note.syllable),
+                                );
+                                Some(VoicingNote {
+                                    fret,
+                                    offset,
+                                    semitones: Semitones::from(note),
+                                })
+                            })
+                            .filter(|x| offsets.contains(&x.offset))
+                            .collect()
+                    })
+                    .collect();
+                VoicingGenerator::generate(settings, chord, &string_notes)
+                    .into_iter()
+                    .filter_map(|x| {
+                        Some($hand_shape::new_barre(
+                            x.barre,
+                            x.frets.try_into().ok()?,
+                            x.fingers.try_into().ok()?,
+                        ))
+                    })
+                    .collect()
+            }
+        }
+    };
+}
+
+impl_voicing!(Fretboard6, 6, HandShape6);
+impl_voicing!(Fretboard4, 4, HandShape4);
+impl_voicing!(Fretboard5, 5, HandShape5);
+impl_voicing!(Fretboard7, 7, HandShape7);
+impl_voicing!(Fretboard8, 8, HandShape8);
@@ -12,11 +12,7 @@ pub struct GuitarUtil();
 
 impl GuitarUtil {
     pub fn new_guitar_fretboard(total_fret_num: usize, strings: [Semitones; 6]) -> Fretboard6 {
-        Fretboard6 {
-            total_fret_num,
-            string_notes: strings,
-            capo: 0,
-        }
+        Fretboard6::new(total_fret_num, strings, 0)
     }
 
     pub fn new_acoustic_guitar_fretboard(tuning: Option<GuitarTuning>) -> Fretboard6 {
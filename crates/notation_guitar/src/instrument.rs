@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use notation_core::prelude::{Octave, Pitch, Semitones};
+use notation_fretted::prelude::{Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8};
+
+use crate::prelude::GuitarTuning;
+
+/// Fretted instruments other than the 6-string guitar, the string notes are
+/// listed from the first string, same as `GuitarTuning`.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum FrettedInstrument {
+    Bass,
+    Bass5,
+    /// Open G, the short 5th string is tuned as if it started at the nut.
+    Banjo,
+    Mandolin,
+    Guitar7,
+    Guitar8,
+    Guitar12,
+}
+
+impl FrettedInstrument {
+    pub fn to_ident(&self) -> String {
+        format!("{:?}", self)
+    }
+    pub fn from_ident(ident: &str) -> Option<Self> {
+        match ident {
+            "Bass" => Some(Self::Bass),
+            "Bass5" => Some(Self::Bass5),
+            "Banjo" => Some(Self::Banjo),
+            "Mandolin" => Some(Self::Mandolin),
+            "Guitar7" => Some(Self::Guitar7),
+            "Guitar8" => Some(Self::Guitar8),
+            "Guitar12" => Some(Self::Guitar12),
+            _ => None,
+        }
+    }
+    /// Used when the fretboard doesn't name a tuning.
+    pub fn default_of_string_num(string_num: usize) -> Option<Self> {
+        match string_num {
+            4 => Some(Self::Bass),
+            5 => Some(Self::Banjo),
+            7 => Some(Self::Guitar7),
+            8 => Some(Self::Guitar8),
+            _ => None,
+        }
+    }
+    pub fn string_num(&self) -> usize {
+        self.string_notes().len()
+    }
+    pub fn fret_num(&self) -> usize {
+        match self {
+            Self::Bass | Self::Bass5 => 20,
+            Self::Banjo => 22,
+            Self::Mandolin => 20,
+            Self::Guitar7 | Self::Guitar8 => 24,
+            Self::Guitar12 => 20,
+        }
+    }
+    pub fn string_notes(&self) -> Vec<Semitones> {
+        match self {
+            Self::Bass => vec![
+                (Pitch::G, Octave::P2).into(),
+                (Pitch::D, Octave::P2).into(),
+                (Pitch::A, Octave::P1).into(),
+                (Pitch::E, Octave::P1).into(),
+            ],
+            Self::Bass5 => vec![
+                (Pitch::G, Octave::P2).into(),
+                (Pitch::D, Octave::P2).into(),
+                (Pitch::A, Octave::P1).into(),
+                (Pitch::E, Octave::P1).into(),
+                (Pitch::B, Octave::P0).into(),
+            ],
+            Self::Banjo => vec![
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::B, Octave::P3).into(),
+                (Pitch::G, Octave::P3).into(),
+                (Pitch::D, Octave::P3).into(),
+                (Pitch::G, Octave::P4).into(),
+            ],
+            Self::Mandolin => vec![
+                (Pitch::E, Octave::P5).into(),
+                (Pitch::A, Octave::P4).into(),
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::G, Octave::P3).into(),
+            ],
+            Self::Guitar7 => {
+                let mut notes = <[Semitones; 6]>::from(GuitarTuning::Standard).to_vec();
+                notes.push((Pitch::B, Octave::P1).into());
+                notes
+            }
+            Self::Guitar8 => {
+                let mut notes = Self::Guitar7.string_notes();
+                notes.push((Pitch::F_SHARP, Octave::P1).into());
+                notes
+            }
+            Self::Guitar12 => <[Semitones; 6]>::from(GuitarTuning::Standard).to_vec(),
+        }
+    }
+    /// Offsets of the paired strings, see `Fretboard6::courses`.
+    pub fn courses(&self) -> Vec<Option<Semitones>> {
+        match self {
+            Self::Mandolin => vec![Some(Semitones(0)); 4],
+            Self::Guitar12 => vec![
+                Some(Semitones(0)),
+                Some(Semitones(0)),
+                Some(Semitones(12)),
+                Some(Semitones(12)),
+                Some(Semitones(12)),
+                Some(Semitones(12)),
+            ],
+            _ => vec![None; self.string_num()],
+        }
+    }
+}
+
+macro_rules! impl_new_fretboard {
+    ($name:ident, $strings:literal, $fretboard:ident) => {
+        impl FrettedInstrument {
+            /// Returns `None` if the instrument has another number of strings.
+            pub fn $name(&self) -> Option<$fretboard> {
+                let string_notes: [Semitones; $strings] = self.string_notes().try_into().ok()?;
+                let courses: [Option<Semitones>; $strings] = self.courses().try_into().ok()?;
+                Some($fretboard::new(self.fret_num(), string_notes, 0).with_courses(courses))
+            }
+        }
+    };
+}
+
+impl_new_fretboard!(new_fretboard4, 4, Fretboard4);
+impl_new_fretboard!(new_fretboard5, 5, Fretboard5);
+impl_new_fretboard!(new_fretboard6, 6, Fretboard6);
+impl_new_fretboard!(new_fretboard7, 7, Fretboard7);
+impl_new_fretboard!(new_fretboard8, 8, Fretboard8);
@@ -1,9 +1,12 @@
 pub mod guitar;
+pub mod instrument;
 pub mod tuning;
 
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::guitar::*;
     #[doc(hidden)]
+    pub use crate::instrument::FrettedInstrument;
+    #[doc(hidden)]
     pub use crate::tuning::GuitarTuning;
 }
@@ -5,8 +5,17 @@ use notation_core::prelude::{Semitones, Pitch, Octave};
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub enum GuitarTuning {
     Standard,
+    /// Half step down, can also be named as `HalfStepDown`.
     StandardFlat,
+    WholeStepDown,
     DropD,
+    DropC,
+    OpenG,
+    OpenD,
+    OpenE,
+    Dadgad,
+    /// Standard with the lower 4 strings an octave higher.
+    Nashville,
 }
 
 impl Default for GuitarTuning {
@@ -34,6 +43,14 @@ impl From<GuitarTuning> for [Semitones; 6] {
                 (Pitch::A_FLAT, Octave::P2).into(),
                 (Pitch::E_FLAT, Octave::P2).into(),
             ],
+            GuitarTuning::WholeStepDown => [
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::A, Octave::P3).into(),
+                (Pitch::F, Octave::P3).into(),
+                (Pitch::C, Octave::P3).into(),
+                (Pitch::G, Octave::P2).into(),
+                (Pitch::D, Octave::P2).into(),
+            ],
             GuitarTuning::DropD => [
                 (Pitch::E, Octave::P4).into(),
                 (Pitch::B, Octave::P3).into(),
@@ -42,19 +59,79 @@ impl From<GuitarTuning> for [Semitones; 6] {
                 (Pitch::A, Octave::P2).into(),
                 (Pitch::D, Octave::P2).into(),
             ],
+            GuitarTuning::DropC => [
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::A, Octave::P3).into(),
+                (Pitch::F, Octave::P3).into(),
+                (Pitch::C, Octave::P3).into(),
+                (Pitch::G, Octave::P2).into(),
+                (Pitch::C, Octave::P2).into(),
+            ],
+            GuitarTuning::OpenG => [
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::B, Octave::P3).into(),
+                (Pitch::G, Octave::P3).into(),
+                (Pitch::D, Octave::P3).into(),
+                (Pitch::G, Octave::P2).into(),
+                (Pitch::D, Octave::P2).into(),
+            ],
+            GuitarTuning::OpenD => [
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::A, Octave::P3).into(),
+                (Pitch::F_SHARP, Octave::P3).into(),
+                (Pitch::D, Octave::P3).into(),
+                (Pitch::A, Octave::P2).into(),
+                (Pitch::D, Octave::P2).into(),
+            ],
+            GuitarTuning::OpenE => [
+                (Pitch::E, Octave::P4).into(),
+                (Pitch::B, Octave::P3).into(),
+                (Pitch::G_SHARP, Octave::P3).into(),
+                (Pitch::E, Octave::P3).into(),
+                (Pitch::B, Octave::P2).into(),
+                (Pitch::E, Octave::P2).into(),
+            ],
+            GuitarTuning::Dadgad => [
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::A, Octave::P3).into(),
+                (Pitch::G, Octave::P3).into(),
+                (Pitch::D, Octave::P3).into(),
+                (Pitch::A, Octave::P2).into(),
+                (Pitch::D, Octave::P2).into(),
+            ],
+            GuitarTuning::Nashville => [
+                (Pitch::E, Octave::P4).into(),
+                (Pitch::B, Octave::P3).into(),
+                (Pitch::G, Octave::P4).into(),
+                (Pitch::D, Octave::P4).into(),
+                (Pitch::A, Octave::P3).into(),
+                (Pitch::E, Octave::P3).into(),
+            ],
         }
     }
 }
 
 impl GuitarTuning {
+    pub const ALL: [GuitarTuning; 10] = [
+        GuitarTuning::Standard,
+        GuitarTuning::StandardFlat,
+        GuitarTuning::WholeStepDown,
+        GuitarTuning::DropD,
+        GuitarTuning::DropC,
+        GuitarTuning::OpenG,
+        GuitarTuning::OpenD,
+        GuitarTuning::OpenE,
+        GuitarTuning::Dadgad,
+        GuitarTuning::Nashville,
+    ];
     pub fn to_ident(&self) -> String {
         format!("{:?}", self)
     }
-    pub fn from_ident(ident: &str) -> Self {
+    pub fn from_ident(ident: &str) -> Option<Self> {
         match ident {
-            "StandardFlat" => Self::StandardFlat,
-            "DropD" => Self::DropD,
-            _ => Self::Standard,
+            "HalfStepDown" => Some(Self::StandardFlat),
+            "DADGAD" => Some(Self::Dadgad),
+            _ => Self::ALL.into_iter().find(|x| x.to_ident() == ident),
         }
     }
 }
@@ -35,6 +35,22 @@ impl MidiMessage {
             midi,
         }
     }
+    /// Message at the ratio of the entry duration, the ones at the end are
+    /// sent just before it, same as the delayed ones.
+    pub fn of_entry_at(entry: &LaneEntry, ratio: f32, midi: StructuredShortMessage) -> Self {
+        let duration = entry.tied_units();
+        let mut delay = Units(duration.0 * ratio);
+        if ratio >= 1.0 {
+            delay = delay - Self::DELAY_GAP;
+        }
+        Self {
+            pass_mode: entry.pass_mode(),
+            pos: entry.bar_position().with_delay(delay),
+            duration,
+            delay: false,
+            midi,
+        }
+    }
     pub fn bar_ordinal(&self) -> usize {
         self.pos.bar_ordinal
     }
@@ -7,7 +7,7 @@ use notation_model::play::play_control::TickResult;
 use notation_model::prelude::*;
 
 use crate::midi_hub::MidiHub;
-use crate::prelude::{MidiMessage, MidiSettings, MidiUtil};
+use crate::prelude::{MidiChannelParams, MidiMessage, MidiSettings, MidiUtil};
 
 pub const DEFAULT_PROGRAM: u8 = 0;
 pub const DEFAULT_VELOCITY: u8 = 64;
@@ -34,6 +34,13 @@ impl MidiChannel {
             next_index: 0,
         }
     }
+    pub fn params(&self) -> MidiChannelParams {
+        MidiChannelParams {
+            channel: self.channel,
+            program: self.program,
+            velocity: self.velocity,
+        }
+    }
     pub fn reset(&mut self) {
         self.track = None;
         self.program = U7::new(DEFAULT_PROGRAM);
@@ -114,7 +121,10 @@ impl MidiChannel {
                                 settings.vocal_velocity
                             };
                         }
-                        TrackKind::Guitar => {
+                        TrackKind::Guitar
+                        | TrackKind::Bass
+                        | TrackKind::Banjo
+                        | TrackKind::Mandolin => {
                             velocity = if !seeking && settings.guitar_mute {
                                 0
                             } else {
@@ -299,23 +309,21 @@ impl MidiState {
                 index, params.0, params.1
             );
             *index += 1;
-            let scale_root = tab.meta.scale.calc_root_syllable();
-            let signature = tab.signature();
-            let bar_units = tab.bar_units();
-            let beat_duration = Units::from(signature.beat_unit);
             for bar in tab.bars.iter() {
+                let meta = bar.tab_meta();
+                let scale_root = meta.scale.calc_root_syllable();
+                let signature = meta.signature;
+                let beat_duration = Units::from(signature.beat_unit);
                 for beat in 0..signature.bar_beats {
-                    let in_bar_pos = Units(beat as f32 * Units::from(signature.beat_unit).0);
+                    let in_bar_pos = Units(beat as f32 * beat_duration.0);
                     let root = bar
                         .get_chord(Some(in_bar_pos))
                         .map(|x| x.root)
                         .unwrap_or(scale_root);
-                    let note = tab.meta.scale.calc_click_note(
-                        &tab.meta.key,
-                        &settings.click_octave,
-                        &root,
-                    );
-                    let pos = BarPosition::new(bar_units, bar.props.bar_ordinal, in_bar_pos);
+                    let note = meta
+                        .scale
+                        .calc_click_note(&meta.key, &settings.click_octave, &root);
+                    let pos = BarPosition::from(bar.props).with_in_bar_pos(in_bar_pos);
                     if let Some(midi_msg) =
                         MidiUtil::note_midi_on_msg(&note, channel.channel, channel.velocity)
                     {
@@ -366,11 +374,24 @@ impl MidiState {
             for ((_k, _i), lane) in bar.lanes.iter() {
                 if let Some(channel) = self.get_channel_mut(&lane.track.id, &lane.track.kind) {
                     for entry in lane.entries.iter() {
-                        if let Some(msgs) = MidiUtil::get_midi_msgs(channel, bar, &entry) {
+                        let intonation = settings.get_pitch_bend_intonation(true);
+                        if let Some(msgs) =
+                            MidiUtil::get_midi_msgs(&channel.params(), intonation, bar, &entry)
+                        {
                             for msg in msgs {
                                 channel.add_message(MidiMessage::of_entry(entry, msg.0, msg.1));
                             }
                         }
+                        if let Some(msgs) = MidiUtil::get_midi_timed_msgs(
+                            &channel.params(),
+                            intonation,
+                            bar,
+                            &entry,
+                        ) {
+                            for msg in msgs {
+                                channel.add_message(MidiMessage::of_entry_at(entry, msg.0, msg.1));
+                            }
+                        }
                     }
                 }
             }
@@ -385,7 +406,6 @@ impl MidiState {
         bar_props: TabBarProps,
     ) {
         self.play_control
-            .position
             .set_in_bar(bar_props.bar_ordinal, Units(0.0));
         if self.play_control.is_bar_in_range(bar_props.bar_ordinal) {
             for channel in self.channels.iter_mut() {
@@ -456,6 +476,10 @@ impl MidiState {
         self.seek_position = Some(seek_position);
         self.play_control.play();
     }
+    /// How far before the target a seek stops, so the target is played next.
+    fn seek_gap() -> Rational {
+        Rational::new(1, 200_000)
+    }
     pub fn seek_forward(&mut self, settings: &MidiSettings) -> bool {
         if self.tab.is_some() {
             let pos = self.play_control.position.bar;
@@ -470,15 +494,13 @@ impl MidiState {
                     }
                 }) {
                     self.setup_seek(
-                        pos.with_in_bar_pos(props.in_bar_pos - Units::HALF_MIN_ACCURACY),
+                        pos.with_in_bar_pos(Units::from(props.in_bar_offset - Self::seek_gap())),
                     );
                     return true;
                 } else {
-                    self.setup_seek(BarPosition::new(
-                        pos.bar_units,
-                        pos.bar_ordinal,
-                        pos.bar_units - Units::HALF_MIN_ACCURACY,
-                    ));
+                    self.setup_seek(
+                        pos.with_in_bar_pos(Units::from(bar.meta.bar_length() - Self::seek_gap())),
+                    );
                     return true;
                 }
             }
@@ -494,8 +516,11 @@ impl MidiState {
     fn seek_passed(&mut self, settings: &MidiSettings) -> bool {
         if self.tab.is_some() {
             let mut pos = self.play_control.position.bar;
-            if pos.in_bar_pos >= pos.bar_units - Units::MIN_ACCURACY {
-                pos = BarPosition::new(pos.bar_units, pos.bar_ordinal + 1, Units::MIN_ACCURACY);
+            let bar_layout = &self.play_control.bar_layout;
+            let passed_pos = bar_layout.calc_bar_length(pos.bar_ordinal) - Self::seek_gap();
+            if pos.in_bar_pos >= Units::from(passed_pos) {
+                pos = bar_layout
+                    .calc_bar_position(pos.bar_ordinal + 1, Units::from(Self::seek_gap()));
                 if let Some(bar) = self.tab.as_ref().unwrap().get_bar(pos) {
                     if let Some(props) = bar.get_next_entry(pos.in_bar_pos, &|x| {
                         if x.track_kind() != settings.seeking_track {
@@ -0,0 +1,14 @@
+pub mod midi_file;
+pub mod midi_settings;
+pub mod midi_util;
+
+pub use helgoboss_midi;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::midi_file::{MidiFile, MidiFileEvent, MidiFileTrack};
+    #[doc(hidden)]
+    pub use crate::midi_settings::{MidiSettings, MidiTuningMode};
+    #[doc(hidden)]
+    pub use crate::midi_util::{MidiChannelParams, MidiUtil};
+}
@@ -0,0 +1,407 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use helgoboss_midi::{ShortMessage, StructuredShortMessage, U7};
+use notation_model::prelude::*;
+
+use crate::prelude::{MidiChannelParams, MidiSettings, MidiTuningMode, MidiUtil};
+
+// https://www.music.mcgill.ca/~ich/classes/mumt306/StandardMIDIfileformat.html
+#[derive(Clone, Debug)]
+pub struct MidiFileEvent {
+    pub tick: u32,
+    pub data: Vec<u8>,
+}
+impl MidiFileEvent {
+    pub fn new(tick: u32, data: Vec<u8>) -> Self {
+        Self { tick, data }
+    }
+    pub fn meta(tick: u32, meta_type: u8, payload: &[u8]) -> Self {
+        let mut data = vec![0xFF, meta_type];
+        MidiFile::push_var_len(&mut data, payload.len() as u32);
+        data.extend_from_slice(payload);
+        Self::new(tick, data)
+    }
+    pub fn short(tick: u32, msg: &StructuredShortMessage) -> Self {
+        let mut data = vec![msg.status_byte(), msg.data_byte_1().into()];
+        match msg {
+            StructuredShortMessage::ProgramChange { .. }
+            | StructuredShortMessage::ChannelPressure { .. } => {}
+            _ => data.push(msg.data_byte_2().into()),
+        }
+        Self::new(tick, data)
+    }
+    /// The data starts with `0xF0`, which is followed by the length in files,
+    /// empty data is skipped.
+    pub fn sysex(tick: u32, data: &[u8]) -> Option<Self> {
+        let (_, payload) = data.split_first()?;
+        let mut bytes = vec![0xF0];
+        MidiFile::push_var_len(&mut bytes, payload.len() as u32);
+        bytes.extend_from_slice(payload);
+        Some(Self::new(tick, bytes))
+    }
+    pub fn is_note_off(&self) -> bool {
+        match self.data.first() {
+            Some(status) if status & 0xF0 == 0x80 => true,
+            Some(status) if status & 0xF0 == 0x90 => self.data.get(2) == Some(&0),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MidiFileTrack {
+    pub events: Vec<MidiFileEvent>,
+}
+impl MidiFileTrack {
+    pub fn add_event(&mut self, event: MidiFileEvent) {
+        self.events.push(event);
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut events = self.events.clone();
+        // Stable sort, note offs go first so repeated notes are not cut short.
+        events.sort_by_key(|x| (x.tick, !x.is_note_off()));
+        let mut data = Vec::new();
+        let mut last_tick = 0;
+        for event in events.iter() {
+            MidiFile::push_var_len(&mut data, event.tick - last_tick);
+            data.extend_from_slice(&event.data);
+            last_tick = event.tick;
+        }
+        MidiFile::push_var_len(&mut data, 0);
+        data.extend_from_slice(&[0xFF, 0x2F, 0x00]);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"MTrk");
+        bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        bytes.append(&mut data);
+        bytes
+    }
+}
+
+/// Standard MIDI File (format 1), with a conductor track for the tab meta,
+/// followed by one track for each track in the tab.
+#[derive(Clone, Debug)]
+pub struct MidiFile {
+    pub ticks_per_quarter: u16,
+    pub tracks: Vec<MidiFileTrack>,
+}
+
+impl MidiFile {
+    pub const DEFAULT_TICKS_PER_QUARTER: u16 = 480;
+    pub const DRUMS_CHANNEL: u8 = 9;
+    pub const TUNING_PROGRAM: u8 = 0;
+
+    pub fn new(ticks_per_quarter: u16) -> Self {
+        Self {
+            ticks_per_quarter,
+            tracks: Vec::new(),
+        }
+    }
+    pub fn push_var_len(data: &mut Vec<u8>, value: u32) {
+        let mut buffer = [0u8; 5];
+        let mut index = buffer.len() - 1;
+        let mut value = value;
+        buffer[index] = (value & 0x7F) as u8;
+        value >>= 7;
+        while value > 0 {
+            index -= 1;
+            buffer[index] = ((value & 0x7F) as u8) | 0x80;
+            value >>= 7;
+        }
+        data.extend_from_slice(&buffer[index..]);
+    }
+    pub fn units_to_ticks(&self, units: Units) -> u32 {
+        (units.0 * 4.0 * self.ticks_per_quarter as f32).round() as u32
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"MThd");
+        bytes.extend_from_slice(&6u32.to_be_bytes());
+        bytes.extend_from_slice(&1u16.to_be_bytes());
+        bytes.extend_from_slice(&(self.tracks.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.ticks_per_quarter.to_be_bytes());
+        for track in self.tracks.iter() {
+            bytes.append(&mut track.to_bytes());
+        }
+        bytes
+    }
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+}
+
+impl MidiFile {
+    // https://www.recordingblogs.com/wiki/midi-key-signature-meta-message
+    pub fn calc_key_signature(meta: &TabMeta) -> (i8, u8) {
+        let sharps = meta.scale.calc_key_signature(&meta.key);
+        let minor = if meta.scale.to_diatonic() == Scale::Aeolian {
+            1
+        } else {
+            0
+        };
+        (sharps, minor)
+    }
+    /// Tempo in the tab is counted in beats of the signature's beat unit,
+    /// while midi files always use microseconds per quarter note.
+    pub fn calc_micros_per_quarter(meta: &TabMeta) -> u32 {
+        let bpm = Bpm::from(meta.tempo) as f32;
+        let quarters_per_minute = bpm * Units::from(meta.signature.beat_unit).0 * 4.0;
+        (60_000_000.0 / quarters_per_minute).round() as u32
+    }
+    pub fn calc_time_signature(meta: &TabMeta) -> [u8; 4] {
+        let denominator = match meta.signature.beat_unit {
+            Unit::Whole => 0,
+            Unit::Half => 1,
+            Unit::Quarter => 2,
+            Unit::Eighth => 3,
+            Unit::Sixteenth => 4,
+            Unit::ThirtySecondth => 5,
+            Unit::SixtyFourth => 6,
+        };
+        [meta.signature.bar_beats, denominator, 24, 8]
+    }
+    fn use_mts(settings: &MidiSettings) -> bool {
+        settings.tuning_mode == MidiTuningMode::Mts && !settings.intonation.is_standard()
+    }
+    // http://www.somascape.org/midi/tech/spec.html#rpns
+    /// Selects the tuning program with RPN 3, then resets the RPN.
+    fn tuning_program_msgs(channel: &MidiChannelParams) -> Vec<StructuredShortMessage> {
+        [
+            (101, 0),
+            (100, 3),
+            (6, Self::TUNING_PROGRAM),
+            (101, 127),
+            (100, 127),
+        ]
+        .iter()
+        .map(|(number, value)| StructuredShortMessage::ControlChange {
+            channel: channel.channel,
+            controller_number: helgoboss_midi::ControllerNumber::new(*number),
+            control_value: U7::new(*value),
+        })
+        .collect()
+    }
+    fn add_meta_events(
+        track: &mut MidiFileTrack,
+        settings: &MidiSettings,
+        tick: u32,
+        meta: &TabMeta,
+        last_meta: Option<&TabMeta>,
+    ) {
+        let tempo_changed = last_meta
+            .map(|x| x.tempo != meta.tempo || x.signature.beat_unit != meta.signature.beat_unit)
+            .unwrap_or(true);
+        if tempo_changed {
+            let micros = Self::calc_micros_per_quarter(meta).to_be_bytes();
+            track.add_event(MidiFileEvent::meta(tick, 0x51, &micros[1..]));
+        }
+        if last_meta
+            .map(|x| x.signature != meta.signature)
+            .unwrap_or(true)
+        {
+            let time_signature = Self::calc_time_signature(meta);
+            track.add_event(MidiFileEvent::meta(tick, 0x58, &time_signature));
+        }
+        if last_meta
+            .map(|x| x.key != meta.key || x.scale != meta.scale)
+            .unwrap_or(true)
+        {
+            let (sharps, minor) = Self::calc_key_signature(meta);
+            track.add_event(MidiFileEvent::meta(tick, 0x59, &[sharps as u8, minor]));
+            if Self::use_mts(settings) {
+                let key = &meta.key;
+                let intonation = &settings.intonation;
+                for data in MidiUtil::calc_mts_sysex(intonation, key, Self::TUNING_PROGRAM) {
+                    if let Some(event) = MidiFileEvent::sysex(tick, &data) {
+                        track.add_event(event);
+                    }
+                }
+            }
+        }
+    }
+    /// Tempo, signature and key changes in bars are written at the bar's tick.
+    fn new_meta_track(
+        &self,
+        settings: &MidiSettings,
+        tab: &Tab,
+        bar_ticks: &[Option<u32>],
+    ) -> MidiFileTrack {
+        let mut track = MidiFileTrack::default();
+        let mut last_meta: Option<TabMeta> = None;
+        for (bar, bar_tick) in tab.bars.iter().zip(bar_ticks.iter()) {
+            if let Some(tick) = bar_tick {
+                Self::add_meta_events(&mut track, settings, *tick, &bar.meta, last_meta.as_ref());
+                last_meta = Some(*bar.meta);
+            }
+        }
+        if last_meta.is_none() {
+            Self::add_meta_events(&mut track, settings, 0, &tab.meta, None);
+        }
+        track
+    }
+    fn new_channel(
+        settings: &MidiSettings,
+        track: &Arc<Track>,
+        index: u8,
+    ) -> Option<MidiChannelParams> {
+        settings
+            .get_track_channel_params(&track.kind)
+            .map(|params| MidiChannelParams::new(index, params.0, params.1))
+    }
+    /// Note that the key numbers from MidiUtil are adjusted for the live synth,
+    /// need to be restored for the file.
+    fn fix_key_number(msg: StructuredShortMessage) -> StructuredShortMessage {
+        let fix = |key_number: helgoboss_midi::KeyNumber| {
+            let value = u8::from(key_number) as i16 - MidiUtil::PLAYBACK_KEY_OFFSET as i16;
+            helgoboss_midi::KeyNumber::new(value.clamp(0, 127) as u8)
+        };
+        match msg {
+            StructuredShortMessage::NoteOn {
+                channel,
+                key_number,
+                velocity,
+            } => StructuredShortMessage::NoteOn {
+                channel,
+                key_number: fix(key_number),
+                velocity,
+            },
+            StructuredShortMessage::NoteOff {
+                channel,
+                key_number,
+                velocity,
+            } => StructuredShortMessage::NoteOff {
+                channel,
+                key_number: fix(key_number),
+                velocity,
+            },
+            _ => msg,
+        }
+    }
+    /// Bars in the tab are already expanded from the form, so the section repeats
+    /// are written out as played, the ready bar is skipped.
+    pub fn from_tab(settings: &MidiSettings, tab: &Tab, ticks_per_quarter: u16) -> Self {
+        let mut file = Self::new(ticks_per_quarter);
+        let intonation = settings.get_pitch_bend_intonation(false);
+        let mut bar_ticks = Vec::new();
+        let mut ticks = 0;
+        for bar in tab.bars.iter() {
+            if bar.section.kind == SectionKind::Ready {
+                bar_ticks.push(None);
+            } else {
+                bar_ticks.push(Some(ticks));
+                ticks += file.units_to_ticks(bar.bar_units());
+            }
+        }
+        file.tracks
+            .push(file.new_meta_track(settings, tab, &bar_ticks));
+        let mut index: u8 = 0;
+        for track in tab.tracks.iter() {
+            let mut file_track = MidiFileTrack::default();
+            file_track.add_event(MidiFileEvent::meta(0, 0x03, track.id.as_bytes()));
+            if index == Self::DRUMS_CHANNEL {
+                index += 1;
+            }
+            let channel = if index < 16 {
+                Self::new_channel(settings, track, index)
+            } else {
+                None
+            };
+            if let Some(channel) = channel {
+                index += 1;
+                file_track.add_event(MidiFileEvent::short(
+                    0,
+                    &StructuredShortMessage::ProgramChange {
+                        channel: channel.channel,
+                        program_number: channel.program,
+                    },
+                ));
+                if Self::use_mts(settings) {
+                    for msg in Self::tuning_program_msgs(&channel) {
+                        file_track.add_event(MidiFileEvent::short(0, &msg));
+                    }
+                }
+                for (bar, bar_tick) in tab.bars.iter().zip(bar_ticks.iter()) {
+                    let bar_tick = match bar_tick {
+                        Some(x) => *x,
+                        None => continue,
+                    };
+                    for lane in bar.lanes.values() {
+                        if lane.track.id != track.id || lane.track.kind != track.kind {
+                            continue;
+                        }
+                        for entry in lane.entries.iter() {
+                            if let Some(msgs) =
+                                MidiUtil::get_midi_msgs(&channel, intonation, bar, entry)
+                            {
+                                let play_tick = bar_tick + file.units_to_ticks(entry.in_bar_pos());
+                                let stop_tick = bar_tick
+                                    + file.units_to_ticks(entry.in_bar_pos() + entry.tied_units());
+                                for (stop, msg) in msgs {
+                                    let tick = if stop { stop_tick } else { play_tick };
+                                    file_track.add_event(MidiFileEvent::short(
+                                        tick,
+                                        &Self::fix_key_number(msg),
+                                    ));
+                                }
+                            }
+                            if let Some(msgs) =
+                                MidiUtil::get_midi_timed_msgs(&channel, intonation, bar, entry)
+                            {
+                                let play_tick = bar_tick + file.units_to_ticks(entry.in_bar_pos());
+                                let ticks = file.units_to_ticks(entry.tied_units());
+                                for (ratio, msg) in msgs {
+                                    let tick = play_tick + (ticks as f32 * ratio).round() as u32;
+                                    file_track.add_event(MidiFileEvent::short(
+                                        tick,
+                                        &Self::fix_key_number(msg),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            file.tracks.push(file_track);
+        }
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var_len(value: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        MidiFile::push_var_len(&mut data, value);
+        data
+    }
+
+    #[test]
+    fn test_var_len() {
+        assert_eq!(var_len(0), vec![0x00]);
+        assert_eq!(var_len(0x7F), vec![0x7F]);
+        assert_eq!(var_len(0x80), vec![0x81, 0x00]);
+        assert_eq!(var_len(0x3FFF), vec![0xFF, 0x7F]);
+        assert_eq!(var_len(0x0FFF_FFFF), vec![0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn test_sysex() {
+        let event = MidiFileEvent::sysex(0, &[0xF0, 0x7F, 0x01, 0xF7]).unwrap();
+        assert_eq!(event.data, vec![0xF0, 0x03, 0x7F, 0x01, 0xF7]);
+        assert!(MidiFileEvent::sysex(0, &[]).is_none());
+    }
+
+    #[test]
+    fn test_note_offs_first() {
+        let mut track = MidiFileTrack::default();
+        track.add_event(MidiFileEvent::new(10, vec![0x90, 60, 100]));
+        track.add_event(MidiFileEvent::new(10, vec![0x90, 60, 0]));
+        let bytes = track.to_bytes();
+        assert_eq!(&bytes[..4], b"MTrk");
+        assert_eq!(&bytes[8..12], &[10, 0x90, 60, 0]);
+        assert_eq!(&bytes[12..16], &[0, 0x90, 60, 100]);
+        assert_eq!(&bytes[bytes.len() - 3..], &[0xFF, 0x2F, 0x00]);
+    }
+}
@@ -1,7 +1,15 @@
 use midi_msg::GMSoundSet;
-use notation_model::prelude::{Octave, TrackKind};
+use notation_model::prelude::{Intonation, Octave, TrackKind};
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+/// How non equal temperaments are sent, a pitch bend is for the whole channel,
+/// so chords are bent by their first note, while MTS retunes every key.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MidiTuningMode {
+    PitchBend,
+    Mts,
+}
+
+#[derive(Clone, PartialEq, Debug)]
 pub struct MidiSettings {
     pub bypass_hub: bool,
     pub click_mute: bool,
@@ -17,9 +25,14 @@ pub struct MidiSettings {
     pub click_sound: u8,
     pub vocal_sound: u8,
     pub guitar_sound: u8,
+    pub bass_sound: u8,
+    pub banjo_sound: u8,
+    pub mandolin_sound: u8,
     pub piano_sound: u8,
     pub seeking_track: TrackKind,
     pub seeking_init_channel: bool,
+    pub intonation: Intonation,
+    pub tuning_mode: MidiTuningMode,
 }
 
 impl Default for MidiSettings {
@@ -39,9 +52,15 @@ impl Default for MidiSettings {
             click_sound: GMSoundSet::Dulcimer as u8,
             vocal_sound: GMSoundSet::Cello as u8,
             guitar_sound: GMSoundSet::AcousticGuitarSteel as u8,
+            bass_sound: GMSoundSet::ElectricBassFinger as u8,
+            banjo_sound: GMSoundSet::Banjo as u8,
+            // General MIDI doesn't have a mandolin
+            mandolin_sound: GMSoundSet::AcousticGuitarSteel as u8,
             piano_sound: GMSoundSet::AcousticGrandPiano as u8,
             seeking_track: TrackKind::Guitar,
             seeking_init_channel: true,
+            intonation: Intonation::default(),
+            tuning_mode: MidiTuningMode::PitchBend,
         }
     }
 }
@@ -59,6 +78,9 @@ impl MidiSettings {
         match kind {
             TrackKind::Vocal => Some((self.vocal_sound, self.vocal_velocity)),
             TrackKind::Guitar => Some((self.guitar_sound, self.guitar_velocity)),
+            TrackKind::Bass => Some((self.bass_sound, self.guitar_velocity)),
+            TrackKind::Banjo => Some((self.banjo_sound, self.guitar_velocity)),
+            TrackKind::Mandolin => Some((self.mandolin_sound, self.guitar_velocity)),
             TrackKind::Piano => Some((self.piano_sound, self.piano_velocity)),
             _ => None,
         }
@@ -66,4 +88,12 @@ impl MidiSettings {
     pub fn get_click_channel_params(&self) -> (u8, u8) {
         (self.click_sound, self.click_velocity)
     }
+    /// Live playback can only send short messages, so it always uses pitch bends.
+    pub fn get_pitch_bend_intonation(&self, live: bool) -> Option<&Intonation> {
+        if self.intonation.is_standard() || (!live && self.tuning_mode == MidiTuningMode::Mts) {
+            None
+        } else {
+            Some(&self.intonation)
+        }
+    }
 }
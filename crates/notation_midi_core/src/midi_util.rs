@@ -0,0 +1,427 @@
+use std::convert::TryFrom;
+
+use helgoboss_midi::{Channel, KeyNumber, StructuredShortMessage, U14, U7};
+use notation_model::prelude::{
+    CoreEntry, Entry, FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8,
+    Intonation, Key, LaneEntry, Note, Pick, PickTechniques, ProtoEntry, Semitones, TabBar, Tone,
+};
+
+/// Channel, program and velocity for the messages of a track.
+#[derive(Copy, Clone, Debug)]
+pub struct MidiChannelParams {
+    pub channel: Channel,
+    pub program: U7,
+    pub velocity: U7,
+}
+impl MidiChannelParams {
+    pub fn new(channel: u8, program: u8, velocity: u8) -> Self {
+        Self {
+            channel: Channel::new(channel),
+            program: U7::new(program),
+            velocity: U7::new(velocity),
+        }
+    }
+}
+
+pub struct MidiUtil();
+
+impl MidiUtil {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub const PLAYBACK_KEY_OFFSET: i8 = -1; //Not sure why got a higher pitch when playing, temp fix for get it right in video
+    #[cfg(target_arch = "wasm32")]
+    pub const PLAYBACK_KEY_OFFSET: i8 = 0;
+    /// Default pitch bend sensitivity of General MIDI, in semitones.
+    pub const PITCH_BEND_RANGE: f32 = 2.0;
+    /// Steps of the pitch bends for bends, slides and vibrato in a note.
+    pub const TECHNIQUE_BEND_STEPS: usize = 32;
+    /// Note offs of the techniques, as ratio of the note duration.
+    pub const DEAD_NOTE_STOP: f32 = 0.1;
+    pub const PALM_MUTE_STOP: f32 = 0.5;
+    pub const LET_RING_STOP: f32 = 2.0;
+
+    pub fn note_midi_key_number(note: &Note) -> Option<KeyNumber> {
+        let midi_note = Semitones::from(*note).0 + 12 + Self::PLAYBACK_KEY_OFFSET;
+        KeyNumber::try_from(midi_note as u8).ok()
+    }
+    pub fn note_midi_on_msg(
+        note: &Note,
+        channel: Channel,
+        velocity: U7,
+    ) -> Option<StructuredShortMessage> {
+        Self::note_midi_key_number(note).map(|key_number| -> StructuredShortMessage {
+            StructuredShortMessage::NoteOn {
+                channel,
+                key_number,
+                velocity,
+            }
+        })
+    }
+    pub fn note_midi_off_msg(
+        note: &Note,
+        channel: Channel,
+        velocity: U7,
+    ) -> Option<StructuredShortMessage> {
+        Self::note_midi_key_number(note).map(|key_number| StructuredShortMessage::NoteOff {
+            channel,
+            key_number,
+            velocity,
+        })
+    }
+    pub fn calc_pitch_bend(cents: f32) -> U14 {
+        let value = 8192.0 + cents / (Self::PITCH_BEND_RANGE * 100.0) * 8192.0;
+        U14::new(value.round().clamp(0.0, 16383.0) as u16)
+    }
+    pub fn note_pitch_bend_msg(
+        intonation: &Intonation,
+        key: &Key,
+        note: &Note,
+        channel: Channel,
+    ) -> StructuredShortMessage {
+        let cents = intonation.calc_cents(key, Semitones::from(*note));
+        StructuredShortMessage::PitchBendChange {
+            channel,
+            pitch_bend_value: Self::calc_pitch_bend(cents),
+        }
+    }
+    // https://www.midi.org/specifications-old/item/the-midi-1-0-specification
+    /// Real time single note tuning changes of all keys for the tuning program,
+    /// split in two messages since the count is 7 bits.
+    pub fn calc_mts_sysex(intonation: &Intonation, key: &Key, program: u8) -> Vec<Vec<u8>> {
+        let standard = Intonation::default();
+        (0..2u8)
+            .map(|half| {
+                let mut data = vec![0xF0, 0x7F, 0x7F, 0x08, 0x02, program & 0x7F, 64];
+                for key_number in half * 64..half * 64 + 64 {
+                    let semitones = Semitones(key_number as i8 - Intonation::MIDI_KEY_OFFSET as i8);
+                    let frequency = intonation.calc_semitones_frequency(key, semitones);
+                    let midi_key = Intonation::REFERENCE_SEMITONES.0 as f32
+                        + Intonation::MIDI_KEY_OFFSET as f32
+                        + 12.0 * (frequency / standard.reference).log2();
+                    let (base, fraction) = if midi_key < 0.0 {
+                        (0, 0)
+                    } else if midi_key >= 127.0 {
+                        (127, 0)
+                    } else {
+                        let base = midi_key.floor();
+                        let fraction = ((midi_key - base) * 16384.0).round().min(16383.0);
+                        (base as u8, fraction as u16)
+                    };
+                    data.extend_from_slice(&[
+                        key_number,
+                        base,
+                        (fraction >> 7) as u8,
+                        (fraction & 0x7F) as u8,
+                    ]);
+                }
+                data.push(0xF7);
+                data
+            })
+            .collect()
+    }
+    /// The notes are bent by the first one if the intonation is given.
+    pub fn get_tone_midi_msgs(
+        channel: &MidiChannelParams,
+        intonation: Option<&Intonation>,
+        bar: &TabBar,
+        entry: &LaneEntry,
+        tone: &Tone,
+    ) -> Option<Vec<(bool, StructuredShortMessage)>> {
+        Self::get_notes_midi_msgs(channel, intonation, bar, entry, &tone.get_notes())
+    }
+    pub fn get_notes_midi_msgs(
+        channel: &MidiChannelParams,
+        intonation: Option<&Intonation>,
+        bar: &TabBar,
+        entry: &LaneEntry,
+        notes: &[Note],
+    ) -> Option<Vec<(bool, StructuredShortMessage)>> {
+        let notes: Vec<(Note, PickTechniques)> = notes
+            .iter()
+            .map(|x| (*x, PickTechniques::default()))
+            .collect();
+        Self::get_technique_notes_midi_msgs(channel, intonation, bar, entry, &notes)
+    }
+    fn calc_technique_velocity(velocity: U7, techniques: &PickTechniques) -> U7 {
+        let factor = techniques.calc_velocity_factor();
+        U7::new((velocity.get() as f32 * factor).round().clamp(1.0, 127.0) as u8)
+    }
+    /// Velocities are lowered by the techniques, notes to let ring are stopped
+    /// by the timed messages instead.
+    pub fn get_technique_notes_midi_msgs(
+        channel: &MidiChannelParams,
+        intonation: Option<&Intonation>,
+        bar: &TabBar,
+        entry: &LaneEntry,
+        notes: &[(Note, PickTechniques)],
+    ) -> Option<Vec<(bool, StructuredShortMessage)>> {
+        if notes.is_empty() || entry.prev_is_tie() {
+            return None;
+        }
+        let mut play_msgs: Vec<(bool, StructuredShortMessage)> = match (intonation, notes.first()) {
+            (Some(intonation), Some((note, _))) => {
+                let key = bar.tab_meta().key;
+                vec![(
+                    false,
+                    Self::note_pitch_bend_msg(intonation, &key, note, channel.channel),
+                )]
+            }
+            _ => vec![],
+        };
+        play_msgs.extend(
+            notes
+                .iter()
+                .flat_map(|(note, techniques)| {
+                    let velocity = Self::calc_technique_velocity(channel.velocity, techniques);
+                    MidiUtil::note_midi_on_msg(note, channel.channel, velocity)
+                })
+                .map(|x| (false, x)),
+        );
+        let mut stop_msgs: Vec<(bool, StructuredShortMessage)> = notes
+            .iter()
+            .filter(|(_, techniques)| !techniques.let_ring)
+            .flat_map(|(note, _)| {
+                MidiUtil::note_midi_off_msg(note, channel.channel, channel.velocity)
+            })
+            .map(|x| (true, x))
+            .collect();
+        play_msgs.append(&mut stop_msgs);
+        if !play_msgs.is_empty() {
+            Some(play_msgs)
+        } else {
+            None
+        }
+    }
+    pub fn get_core_midi_msgs(
+        channel: &MidiChannelParams,
+        intonation: Option<&Intonation>,
+        bar: &TabBar,
+        entry: &LaneEntry,
+        core_entry: &CoreEntry,
+    ) -> Option<Vec<(bool, StructuredShortMessage)>> {
+        match core_entry {
+            CoreEntry::Tone(tone, _) => {
+                Self::get_tone_midi_msgs(channel, intonation, bar, entry, tone)
+            }
+            _ => None,
+        }
+    }
+    pub fn get_midi_msgs(
+        channel: &MidiChannelParams,
+        intonation: Option<&Intonation>,
+        bar: &TabBar,
+        entry: &LaneEntry,
+    ) -> Option<Vec<(bool, StructuredShortMessage)>> {
+        match entry.proto() {
+            notation_model::prelude::ProtoEntry::Core(core_entry) => {
+                Self::get_core_midi_msgs(channel, intonation, bar, entry, core_entry)
+            }
+            notation_model::prelude::ProtoEntry::Fretted6(fretted_entry) => {
+                Self::get_fretted_midi_msgs6(channel, intonation, bar, entry, fretted_entry)
+            }
+            notation_model::prelude::ProtoEntry::Fretted4(fretted_entry) => {
+                Self::get_fretted_midi_msgs4(channel, intonation, bar, entry, fretted_entry)
+            }
+            notation_model::prelude::ProtoEntry::Fretted5(fretted_entry) => {
+                Self::get_fretted_midi_msgs5(channel, intonation, bar, entry, fretted_entry)
+            }
+            notation_model::prelude::ProtoEntry::Fretted7(fretted_entry) => {
+                Self::get_fretted_midi_msgs7(channel, intonation, bar, entry, fretted_entry)
+            }
+            notation_model::prelude::ProtoEntry::Fretted8(fretted_entry) => {
+                Self::get_fretted_midi_msgs8(channel, intonation, bar, entry, fretted_entry)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl MidiUtil {
+    /// Messages in the middle of the entries, with the ratio of the duration,
+    /// for the techniques of the picks, e.g. bends.
+    pub fn get_midi_timed_msgs(
+        channel: &MidiChannelParams,
+        intonation: Option<&Intonation>,
+        bar: &TabBar,
+        entry: &LaneEntry,
+    ) -> Option<Vec<(f32, StructuredShortMessage)>> {
+        match entry.proto() {
+            ProtoEntry::Fretted6(FrettedEntry6::Pick(pick, _)) => {
+                Self::get_pick_timed_msgs6(channel, intonation, bar, entry, pick)
+            }
+            ProtoEntry::Fretted4(FrettedEntry4::Pick(pick, _)) => {
+                Self::get_pick_timed_msgs4(channel, intonation, bar, entry, pick)
+            }
+            ProtoEntry::Fretted5(FrettedEntry5::Pick(pick, _)) => {
+                Self::get_pick_timed_msgs5(channel, intonation, bar, entry, pick)
+            }
+            ProtoEntry::Fretted7(FrettedEntry7::Pick(pick, _)) => {
+                Self::get_pick_timed_msgs7(channel, intonation, bar, entry, pick)
+            }
+            ProtoEntry::Fretted8(FrettedEntry8::Pick(pick, _)) => {
+                Self::get_pick_timed_msgs8(channel, intonation, bar, entry, pick)
+            }
+            _ => None,
+        }
+    }
+    /// Pitch bend is for the whole channel, so the first note bent decides it
+    /// for all the notes, bends over the pitch bend range are cut.
+    pub fn get_technique_notes_timed_msgs(
+        channel: &MidiChannelParams,
+        intonation: Option<&Intonation>,
+        bar: &TabBar,
+        entry: &LaneEntry,
+        notes: &[(Note, PickTechniques)],
+    ) -> Option<Vec<(f32, StructuredShortMessage)>> {
+        if notes.is_empty() || entry.prev_is_tie() {
+            return None;
+        }
+        let mut msgs = vec![];
+        for (note, techniques) in notes.iter() {
+            let stop = if techniques.let_ring {
+                Self::LET_RING_STOP
+            } else if techniques.dead_note {
+                Self::DEAD_NOTE_STOP
+            } else if techniques.palm_mute {
+                Self::PALM_MUTE_STOP
+            } else {
+                continue;
+            };
+            if let Some(msg) = Self::note_midi_off_msg(note, channel.channel, channel.velocity) {
+                msgs.push((stop, msg));
+            }
+        }
+        if let Some((note, techniques)) = notes.iter().find(|x| x.1.has_pitch_change()) {
+            let base = match intonation {
+                Some(intonation) => {
+                    intonation.calc_cents(&bar.tab_meta().key, Semitones::from(*note))
+                }
+                None => 0.0,
+            };
+            for step in 0..=Self::TECHNIQUE_BEND_STEPS {
+                let ratio = step as f32 / Self::TECHNIQUE_BEND_STEPS as f32;
+                let cents = if step == Self::TECHNIQUE_BEND_STEPS {
+                    base
+                } else {
+                    base + techniques.calc_bend_cents(ratio)
+                };
+                msgs.push((
+                    ratio,
+                    StructuredShortMessage::PitchBendChange {
+                        channel: channel.channel,
+                        pitch_bend_value: Self::calc_pitch_bend(cents),
+                    },
+                ));
+            }
+        }
+        if !msgs.is_empty() {
+            Some(msgs)
+        } else {
+            None
+        }
+    }
+}
+
+macro_rules! impl_get_pick_midi_msgs {
+    ($name:ident, $timed_name:ident, $notes_name:ident, $get_fretted_shape:ident) => {
+        impl MidiUtil {
+            /// Sounding notes of the pick, each with the techniques of its pick note.
+            fn $notes_name(
+                bar: &TabBar,
+                entry: &LaneEntry,
+                pick: &Pick,
+            ) -> Option<Vec<(Note, PickTechniques)>> {
+                let (fretboard, shape) = bar.$get_fretted_shape(entry)?;
+                let meta = bar.tab_meta();
+                Some(
+                    pick.get_notes()
+                        .into_iter()
+                        .flat_map(|x| {
+                            fretboard
+                                .pick_sounding_notes(&meta.scale, &meta.key, &shape, &Pick::from(x))
+                                .into_iter()
+                                .map(move |note| (note, x.techniques))
+                        })
+                        .collect(),
+                )
+            }
+            pub fn $name(
+                channel: &MidiChannelParams,
+                intonation: Option<&Intonation>,
+                bar: &TabBar,
+                entry: &LaneEntry,
+                pick: &Pick,
+            ) -> Option<Vec<(bool, StructuredShortMessage)>> {
+                let notes = Self::$notes_name(bar, entry, pick)?;
+                Self::get_technique_notes_midi_msgs(channel, intonation, bar, entry, &notes)
+            }
+            pub fn $timed_name(
+                channel: &MidiChannelParams,
+                intonation: Option<&Intonation>,
+                bar: &TabBar,
+                entry: &LaneEntry,
+                pick: &Pick,
+            ) -> Option<Vec<(f32, StructuredShortMessage)>> {
+                let notes = Self::$notes_name(bar, entry, pick)?;
+                Self::get_technique_notes_timed_msgs(channel, intonation, bar, entry, &notes)
+            }
+        }
+    };
+}
+
+macro_rules! impl_get_fretted_midi_msgs {
+    ($name:ident, $get_pick_midi_msgs:ident, $fretted_entry:ident) => {
+        impl MidiUtil {
+            pub fn $name(
+                channel: &MidiChannelParams,
+                intonation: Option<&Intonation>,
+                bar: &TabBar,
+                entry: &LaneEntry,
+                fretted_entry: &$fretted_entry,
+            ) -> Option<Vec<(bool, StructuredShortMessage)>> {
+                match fretted_entry {
+                    $fretted_entry::Pick(pick, _) => {
+                        Self::$get_pick_midi_msgs(channel, intonation, bar, entry, pick)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_get_pick_midi_msgs!(
+    get_pick_midi_msgs6,
+    get_pick_timed_msgs6,
+    get_pick_technique_notes6,
+    get_fretted_shape6
+);
+impl_get_pick_midi_msgs!(
+    get_pick_midi_msgs4,
+    get_pick_timed_msgs4,
+    get_pick_technique_notes4,
+    get_fretted_shape4
+);
+impl_get_pick_midi_msgs!(
+    get_pick_midi_msgs5,
+    get_pick_timed_msgs5,
+    get_pick_technique_notes5,
+    get_fretted_shape5
+);
+impl_get_pick_midi_msgs!(
+    get_pick_midi_msgs7,
+    get_pick_timed_msgs7,
+    get_pick_technique_notes7,
+    get_fretted_shape7
+);
+impl_get_pick_midi_msgs!(
+    get_pick_midi_msgs8,
+    get_pick_timed_msgs8,
+    get_pick_technique_notes8,
+    get_fretted_shape8
+);
+
+impl_get_fretted_midi_msgs!(get_fretted_midi_msgs6, get_pick_midi_msgs6, FrettedEntry6);
+impl_get_fretted_midi_msgs!(get_fretted_midi_msgs4, get_pick_midi_msgs4, FrettedEntry4);
+impl_get_fretted_midi_msgs!(get_fretted_midi_msgs5, get_pick_midi_msgs5, FrettedEntry5);
+impl_get_fretted_midi_msgs!(get_fretted_midi_msgs7, get_pick_midi_msgs7, FrettedEntry7);
+impl_get_fretted_midi_msgs!(get_fretted_midi_msgs8, get_pick_midi_msgs8, FrettedEntry8);
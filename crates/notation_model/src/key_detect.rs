@@ -0,0 +1,122 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use notation_proto::prelude::{
+    CoreEntry, Entry, FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8,
+    KeyEstimate, PitchWeights, ProtoEntry, Tone,
+};
+
+use crate::prelude::{LaneEntry, Tab, TabBar};
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SectionKeyEstimate {
+    pub section_index: usize,
+    pub section_id: String,
+    pub estimate: KeyEstimate,
+    /// The do is different from the one of the whole tab.
+    pub modulates: bool,
+}
+impl Display for SectionKeyEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "<SectionKeyEstimate>({}:{} {}{})",
+            self.section_index,
+            self.section_id,
+            self.estimate,
+            if self.modulates { " modulates" } else { "" }
+        )
+    }
+}
+
+impl Tab {
+    /// Sections with less confidence than this are not flagged as modulating.
+    pub const MODULATION_CONFIDENCE: f32 = 0.05;
+
+    /// Estimates from the tones and fretted picks in all tracks, ignoring `Meta:`.
+    pub fn detect_key(&self) -> Option<KeyEstimate> {
+        KeyEstimate::estimate(&Self::calc_pitch_weights(self.bars.iter()))
+    }
+    /// One estimate for each section with notes, all rounds are counted.
+    pub fn detect_section_keys(&self) -> Vec<SectionKeyEstimate> {
+        let tab_estimate = self.detect_key();
+        self.sections
+            .iter()
+            .filter_map(|section| {
+                let bars = self
+                    .bars
+                    .iter()
+                    .filter(|x| x.props.section_index == section.index);
+                KeyEstimate::estimate(&Self::calc_pitch_weights(bars)).map(|estimate| {
+                    let modulates = match tab_estimate {
+                        Some(tab_estimate) => {
+                            estimate.confidence >= Self::MODULATION_CONFIDENCE
+                                && !tab_estimate.matches(&estimate.key, &estimate.scale)
+                        }
+                        None => false,
+                    };
+                    SectionKeyEstimate {
+                        section_index: section.index,
+                        section_id: section.id.clone(),
+                        estimate,
+                        modulates,
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+impl Tab {
+    fn calc_pitch_weights<'a, I: Iterator<Item = &'a Arc<TabBar>>>(bars: I) -> PitchWeights {
+        let mut weights = PitchWeights::default();
+        for bar in bars {
+            for lane in bar.lanes.values() {
+                for entry in lane.entries.iter() {
+                    if entry.prev_is_tie() {
+                        continue;
+                    }
+                    if let Some(tone) = Self::get_entry_tone(bar, entry) {
+                        for note in tone.get_notes() {
+                            weights.add_note(&note, entry.tied_units().0);
+                        }
+                    }
+                }
+            }
+        }
+        weights
+    }
+    /// Tone of a core or fretted pick entry, in the meta of the bar.
+    pub fn get_entry_tone(bar: &TabBar, entry: &LaneEntry) -> Option<Tone> {
+        let meta = bar.tab_meta();
+        match entry.proto() {
+            ProtoEntry::Core(CoreEntry::Tone(tone, _)) => Some(*tone),
+            ProtoEntry::Fretted6(FrettedEntry6::Pick(pick, _)) => {
+                bar.get_fretted_shape6(entry).map(|(fretboard, shape)| {
+                    fretboard.pick_tone(&meta.scale, &meta.key, &shape, pick)
+                })
+            }
+            ProtoEntry::Fretted4(FrettedEntry4::Pick(pick, _)) => {
+                bar.get_fretted_shape4(entry).map(|(fretboard, shape)| {
+                    fretboard.pick_tone(&meta.scale, &meta.key, &shape, pick)
+                })
+            }
+            ProtoEntry::Fretted5(FrettedEntry5::Pick(pick, _)) => {
+                bar.get_fretted_shape5(entry).map(|(fretboard, shape)| {
+                    fretboard.pick_tone(&meta.scale, &meta.key, &shape, pick)
+                })
+            }
+            ProtoEntry::Fretted7(FrettedEntry7::Pick(pick, _)) => {
+                bar.get_fretted_shape7(entry).map(|(fretboard, shape)| {
+                    fretboard.pick_tone(&meta.scale, &meta.key, &shape, pick)
+                })
+            }
+            ProtoEntry::Fretted8(FrettedEntry8::Pick(pick, _)) => {
+                bar.get_fretted_shape8(entry).map(|(fretboard, shape)| {
+                    fretboard.pick_tone(&meta.scale, &meta.key, &shape, pick)
+                })
+            }
+            _ => None,
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 
 use crate::prelude::{BarLane, BarLaneProps, ModelEntry, Tab, TabBar, TabBarProps, Track};
 use notation_proto::prelude::{
-    BarPosition, Duration, Entry, EntryPassMode, ProtoEntry, TrackKind, Units, Slice,
+    BarPosition, Duration, Entry, EntryPassMode, ProtoEntry, Rational, TrackKind, Units, Slice,
 };
 
 #[derive(Clone, Debug, Default)]
@@ -12,6 +12,8 @@ pub struct LaneEntryProps {
     pub slice_index: usize,
     pub index: usize,
     pub in_bar_pos: Units,
+    /// Exact `in_bar_pos`, for comparing positions without rounding.
+    pub in_bar_offset: Rational,
     pub tied_units: Units,
     pub duration: Duration,
 }
@@ -34,13 +36,14 @@ impl LaneEntry {
         slice_index: usize,
         index: usize,
         model: Arc<ModelEntry>,
-        in_bar_pos: Units,
+        in_bar_offset: Rational,
     ) -> Self {
         let props = LaneEntryProps {
             slice,
             slice_index,
             index,
-            in_bar_pos,
+            in_bar_pos: Units::from(in_bar_offset),
+            in_bar_offset,
             tied_units: model.tied_units(),
             duration: model.duration(),
         };
@@ -68,12 +71,11 @@ impl LaneEntry {
     pub fn in_bar_pos(&self) -> Units {
         self.props.in_bar_pos
     }
+    pub fn in_bar_offset(&self) -> Rational {
+        self.props.in_bar_offset
+    }
     pub fn bar_position(&self) -> BarPosition {
-        BarPosition::new(
-            self.bar_props().bar_units,
-            self.bar_props().bar_ordinal,
-            self.props.in_bar_pos,
-        )
+        BarPosition::from(self.bar_props()).with_in_bar_pos(self.props.in_bar_pos)
     }
 }
 impl LaneEntry {
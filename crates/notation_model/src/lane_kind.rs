@@ -1,10 +1,24 @@
 use std::fmt::Display;
 use std::sync::Arc;
 
-use notation_proto::prelude::{FrettedEntry6, ProtoEntry, TrackKind};
+use notation_proto::prelude::{
+    FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8, ProtoEntry,
+    TrackKind,
+};
 
 use crate::prelude::ModelEntry;
 
+macro_rules! fretted_lane_kind {
+    ($entry:ident, $fretted_entry:ident) => {
+        match $entry {
+            $fretted_entry::Pick(_, _) => Self::Strings,
+            $fretted_entry::Strum(_, _) => Self::Strings,
+            $fretted_entry::Shape(_, _) => Self::Shapes,
+            $fretted_entry::Fretboard(_) => Self::None,
+        }
+    };
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 pub enum LaneKind {
     None,
@@ -49,19 +63,19 @@ impl LaneKind {
             TrackKind::Chord => Self::Chord,
             TrackKind::Lyrics => Self::Lyrics,
             TrackKind::Vocal => Self::Melody,
-            TrackKind::Guitar => match entry {
-                ProtoEntry::Fretted6(entry) => match entry {
-                    FrettedEntry6::Pick(_, _) => Self::Strings,
-                    FrettedEntry6::Strum(_, _) => Self::Strings,
-                    FrettedEntry6::Shape(_, _) => Self::Shapes,
-                    FrettedEntry6::Fretboard(_) => Self::None,
-                },
-                _ => Self::None,
-            },
+            TrackKind::Guitar | TrackKind::Bass | TrackKind::Banjo | TrackKind::Mandolin => {
+                match entry {
+                    ProtoEntry::Fretted4(entry) => fretted_lane_kind!(entry, FrettedEntry4),
+                    ProtoEntry::Fretted5(entry) => fretted_lane_kind!(entry, FrettedEntry5),
+                    ProtoEntry::Fretted6(entry) => fretted_lane_kind!(entry, FrettedEntry6),
+                    ProtoEntry::Fretted7(entry) => fretted_lane_kind!(entry, FrettedEntry7),
+                    ProtoEntry::Fretted8(entry) => fretted_lane_kind!(entry, FrettedEntry8),
+                    _ => Self::None,
+                }
+            }
             TrackKind::Synth => Self::Keyboard,
             TrackKind::Piano => Self::Keyboard,
             TrackKind::Drums => Self::None,
-            TrackKind::Bass => Self::None,
         }
     }
     pub fn of_entries(track_kind: &TrackKind, entries: &Vec<Arc<ModelEntry>>) -> LaneKind {
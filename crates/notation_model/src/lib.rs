@@ -3,6 +3,7 @@ pub use notation_proto;
 pub mod bar;
 pub mod bar_lane;
 pub mod form;
+pub mod key_detect;
 pub mod lane_entry;
 pub mod lane_kind;
 pub mod model_entry;
@@ -14,6 +15,7 @@ pub mod tab_bar;
 pub mod tab_chord;
 pub mod track;
 pub mod util;
+pub mod validate;
 
 pub mod prelude {
     #[doc(hidden)]
@@ -25,6 +27,8 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::form::Form;
     #[doc(hidden)]
+    pub use crate::key_detect::SectionKeyEstimate;
+    #[doc(hidden)]
     pub use crate::lane_entry::{LaneEntry, LaneEntryProps};
     #[doc(hidden)]
     pub use crate::lane_kind::LaneKind;
@@ -51,6 +55,8 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::track::{Track, TrackProps};
     #[doc(hidden)]
+    pub use crate::validate::{DiagnosticKind, DiagnosticLocation, TabDiagnostic};
+    #[doc(hidden)]
     pub use notation_proto::prelude::Bar as ProtoBar;
     #[doc(hidden)]
     pub use notation_proto::prelude::BarLayer as ProtoBarLayer;
@@ -2,7 +2,8 @@ use std::sync::{Arc, Weak};
 
 use crate::prelude::{Tab, Track};
 use notation_proto::prelude::{
-    Duration, Entry, EntryPassMode, FrettedEntry4, FrettedEntry6, ProtoEntry, TrackKind, Units,
+    Duration, Entry, EntryPassMode, FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7,
+    FrettedEntry8, ProtoEntry, TrackKind, Units,
 };
 
 #[derive(Copy, Clone, Debug)]
@@ -62,6 +63,15 @@ impl ModelEntry {
     pub fn as_fretted4(&self) -> Option<&FrettedEntry4> {
         self.proto.as_fretted4()
     }
+    pub fn as_fretted5(&self) -> Option<&FrettedEntry5> {
+        self.proto.as_fretted5()
+    }
+    pub fn as_fretted7(&self) -> Option<&FrettedEntry7> {
+        self.proto.as_fretted7()
+    }
+    pub fn as_fretted8(&self) -> Option<&FrettedEntry8> {
+        self.proto.as_fretted8()
+    }
     pub fn prev(&self) -> Option<Arc<ModelEntry>> {
         if self.props.index == 0 {
             None
@@ -4,8 +4,8 @@ use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 use thiserror::Error;
 
-use crate::prelude::{BarLane, Form, LaneEntry, ModelEntry, Section, Tab, TabBar, TabMeta, Track, Slice};
-use notation_proto::prelude::{Duration, Entry, ProtoEntry, Units};
+use crate::prelude::{Bar, BarLane, Form, LaneEntry, ModelEntry, Section, Tab, TabBar, TabBarProps, TabMeta, Track, Slice};
+use notation_proto::prelude::{Duration, Entry, ProtoEntry, Rational, Units};
 
 #[derive(Error, Clone, Debug)]
 pub enum ParseError {
@@ -20,6 +20,7 @@ impl Tab {
     pub fn try_parse_arc(proto: notation_proto::prelude::Tab, add_ready_section: bool, bars_range:Option<(usize, usize)>) -> Arc<Self> {
         Arc::<Tab>::new_cyclic(|weak_self| {
             let uuid = proto.uuid;
+            let title = proto.title;
             let meta = Arc::new(proto.meta);
             let tracks = proto
                 .tracks
@@ -49,6 +50,11 @@ impl Tab {
             let bars = if let Some((begin, end)) = bars_range {
                 if begin < all_bars.len() && end < all_bars.len() && end >= begin {
                     let ready_added = add_ready_section && begin > 0;
+                    let mut bar_pos = if ready_added {
+                        all_bars[0].meta.bar_length()
+                    } else {
+                        Rational::ZERO
+                    };
                     let mut bars: Vec<Arc<TabBar>> = all_bars[begin..=end].iter()
                         .enumerate()
                         .map(|(index, bar)| {
@@ -58,16 +64,19 @@ impl Tab {
                             } else {
                                 begin + index + 1
                             };
+                            let props = TabBarProps {
+                                bar_ordinal,
+                                bar_number,
+                                bar_pos: Units::from(bar_pos),
+                                ..bar.props
+                            };
+                            bar_pos += bar.meta.bar_length();
                             TabBar::new_arc(
                                 bar.tab.clone(),
                                 bar.section.clone(),
                                 bar.proto.clone(),
-                                bar.props.section_round,
-                                bar.props.section_ordinal,
-                                bar.props.bar_index,
-                                bar_ordinal,
-                                bar_number,
-                                bar.props.bar_units,
+                                props,
+                                bar.meta.clone(),
                             )
                         }).collect();
                     if ready_added {
@@ -82,6 +91,7 @@ impl Tab {
             };
             Self {
                 uuid,
+                title,
                 meta,
                 tracks,
                 sections,
@@ -93,7 +103,11 @@ impl Tab {
     fn new_tab_bars(add_ready_section: bool, weak_self: &Weak<Tab>, meta: &TabMeta, form: &Form) -> Vec<Arc<TabBar>> {
         let mut section_rounds: HashMap<String, usize> = HashMap::new();
         let mut section_ordinal: usize = 0;
-        let mut bar_ordinal: usize = 0;
+        let mut cursor = TabBarCursor {
+            bar_ordinal: 0,
+            bar_pos: Rational::ZERO,
+            meta: Arc::new(*meta),
+        };
         let mut bars: Vec<Arc<TabBar>> = vec![];
         for section in form.sections.iter() {
             let section_round = match section_rounds.get(&section.id) {
@@ -107,11 +121,9 @@ impl Tab {
                 weak_self.clone(),
                 section_round,
                 section_ordinal,
-                bar_ordinal,
-                meta.bar_units(),
+                &mut cursor,
             ));
             section_ordinal += 1;
-            bar_ordinal += section.bars.len();
             println!(
                 "new_tab_bars() section: {} <{}> -> {:?} bars",
                 section.id,
@@ -123,59 +135,94 @@ impl Tab {
         bars
     }
 }
+/// Ordinal, position and meta of the next bar, while creating the bars of the form.
+struct TabBarCursor {
+    bar_ordinal: usize,
+    bar_pos: Rational,
+    meta: Arc<TabMeta>,
+}
 impl Section {
-    pub fn new_tab_bars(
+    fn new_tab_bars(
         &self,
         add_ready_section: bool,
         arc_section: Arc<Section>,
         tab: Weak<Tab>,
         section_round: usize,
         section_ordinal: usize,
-        section_bar_ordinal: usize,
-        bar_units: Units,
+        cursor: &mut TabBarCursor,
     ) -> Vec<Arc<TabBar>> {
         self.bars
             .iter()
             .enumerate()
             .map(|(bar_index, bar)| {
-                let bar_ordinal = section_bar_ordinal + bar_index;
+                let bar_ordinal = cursor.bar_ordinal;
                 let bar_number = if !add_ready_section {
                     bar_ordinal + 1
                 } else {
                     bar_ordinal
                 };
-                TabBar::new_arc(
-                    tab.clone(),
-                    arc_section.clone(),
-                    bar.clone(),
+                if let Some(bar_meta) = Self::calc_bar_meta(bar, section_round, &cursor.meta) {
+                    cursor.meta = Arc::new(bar_meta);
+                }
+                let props = TabBarProps {
                     section_round,
                     section_ordinal,
                     bar_index,
                     bar_ordinal,
                     bar_number,
-                    bar_units,
+                    bar_pos: Units::from(cursor.bar_pos),
+                    ..TabBarProps::default()
+                };
+                cursor.bar_ordinal += 1;
+                cursor.bar_pos += cursor.meta.bar_length();
+                TabBar::new_arc(
+                    tab.clone(),
+                    arc_section.clone(),
+                    bar.clone(),
+                    props,
+                    cursor.meta.clone(),
                 )
             })
             .collect()
     }
+    /// Returns the new meta if there are meta entries in the bar.
+    fn calc_bar_meta(bar: &Bar, section_round: usize, meta: &TabMeta) -> Option<TabMeta> {
+        let mut result: Option<TabMeta> = None;
+        for layer in bar.layers.iter() {
+            for slice in layer.slices.iter() {
+                if !slice.in_round(section_round) {
+                    continue;
+                }
+                for entry in layer.track.get_entries(&slice.begin, &slice.end) {
+                    if let Some(meta_entry) = entry.proto.as_meta() {
+                        result = Some(result.unwrap_or(*meta).apply(meta_entry));
+                    }
+                }
+            }
+        }
+        result
+    }
 }
 impl ModelEntry {
     pub fn calc_tied_units(entries: &Vec<ProtoEntry>, index: usize) -> Units {
-        let mut units = Units(0.0);
+        Self::calc_tied_length(entries, index).into()
+    }
+    fn calc_tied_length(entries: &Vec<ProtoEntry>, index: usize) -> Rational {
+        let mut length = Rational::ZERO;
         if let Some(entry) = entries.get(index) {
-            units = units + Units::from(entry.duration());
+            length += Rational::from(entry.duration());
             if let Some(next_entry) = entries.get(index + 1) {
                 if next_entry.is_core_tie() {
                     for i in index + 2..entries.len() {
                         let peek_entry = entries.get(i).unwrap();
                         if peek_entry.duration() != Duration::Zero {
-                            return units + Self::calc_tied_units(entries, i);
+                            return length + Self::calc_tied_length(entries, i);
                         }
                     }
                 }
             }
         }
-        units
+        length
     }
     pub fn new_entries(v: Vec<ProtoEntry>, track: &Weak<Track>) -> Vec<Arc<ModelEntry>> {
         let entries = v.clone();
@@ -192,13 +239,13 @@ impl ModelEntry {
 }
 impl LaneEntry {
     pub fn new_entries(v: Vec<Arc<ModelEntry>>, lane: &Weak<BarLane>, slice: Slice) -> Vec<Arc<LaneEntry>> {
-        let mut pos = 0.0;
+        let mut pos = Rational::ZERO;
         v.into_iter()
             .enumerate()
             .map(|(index, entry)| {
                 let in_bar_pos = pos;
-                pos += Units::from(entry.as_ref().duration()).0;
-                LaneEntry::new(lane.clone(), slice.clone(), index, index, entry, Units(in_bar_pos))
+                pos += Rational::from(entry.as_ref().duration());
+                LaneEntry::new(lane.clone(), slice.clone(), index, index, entry, in_bar_pos)
             })
             .map(Arc::new)
             .collect()
@@ -1,6 +1,6 @@
 use notation_proto::prelude::TabPosition;
 
-use crate::prelude::{BarPosition, Bpm, PlayState, Position, Tab, TabMeta, Units};
+use crate::prelude::{BarLayout, BarPosition, Bpm, PlayState, Position, Tab, TabMeta, Units};
 
 #[derive(Debug)]
 pub struct TabPlayStateChanged();
@@ -22,6 +22,13 @@ impl PlaySpeed {
             factor: 1.0,
         }
     }
+    /// Keeps the factor, for the tempo changes in bars.
+    pub fn with_tempo(&self, tab_meta: &TabMeta) -> Self {
+        Self {
+            factor: self.factor,
+            ..Self::new(tab_meta)
+        }
+    }
     pub fn factor(&self) -> f32 {
         self.factor
     }
@@ -53,7 +60,11 @@ pub struct PlayControl {
     pub end_bar_ordinal: usize,
     pub should_loop: bool,
     pub play_state: PlayState,
+    /// Speed of the current bar.
     pub play_speed: PlaySpeed,
+    pub bar_layout: BarLayout,
+    /// Metas of the bars, for the tempo changes.
+    pub bar_metas: Vec<TabMeta>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -76,20 +87,51 @@ impl TickResult {
 impl Default for PlayControl {
     fn default() -> Self {
         let tab_meta = TabMeta::default();
-        Self::_new(&tab_meta, 0)
+        Self::_new(&tab_meta, vec![])
     }
 }
 
 impl PlayControl {
-    fn _new(tab_meta: &TabMeta, bars: usize) -> Self {
+    fn _new(tab_meta: &TabMeta, bar_metas: Vec<TabMeta>) -> Self {
+        let bars = bar_metas.len();
+        let bar_layout = BarLayout::new(bar_metas.iter().map(|x| x.bar_length()).collect());
+        let first_meta = bar_metas.first().unwrap_or(tab_meta);
         Self {
-            position: Position::new(tab_meta.bar_units()),
+            position: Position::new(first_meta.bar_units()),
             bars,
             begin_bar_ordinal: 0,
             end_bar_ordinal: if bars > 0 { bars - 1 } else { bars },
             should_loop: false,
             play_state: PlayState::default(),
-            play_speed: PlaySpeed::new(tab_meta),
+            play_speed: PlaySpeed::new(first_meta),
+            bar_layout,
+            bar_metas,
+        }
+    }
+    fn calc_bar_speed(&self, bar_ordinal: usize) -> PlaySpeed {
+        match self.bar_metas.get(bar_ordinal).or_else(|| self.bar_metas.last()) {
+            Some(meta) => self.play_speed.with_tempo(meta),
+            None => self.play_speed,
+        }
+    }
+    fn update_speed(&mut self) {
+        self.play_speed = self.calc_bar_speed(self.position.bar.bar_ordinal);
+    }
+    /// The time can pass several bars with different tempos.
+    fn calc_delta_units(&self, delta_seconds: f32) -> Units {
+        let mut seconds = delta_seconds;
+        let mut units = Units(0.0);
+        let mut bar = self.position.bar;
+        loop {
+            let speed = self.calc_bar_speed(bar.bar_ordinal);
+            let left_units = bar.bar_units - bar.in_bar_pos;
+            let left_seconds = speed.calc_seconds(left_units);
+            if seconds <= left_seconds || bar.bar_ordinal + 1 >= self.bars {
+                return units + speed.calc_units(seconds);
+            }
+            seconds -= left_seconds;
+            units = units + left_units;
+            bar = self.bar_layout.calc_bar_position(bar.bar_ordinal + 1, Units(0.0));
         }
     }
     pub fn get_last_bar_ordinal(&self) -> usize {
@@ -100,7 +142,11 @@ impl PlayControl {
         }
     }
     pub fn new(tab: &Tab) -> Self {
-        Self::_new(&tab.meta, tab.bars.len())
+        Self::_new(&tab.meta, tab.bars.iter().map(|x| *x.meta).collect())
+    }
+    pub fn set_in_bar(&mut self, bar_ordinal: usize, in_bar_pos: Units) {
+        self.position.set_in_bar(&self.bar_layout, bar_ordinal, in_bar_pos);
+        self.update_speed();
     }
     pub fn play(&mut self) -> bool {
         if self.play_state.is_playing() {
@@ -124,16 +170,16 @@ impl PlayControl {
         if !self.play_state.is_stopped() {
             self.play_state = PlayState::Stopped;
         }
-        self.position.set_in_bar(self.begin_bar_ordinal, Units(0.0));
+        self.set_in_bar(self.begin_bar_ordinal, Units(0.0));
         true
     }
     pub fn _tick_to_position(&mut self, jumped: bool, pos: TabPosition) -> TickResult {
-        self.position.set_in_tab(pos.in_tab_pos);
+        self.position.set_in_tab(&self.bar_layout, pos.in_tab_pos);
+        self.update_speed();
         let end_passed = self.position.bar.bar_ordinal > self.end_bar_ordinal;
         let stopped = if end_passed {
             if self.should_loop {
-                self.position
-                    .set_in_bar(self.begin_bar_ordinal, self.position.bar.in_bar_pos);
+                self.set_in_bar(self.begin_bar_ordinal, self.position.bar.in_bar_pos);
                 if self.position.bar.bar_ordinal > self.end_bar_ordinal {
                     self.stop() //Corner case for too small range
                 } else {
@@ -153,11 +199,11 @@ impl PlayControl {
             let delta_units = if self.position.bar.bar_ordinal < self.begin_bar_ordinal
                 || self.position.bar.bar_ordinal > self.end_bar_ordinal
             {
-                self.position.set_in_bar(self.begin_bar_ordinal, Units(0.0));
+                self.set_in_bar(self.begin_bar_ordinal, Units(0.0));
                 jumped = true;
                 Units(0.0)
             } else {
-                self.play_speed.calc_units(delta_seconds)
+                self.calc_delta_units(delta_seconds)
             };
             self._tick_to_position(
                 jumped,
@@ -173,11 +219,8 @@ impl PlayControl {
             && bar_ordinal <= self.end_bar_ordinal
     }
     pub fn begin_bar_position(&self) -> BarPosition {
-        BarPosition::new(
-            self.position.bar.bar_units,
-            self.begin_bar_ordinal,
-            Units(0.0),
-        )
+        self.bar_layout
+            .calc_bar_position(self.begin_bar_ordinal, Units(0.0))
     }
     pub fn has_selection(&self, add_ready_section: bool) -> bool {
         if add_ready_section {
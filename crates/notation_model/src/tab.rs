@@ -11,6 +11,7 @@ use crate::prelude::{
 #[derive(Debug)]
 pub struct Tab {
     pub uuid: Uuid,
+    pub title: Option<String>,
     pub meta: Arc<TabMeta>,
     pub tracks: Vec<Arc<Track>>,
     pub sections: Vec<Arc<Section>>,
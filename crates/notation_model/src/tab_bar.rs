@@ -3,8 +3,8 @@ use std::sync::{Arc, Weak};
 use std::collections::HashMap;
 
 use notation_proto::prelude::{
-    BarPosition, Chord, Fretboard4, Fretboard6, HandShape4, HandShape6, Note, Position,
-    TabPosition, Octave,
+    BarPosition, Chord, Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8, HandShape4,
+    HandShape5, HandShape6, HandShape7, HandShape8, Note, Position, TabPosition, Octave,
 };
 
 use crate::prelude::{
@@ -21,11 +21,12 @@ pub struct TabBarProps {
     pub bar_ordinal: usize,
     pub bar_number: usize,
     pub bar_units: Units,
+    pub bar_pos: Units,
 }
 
 impl From<TabBarProps> for BarPosition {
     fn from(v: TabBarProps) -> Self {
-        BarPosition::new(v.bar_units, v.bar_ordinal, Units(0.0))
+        BarPosition::new(v.bar_units, v.bar_ordinal, v.bar_pos, Units(0.0))
     }
 }
 
@@ -47,6 +48,8 @@ pub struct TabBar {
     pub section: Arc<Section>,
     pub lanes: HashMap<(LaneKind, usize), Arc<BarLane>>,
     pub proto: Arc<Bar>,
+    /// The tab meta with the meta entries up to this bar applied.
+    pub meta: Arc<TabMeta>,
     pub props: TabBarProps,
 }
 impl Display for TabBar {
@@ -63,23 +66,21 @@ impl Display for TabBar {
     }
 }
 impl TabBar {
+    /// The section index and bar units of the props are set from the section
+    /// and the meta.
     pub fn new_arc(
         tab: Weak<Tab>,
         section: Arc<Section>,
         bar: Arc<Bar>,
-        section_round: usize,
-        section_ordinal: usize,
-        bar_index: usize,
-        bar_ordinal: usize,
-        bar_number: usize,
-        bar_units: Units,
+        props: TabBarProps,
+        meta: Arc<TabMeta>,
     ) -> Arc<Self> {
         Arc::<Self>::new_cyclic(|weak_self| {
             let mut lanes: HashMap<(LaneKind, usize), Arc<BarLane>> = HashMap::new();
             let mut index = 0;
             for layer in bar.layers.iter() {
                 for slice in layer.slices.iter() {
-                    if slice.in_round(section_round) {
+                    if slice.in_round(props.section_round) {
                         if let Some(new_lane) = BarLane::try_new_arc(
                             weak_self.clone(),
                             index,
@@ -102,36 +103,27 @@ impl TabBar {
             }
             let props = TabBarProps {
                 section_index: section.index,
-                section_round,
-                section_ordinal,
-                bar_index,
-                bar_ordinal,
-                bar_number,
-                bar_units,
+                bar_units: meta.bar_units(),
+                ..props
             };
             Self {
                 tab: tab,
                 section: section,
                 proto: bar,
                 lanes,
+                meta,
                 props,
             }
         })
     }
     pub fn tab_position(&self) -> TabPosition {
-        TabPosition::new(Units(self.props.bar_ordinal as f32 * self.bar_units().0))
+        TabPosition::new(self.props.bar_pos)
     }
     pub fn tab_meta(&self) -> Arc<TabMeta> {
-        match self.tab.upgrade() {
-            Some(tab) => tab.meta.clone(),
-            None => {
-                println!("<TabBar>.bar_units() tab_meta missing: {}", self);
-                Arc::new(TabMeta::default())
-            }
-        }
+        self.meta.clone()
     }
     pub fn bar_units(&self) -> Units {
-        self.tab_meta().bar_units()
+        self.props.bar_units
     }
     pub fn bar_beats(&self) -> u8 {
         self.tab_meta().signature.bar_beats
@@ -148,6 +140,19 @@ impl TabBar {
     pub fn calc_note(&self, pitch: &Pitch, octave: &Octave) -> Note {
         self.tab_meta().calc_note_from_pitch(pitch, octave)
     }
+    /// Notes in tones got their syllables from the key of the tab when parsed,
+    /// they are re-calculated in bars after a key or scale change.
+    pub fn rekey_note(&self, note: &Note) -> Note {
+        let changed = match self.tab.upgrade() {
+            Some(tab) => tab.meta.key != self.meta.key || tab.meta.scale != self.meta.scale,
+            None => false,
+        };
+        if changed {
+            self.meta.rekey_note(note)
+        } else {
+            *note
+        }
+    }
 }
 impl TabBar {
     pub fn tab(&self) -> Option<Arc<Tab>> {
@@ -302,3 +307,27 @@ impl_get_fretted_shape!(
     Fretboard4,
     HandShape4
 );
+impl_get_fretted_shape!(
+    get_fretted_shape5,
+    5,
+    as_fretted5,
+    get_fretboard5,
+    Fretboard5,
+    HandShape5
+);
+impl_get_fretted_shape!(
+    get_fretted_shape7,
+    7,
+    as_fretted7,
+    get_fretboard7,
+    Fretboard7,
+    HandShape7
+);
+impl_get_fretted_shape!(
+    get_fretted_shape8,
+    8,
+    as_fretted8,
+    get_fretboard8,
+    Fretboard8,
+    HandShape8
+);
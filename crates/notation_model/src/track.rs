@@ -5,7 +5,8 @@ use std::sync::{Arc, Weak};
 use notation_proto::prelude::Chord;
 
 use crate::prelude::{
-    Fretboard4, Fretboard6, ModelEntry, SliceBegin, SliceEnd, Tab, TabChord, TrackKind,
+    Fretboard4, Fretboard5, Fretboard6, Fretboard7, Fretboard8, ModelEntry, SliceBegin, SliceEnd,
+    Tab, TabChord, TrackKind,
 };
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -140,6 +141,21 @@ impl Track {
     }
 }
 
+impl Track {
+    /// String number of the fretted entries, `None` if the track isn't fretted.
+    pub fn fretted_string_num(&self) -> Option<usize> {
+        self.get_entry(&|x: &ModelEntry| x.proto.fretted_string_num())
+    }
+    pub fn get_fretboard_capo(&self) -> Option<u8> {
+        self.get_fretboard6()
+            .map(|x| x.capo)
+            .or_else(|| self.get_fretboard7().map(|x| x.capo))
+            .or_else(|| self.get_fretboard8().map(|x| x.capo))
+            .or_else(|| self.get_fretboard5().map(|x| x.capo))
+            .or_else(|| self.get_fretboard4().map(|x| x.capo))
+    }
+}
+
 macro_rules! impl_get_fretboard {
     ($name:ident, $strings:literal, $as_fretted:ident, $fretboard:ident) => {
         impl Track {
@@ -155,3 +171,6 @@ macro_rules! impl_get_fretboard {
 
 impl_get_fretboard!(get_fretboard6, 6, as_fretted6, Fretboard6);
 impl_get_fretboard!(get_fretboard4, 4, as_fretted4, Fretboard4);
+impl_get_fretboard!(get_fretboard5, 5, as_fretted5, Fretboard5);
+impl_get_fretboard!(get_fretboard7, 7, as_fretted7, Fretboard7);
+impl_get_fretboard!(get_fretboard8, 8, as_fretted8, Fretboard8);
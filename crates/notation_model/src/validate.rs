@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use thiserror::Error;
+
+use notation_proto::prelude::{
+    HandShape4, HandShape5, HandShape6, HandShape7, HandShape8, MetaEntry, ProtoEntry, Rational,
+    Slice, SliceBegin, SliceEnd,
+};
+
+use crate::prelude::{LaneKind, ProtoTab, ProtoTrack, Tab};
+
+/// Where the problem is in the proto tab, all indices are 0-based, as in the
+/// `sections`, `bars`, `slices` and `entries` vectors.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DiagnosticLocation {
+    pub section_index: usize,
+    pub section_id: String,
+    pub bar_index: usize,
+    pub track_id: String,
+    pub slice_index: Option<usize>,
+    pub entry_index: Option<usize>,
+}
+impl Display for DiagnosticLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{} B:{} {}",
+            self.section_index, self.section_id, self.bar_index, self.track_id
+        )?;
+        if let Some(slice_index) = self.slice_index {
+            write!(f, " S:{}", slice_index)?;
+        }
+        if let Some(entry_index) = self.entry_index {
+            write!(f, " E:{}", entry_index)?;
+        }
+        Ok(())
+    }
+}
+impl DiagnosticLocation {
+    pub fn with_entry(&self, entry_index: usize) -> Self {
+        Self {
+            entry_index: Some(entry_index),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Error, Clone, PartialEq, Debug)]
+pub enum DiagnosticKind {
+    #[error("track not found: {0}")]
+    TrackNotFound(String),
+    #[error("mark not found: {0}")]
+    MarkNotFound(String),
+    #[error("bar units mismatch: {0} != {1}")]
+    BarUnitsMismatch(Rational, Rational),
+    #[error("fret out of range: string {0} fret {1} >= {2}")]
+    FretOutOfRange(u8, u8, usize),
+    #[error("pick on muted string: {0}")]
+    PickOnMutedString(u8),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TabDiagnostic {
+    pub location: DiagnosticLocation,
+    pub kind: DiagnosticKind,
+}
+impl Display for TabDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<TabDiagnostic>({} {})", self.location, self.kind)
+    }
+}
+impl TabDiagnostic {
+    pub fn new(location: DiagnosticLocation, kind: DiagnosticKind) -> Self {
+        Self { location, kind }
+    }
+}
+
+/// Entries of a slice, resolved the same way as `Track::get_entries()`.
+struct ValidateSlice<'a> {
+    location: DiagnosticLocation,
+    slice: &'a Slice,
+    entries: Vec<(usize, &'a ProtoEntry)>,
+    lane_kind: LaneKind,
+    length: Rational,
+}
+
+impl Tab {
+    /// Checks the things that `Tab::try_parse_arc()` drops silently, or that
+    /// are rendered as empty bars. Signature changes are followed in the
+    /// order of the sections, not the form.
+    pub fn validate(proto: &ProtoTab) -> Vec<TabDiagnostic> {
+        let mut bar_length = proto.meta.bar_length();
+        let mut diagnostics = Vec::new();
+        for (section_index, section) in proto.sections.iter().enumerate() {
+            for (bar_index, bar) in section.bars.iter().enumerate() {
+                let mut track_slices: HashMap<&str, Vec<ValidateSlice>> = HashMap::new();
+                let mut signature = None;
+                for layer in bar.layers.iter() {
+                    let location = DiagnosticLocation {
+                        section_index,
+                        section_id: section.id.clone(),
+                        bar_index,
+                        track_id: layer.track.clone(),
+                        slice_index: None,
+                        entry_index: None,
+                    };
+                    let track = match proto.tracks.iter().find(|x| x.id == layer.track) {
+                        Some(track) => track,
+                        None => {
+                            diagnostics.push(TabDiagnostic::new(
+                                location,
+                                DiagnosticKind::TrackNotFound(layer.track.clone()),
+                            ));
+                            continue;
+                        }
+                    };
+                    for (slice_index, slice) in layer.slices.iter().enumerate() {
+                        let location = DiagnosticLocation {
+                            slice_index: Some(slice_index),
+                            ..location.clone()
+                        };
+                        let entries = match Self::validate_slice_entries(track, slice) {
+                            Ok(entries) => entries,
+                            Err(mark) => {
+                                diagnostics.push(TabDiagnostic::new(
+                                    location,
+                                    DiagnosticKind::MarkNotFound(mark),
+                                ));
+                                continue;
+                            }
+                        };
+                        let lane_kind = entries
+                            .iter()
+                            .map(|x| LaneKind::of_entry(&track.kind, x.1))
+                            .find(|x| !x.is_none())
+                            .unwrap_or_default();
+                        let length = entries.iter().map(|x| Rational::from(x.1.duration())).sum();
+                        // the last signature in the order of the layers wins
+                        for (_, entry) in entries.iter() {
+                            if let Some(MetaEntry::Signature(x)) = entry.as_meta() {
+                                signature = Some(*x);
+                            }
+                        }
+                        track_slices
+                            .entry(track.id.as_str())
+                            .or_default()
+                            .push(ValidateSlice {
+                                location,
+                                slice,
+                                entries,
+                                lane_kind,
+                                length,
+                            });
+                    }
+                }
+                if let Some(signature) = signature {
+                    bar_length = Rational::from(signature);
+                }
+                for (track_id, slices) in track_slices.iter() {
+                    Self::validate_bar_length(&mut diagnostics, slices, bar_length);
+                    if let Some(track) = proto.tracks.iter().find(|x| x.id == *track_id) {
+                        Self::validate_fretted6(&mut diagnostics, track, slices);
+                        Self::validate_fretted4(&mut diagnostics, track, slices);
+                        Self::validate_fretted5(&mut diagnostics, track, slices);
+                        Self::validate_fretted7(&mut diagnostics, track, slices);
+                        Self::validate_fretted8(&mut diagnostics, track, slices);
+                    }
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+impl Tab {
+    fn index_of_mark(track: &ProtoTrack, begin: usize, mark: &String) -> Option<usize> {
+        track
+            .entries
+            .iter()
+            .enumerate()
+            .skip(begin)
+            .find(|x| x.1.is_mark_string(mark))
+            .map(|x| x.0)
+    }
+    /// Returns the missing mark as error.
+    fn validate_slice_entries<'a>(
+        track: &'a ProtoTrack,
+        slice: &Slice,
+    ) -> Result<Vec<(usize, &'a ProtoEntry)>, String> {
+        let find_mark = |begin: usize, mark: &String| {
+            Self::index_of_mark(track, begin, mark).ok_or_else(|| mark.clone())
+        };
+        let (index, count) = match (&slice.begin, &slice.end) {
+            (SliceBegin::Mark(x), SliceEnd::Mark(y)) => {
+                let index = find_mark(0, x)? + 1;
+                (index, find_mark(index, y)? - index)
+            }
+            (SliceBegin::Mark(x), SliceEnd::Count(y)) => (find_mark(0, x)? + 1, *y),
+            (SliceBegin::Index(x), SliceEnd::Mark(y)) => {
+                (*x, find_mark(*x, y)?.saturating_sub(1 + *x))
+            }
+            (SliceBegin::Index(x), SliceEnd::Count(y)) => (*x, *y),
+        };
+        Ok(track
+            .entries
+            .iter()
+            .enumerate()
+            .skip(index)
+            .take(count)
+            .collect())
+    }
+    /// Slices of the same lane are merged, all starting from the beginning of
+    /// the bar, so the longest one should fill the bar.
+    fn validate_bar_length(
+        diagnostics: &mut Vec<TabDiagnostic>,
+        slices: &[ValidateSlice],
+        bar_length: Rational,
+    ) {
+        for round in Self::validate_rounds(slices) {
+            let mut lanes: HashMap<LaneKind, &ValidateSlice> = HashMap::new();
+            for slice in slices.iter().filter(|x| x.slice.in_round(round)) {
+                if slice.lane_kind.is_none() {
+                    continue;
+                }
+                match lanes.get(&slice.lane_kind) {
+                    Some(longest) if slice.length <= longest.length => {}
+                    _ => {
+                        lanes.insert(slice.lane_kind, slice);
+                    }
+                }
+            }
+            for slice in lanes.values() {
+                if slice.length != bar_length {
+                    let diagnostic = TabDiagnostic::new(
+                        slice.location.clone(),
+                        DiagnosticKind::BarUnitsMismatch(slice.length, bar_length),
+                    );
+                    if !diagnostics.contains(&diagnostic) {
+                        diagnostics.push(diagnostic);
+                    }
+                }
+            }
+        }
+    }
+    /// Slices are only played in their rounds, so each round is checked on its
+    /// own, the last one is for the rounds that are not listed.
+    fn validate_rounds(slices: &[ValidateSlice]) -> Vec<usize> {
+        let mut rounds: Vec<usize> = slices
+            .iter()
+            .flat_map(|x| x.slice.rounds.clone().unwrap_or_default())
+            .collect();
+        rounds.sort_unstable();
+        rounds.dedup();
+        rounds.push(rounds.last().map(|x| x + 1).unwrap_or(1));
+        rounds
+    }
+}
+
+macro_rules! impl_validate_fretted {
+    ($name:ident, $strings:literal, $as_fretted:ident, $hand_shape:ident) => {
+        impl Tab {
+            fn $name(
+                diagnostics: &mut Vec<TabDiagnostic>,
+                track: &ProtoTrack,
+                slices: &[ValidateSlice],
+            ) {
+                let fretboard = match track
+                    .entries
+                    .iter()
+                    .find_map(|x| x.$as_fretted().and_then(|x| x.as_fretboard()))
+                {
+                    Some(fretboard) => *fretboard,
+                    None => return,
+                };
+                let fret_num = fretboard.fret_num();
+                let mut push = |diagnostic: TabDiagnostic| {
+                    if !diagnostics.contains(&diagnostic) {
+                        diagnostics.push(diagnostic);
+                    }
+                };
+                for round in Self::validate_rounds(slices) {
+                    let mut shapes: Vec<(Rational, Rational, $hand_shape)> = Vec::new();
+                    let mut picks = Vec::new();
+                    for slice in slices.iter().filter(|x| x.slice.in_round(round)) {
+                        let mut pos = Rational::ZERO;
+                        for (index, entry) in slice.entries.iter() {
+                            let length = Rational::from(entry.duration());
+                            if let Some(fretted) = entry.$as_fretted() {
+                                if let Some(shape) = fretted.as_shape() {
+                                    for string in 1..=$strings {
+                                        match shape.string_fret_with_barre(string) {
+                                            Some(fret) if fret as usize >= fret_num => {
+                                                push(TabDiagnostic::new(
+                                                    slice.location.with_entry(*index),
+                                                    DiagnosticKind::FretOutOfRange(
+                                                        string, fret, fret_num,
+                                                    ),
+                                                ))
+                                            }
+                                            _ => {}
+                                        }
+                                    }
+                                    shapes.push((pos, pos + length, *shape));
+                                }
+                                if let Some(pick) = fretted.as_pick() {
+                                    picks.push((pos, slice.location.with_entry(*index), *pick));
+                                }
+                            }
+                            pos += length;
+                        }
+                    }
+                    for (pos, location, pick) in picks {
+                        let shape = shapes
+                            .iter()
+                            .find(|x| x.0 <= pos && x.1 > pos)
+                            .map(|x| x.2)
+                            .unwrap_or_default();
+                        for note in pick.get_notes() {
+                            if note.string == 0 || note.string > $strings {
+                                continue;
+                            }
+                            match note.fret {
+                                Some(fret) if fret as usize >= fret_num => {
+                                    push(TabDiagnostic::new(
+                                        location.clone(),
+                                        DiagnosticKind::FretOutOfRange(note.string, fret, fret_num),
+                                    ));
+                                }
+                                Some(_) => {}
+                                None => {
+                                    if shape.string_fret(note.string).is_none() {
+                                        push(TabDiagnostic::new(
+                                            location.clone(),
+                                            DiagnosticKind::PickOnMutedString(note.string),
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_validate_fretted!(validate_fretted6, 6, as_fretted6, HandShape6);
+impl_validate_fretted!(validate_fretted4, 4, as_fretted4, HandShape4);
+impl_validate_fretted!(validate_fretted5, 5, as_fretted5, HandShape5);
+impl_validate_fretted!(validate_fretted7, 7, as_fretted7, HandShape7);
+impl_validate_fretted!(validate_fretted8, 8, as_fretted8, HandShape8);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn new_fretted_tab(fretboard: Fretboard6, entry: FrettedEntry6) -> ProtoTab {
+        let entries = vec![
+            ProtoEntry::from(FrettedEntry6::from(fretboard)),
+            ProtoEntry::from(entry),
+        ];
+        let track = ProtoTrack::new("guitar".to_string(), TrackKind::Guitar, entries);
+        let slice = Slice::new(SliceBegin::Index(0), SliceEnd::Count(2), None);
+        let bar = ProtoBar::new(vec![ProtoBarLayer::new("guitar".to_string(), vec![slice])]);
+        let section = ProtoSection::new("A".to_string(), SectionKind::Verse, vec![bar]);
+        ProtoTab::new(
+            &ProtoTab::new_uuid(),
+            TabMeta::default(),
+            vec![track],
+            vec![section],
+            ProtoForm::from(vec!["A"]),
+        )
+    }
+
+    #[test]
+    fn test_pick_fret_out_of_range() {
+        let fretboard = GuitarUtil::new_acoustic_guitar_fretboard(None);
+        let fret_num = fretboard.fret_num();
+        let validate = |fret: u8| {
+            let pick = Pick::from(PickNote::new_string_fret(1, fret));
+            let entry = FrettedEntry6::Pick(pick, Duration::Simple(Unit::Whole));
+            Tab::validate(&new_fretted_tab(fretboard, entry))
+        };
+        assert!(validate(0).is_empty());
+        assert!(validate(fret_num as u8 - 1).is_empty());
+        let diagnostics = validate(fret_num as u8);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].kind,
+            DiagnosticKind::FretOutOfRange(1, fret_num as u8, fret_num)
+        );
+    }
+
+    #[test]
+    fn test_shape_fret_out_of_range() {
+        let fretboard = GuitarUtil::new_acoustic_guitar_fretboard(None).with_capo(2);
+        let fret_num = fretboard.fret_num();
+        let validate = |fret: u8| {
+            let shape = HandShape6::from([None, None, None, None, Some(fret), None]);
+            let entry = FrettedEntry6::Shape(shape, Duration::Simple(Unit::Whole));
+            Tab::validate(&new_fretted_tab(fretboard, entry))
+        };
+        assert!(validate(fret_num as u8 - 1).is_empty());
+        let diagnostics = validate(fret_num as u8);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].kind,
+            DiagnosticKind::FretOutOfRange(5, fret_num as u8, fret_num)
+        );
+    }
+}
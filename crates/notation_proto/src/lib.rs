@@ -17,7 +17,7 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::lyric_entry::{LyricEntry, LyricWord};
     #[doc(hidden)]
-    pub use crate::position::{BarPosition, Position, TabPosition};
+    pub use crate::position::{BarLayout, BarPosition, Position, TabPosition};
     #[doc(hidden)]
     pub use crate::proto_entry::ProtoEntry;
     #[doc(hidden)]
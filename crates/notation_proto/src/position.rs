@@ -3,7 +3,7 @@ use std::fmt::Display;
 use notation_core::prelude::EntryPassMode;
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::Units;
+use crate::prelude::{Rational, Units};
 
 #[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub struct TabPosition {
@@ -32,6 +32,8 @@ impl From<TabPosition> for Units {
 pub struct BarPosition {
     pub bar_units: Units,
     pub bar_ordinal: usize,
+    /// Begin of the bar in the tab, bars can have different units.
+    pub bar_pos: Units,
     pub in_bar_pos: Units,
 }
 impl Display for BarPosition {
@@ -45,30 +47,137 @@ impl Display for BarPosition {
 }
 impl From<BarPosition> for Units {
     fn from(v: BarPosition) -> Self {
-        v.bar_pos() + v.in_bar_pos
+        v.bar_pos + v.in_bar_pos
     }
 }
 impl BarPosition {
     pub const ZERO: Self = Self {
         bar_units: Units(0.0),
         bar_ordinal: 0,
+        bar_pos: Units(0.0),
         in_bar_pos: Units(0.0),
     };
-    pub fn new(bar_units: Units, bar_ordinal: usize, in_bar_pos: Units) -> Self {
+    pub fn new(bar_units: Units, bar_ordinal: usize, bar_pos: Units, in_bar_pos: Units) -> Self {
         Self {
             bar_units,
             bar_ordinal,
+            bar_pos,
             in_bar_pos,
         }
     }
     pub fn with_in_bar_pos(&self, in_bar_pos: Units) -> Self {
-        Self::new(self.bar_units, self.bar_ordinal, in_bar_pos)
+        Self::new(self.bar_units, self.bar_ordinal, self.bar_pos, in_bar_pos)
     }
     pub fn with_delay(&self, delay: Units) -> Self {
-        Self::new(self.bar_units, self.bar_ordinal, self.in_bar_pos + delay)
+        Self::new(
+            self.bar_units,
+            self.bar_ordinal,
+            self.bar_pos,
+            self.in_bar_pos + delay,
+        )
     }
-    pub fn bar_pos(&self) -> Units {
-        Units(self.bar_ordinal as f32 * self.bar_units.0)
+}
+
+/// Lengths of all the bars in a tab, which change with the signature. Positions
+/// after the last bar are counted with the length of the last bar.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct BarLayout {
+    bar_lengths: Vec<Rational>,
+    /// Summed up exactly, so long tabs don't drift.
+    bar_begins: Vec<Rational>,
+}
+impl Display for BarLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "<BarLayout>(B:{} L:{})",
+            self.bar_lengths.len(),
+            self.total_length()
+        )
+    }
+}
+impl BarLayout {
+    pub fn new(bar_lengths: Vec<Rational>) -> Self {
+        let mut pos = Rational::ZERO;
+        let bar_begins = bar_lengths
+            .iter()
+            .map(|x| {
+                let begin = pos;
+                pos += *x;
+                begin
+            })
+            .collect();
+        Self {
+            bar_lengths,
+            bar_begins,
+        }
+    }
+    pub fn new_uniform(bar_length: Rational, bars: usize) -> Self {
+        Self::new(vec![bar_length; bars])
+    }
+    pub fn bars(&self) -> usize {
+        self.bar_lengths.len()
+    }
+    pub fn total_length(&self) -> Rational {
+        match (self.bar_begins.last(), self.bar_lengths.last()) {
+            (Some(begin), Some(length)) => *begin + *length,
+            _ => Rational::ZERO,
+        }
+    }
+    pub fn total_units(&self) -> Units {
+        Units::from(self.total_length())
+    }
+    pub fn calc_bar_length(&self, bar_ordinal: usize) -> Rational {
+        self.bar_lengths
+            .get(bar_ordinal)
+            .or_else(|| self.bar_lengths.last())
+            .copied()
+            .unwrap_or(Rational::ZERO)
+    }
+    pub fn calc_bar_units(&self, bar_ordinal: usize) -> Units {
+        Units::from(self.calc_bar_length(bar_ordinal))
+    }
+    pub fn calc_bar_begin(&self, bar_ordinal: usize) -> Rational {
+        match self.bar_begins.get(bar_ordinal) {
+            Some(begin) => *begin,
+            None => {
+                let extra = Rational::from((bar_ordinal - self.bars()) as i64);
+                self.total_length() + extra * self.calc_bar_length(bar_ordinal)
+            }
+        }
+    }
+    pub fn calc_bar_pos(&self, bar_ordinal: usize) -> Units {
+        Units::from(self.calc_bar_begin(bar_ordinal))
+    }
+    /// The begins are converted to units before comparing, so a position set
+    /// from the begin of a bar is always in that bar.
+    pub fn calc_bar_ordinal(&self, pos: Units) -> usize {
+        let total_units = self.total_units();
+        if pos.0 >= total_units.0 {
+            let units = self.calc_bar_units(self.bars()).0;
+            if units <= 0.0 {
+                return self.bars();
+            }
+            let extra = (pos.0 - total_units.0) / units;
+            return self.bars() + extra.trunc() as usize;
+        }
+        match self.bar_begins.binary_search_by(|x| {
+            Units::from(*x)
+                .0
+                .partial_cmp(&pos.0)
+                .unwrap_or(std::cmp::Ordering::Less)
+        }) {
+            Ok(index) => index,
+            Err(index) => index.saturating_sub(1),
+        }
+    }
+    pub fn calc_bar_position(&self, bar_ordinal: usize, in_bar_pos: Units) -> BarPosition {
+        BarPosition::new(
+            self.calc_bar_units(bar_ordinal),
+            bar_ordinal,
+            self.calc_bar_pos(bar_ordinal),
+            in_bar_pos,
+        )
     }
 }
 
@@ -96,25 +205,18 @@ impl Position {
     pub fn new(bar_units: Units) -> Self {
         Self {
             tab: TabPosition::new(Units(0.0)),
-            bar: BarPosition::new(bar_units, 0, Units(0.0)),
+            bar: BarPosition::new(bar_units, 0, Units(0.0), Units(0.0)),
         }
     }
-    pub fn calc_bar_ordinal(&self, pos: Units) -> usize {
-        let bar = pos.0 / self.bar.bar_units.0;
-        bar.trunc() as usize
-    }
-    pub fn cal_bar_pos(&self, bar_ordinal: usize) -> Units {
-        Units(bar_ordinal as f32 * self.bar.bar_units.0)
-    }
-    pub fn set_in_tab(&mut self, pos: Units) {
+    pub fn set_in_tab(&mut self, layout: &BarLayout, pos: Units) {
+        let bar_ordinal = layout.calc_bar_ordinal(pos);
         self.tab = TabPosition::new(pos);
-        self.bar.bar_ordinal = self.calc_bar_ordinal(pos);
-        self.bar.in_bar_pos = self.tab.in_tab_pos - self.cal_bar_pos(self.bar.bar_ordinal);
+        self.bar = layout.calc_bar_position(bar_ordinal, Units(0.0));
+        self.bar.in_bar_pos = pos - self.bar.bar_pos;
     }
-    pub fn set_in_bar(&mut self, bar_ordinal: usize, in_bar_pos: Units) {
-        self.tab = TabPosition::new(self.cal_bar_pos(bar_ordinal) + in_bar_pos);
-        self.bar.bar_ordinal = bar_ordinal;
-        self.bar.in_bar_pos = in_bar_pos;
+    pub fn set_in_bar(&mut self, layout: &BarLayout, bar_ordinal: usize, in_bar_pos: Units) {
+        self.bar = layout.calc_bar_position(bar_ordinal, in_bar_pos);
+        self.tab = TabPosition::from(self.bar);
     }
     pub fn _is_passed(&self, pass_mode: EntryPassMode, in_tab_pos: Units) -> bool {
         match pass_mode {
@@ -123,8 +225,7 @@ impl Position {
         }
     }
     pub fn is_passed(&self, pass_mode: EntryPassMode, pos: &BarPosition) -> bool {
-        let in_tab_pos = self.cal_bar_pos(pos.bar_ordinal) + pos.in_bar_pos;
-        self._is_passed(pass_mode, in_tab_pos)
+        self._is_passed(pass_mode, Units::from(*pos))
     }
     pub fn is_passed_with(
         &self,
@@ -132,7 +233,7 @@ impl Position {
         pos: &BarPosition,
         units: Units,
     ) -> bool {
-        let in_tab_pos = self.cal_bar_pos(pos.bar_ordinal) + pos.in_bar_pos + units;
+        let in_tab_pos = Units::from(*pos) + units;
         self._is_passed(pass_mode, in_tab_pos)
     }
 }
@@ -2,10 +2,12 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-use notation_core::prelude::{CoreEntry, Duration, Entry, EntryPassMode, MetaEntry};
-use notation_fretted::prelude::{FrettedEntry4, FrettedEntry6};
+use notation_core::prelude::{CoreEntry, Duration, Entry, EntryPassMode, MetaEntry, Semitones};
+use notation_fretted::prelude::{
+    FrettedEntry4, FrettedEntry5, FrettedEntry6, FrettedEntry7, FrettedEntry8, FrettedTranspose,
+};
 
-use crate::prelude::LyricEntry;
+use crate::prelude::{LyricEntry, TabMeta};
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub enum ProtoEntry {
@@ -15,6 +17,9 @@ pub enum ProtoEntry {
     Lyric(LyricEntry),
     Fretted6(FrettedEntry6),
     Fretted4(FrettedEntry4),
+    Fretted5(FrettedEntry5),
+    Fretted7(FrettedEntry7),
+    Fretted8(FrettedEntry8),
     Extra(String, String),
 }
 impl Display for ProtoEntry {
@@ -26,6 +31,9 @@ impl Display for ProtoEntry {
             ProtoEntry::Lyric(x) => write!(f, "Lyric({})", x),
             ProtoEntry::Fretted6(x) => write!(f, "Fretted6({})", x),
             ProtoEntry::Fretted4(x) => write!(f, "Fretted4({})", x),
+            ProtoEntry::Fretted5(x) => write!(f, "Fretted5({})", x),
+            ProtoEntry::Fretted7(x) => write!(f, "Fretted7({})", x),
+            ProtoEntry::Fretted8(x) => write!(f, "Fretted8({})", x),
             ProtoEntry::Extra(x, y) => write!(f, "Extra({}, {})", x, y),
         }
     }
@@ -39,6 +47,9 @@ impl ProtoEntry {
             ProtoEntry::Lyric(entry) => entry.duration(),
             ProtoEntry::Fretted6(entry) => entry.duration(),
             ProtoEntry::Fretted4(entry) => entry.duration(),
+            ProtoEntry::Fretted5(entry) => entry.duration(),
+            ProtoEntry::Fretted7(entry) => entry.duration(),
+            ProtoEntry::Fretted8(entry) => entry.duration(),
             ProtoEntry::Extra(_, _) => Duration::Zero,
         }
     }
@@ -50,6 +61,9 @@ impl ProtoEntry {
             ProtoEntry::Lyric(entry) => entry.pass_mode(),
             ProtoEntry::Fretted6(entry) => entry.pass_mode(),
             ProtoEntry::Fretted4(entry) => entry.pass_mode(),
+            ProtoEntry::Fretted5(entry) => entry.pass_mode(),
+            ProtoEntry::Fretted7(entry) => entry.pass_mode(),
+            ProtoEntry::Fretted8(entry) => entry.pass_mode(),
             ProtoEntry::Extra(_, _) => EntryPassMode::Immediate,
         }
     }
@@ -104,11 +118,11 @@ impl ProtoEntry {
             None
         }
     }
-    pub fn try_into_core(self) -> Result<CoreEntry, Self> {
+    pub fn try_into_core(self) -> Result<CoreEntry, Box<Self>> {
         if let Self::Core(v) = self {
             Ok(v)
         } else {
-            Err(self)
+            Err(Box::new(self))
         }
     }
     /// Returns `true` if the proto_entry is [`FrettedSix`].
@@ -122,11 +136,11 @@ impl ProtoEntry {
             None
         }
     }
-    pub fn try_into_fretted6(self) -> Result<FrettedEntry6, Self> {
+    pub fn try_into_fretted6(self) -> Result<FrettedEntry6, Box<Self>> {
         if let Self::Fretted6(v) = self {
             Ok(v)
         } else {
-            Err(self)
+            Err(Box::new(self))
         }
     }
     /// Returns `true` if the proto_entry is [`FrettedFour`].
@@ -140,11 +154,75 @@ impl ProtoEntry {
             None
         }
     }
-    pub fn try_into_fretted4(self) -> Result<FrettedEntry4, Self> {
+    pub fn try_into_fretted4(self) -> Result<FrettedEntry4, Box<Self>> {
         if let Self::Fretted4(v) = self {
             Ok(v)
         } else {
-            Err(self)
+            Err(Box::new(self))
+        }
+    }
+    /// Returns `true` if the proto_entry is [`FrettedFive`].
+    pub fn is_fretted5(&self) -> bool {
+        matches!(self, Self::Fretted5(..))
+    }
+    pub fn as_fretted5(&self) -> Option<&FrettedEntry5> {
+        if let Self::Fretted5(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+    pub fn try_into_fretted5(self) -> Result<FrettedEntry5, Box<Self>> {
+        if let Self::Fretted5(v) = self {
+            Ok(v)
+        } else {
+            Err(Box::new(self))
+        }
+    }
+    /// Returns `true` if the proto_entry is [`FrettedSeven`].
+    pub fn is_fretted7(&self) -> bool {
+        matches!(self, Self::Fretted7(..))
+    }
+    pub fn as_fretted7(&self) -> Option<&FrettedEntry7> {
+        if let Self::Fretted7(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+    pub fn try_into_fretted7(self) -> Result<FrettedEntry7, Box<Self>> {
+        if let Self::Fretted7(v) = self {
+            Ok(v)
+        } else {
+            Err(Box::new(self))
+        }
+    }
+    /// Returns `true` if the proto_entry is [`FrettedEight`].
+    pub fn is_fretted8(&self) -> bool {
+        matches!(self, Self::Fretted8(..))
+    }
+    pub fn as_fretted8(&self) -> Option<&FrettedEntry8> {
+        if let Self::Fretted8(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+    pub fn try_into_fretted8(self) -> Result<FrettedEntry8, Box<Self>> {
+        if let Self::Fretted8(v) = self {
+            Ok(v)
+        } else {
+            Err(Box::new(self))
+        }
+    }
+    pub fn fretted_string_num(&self) -> Option<usize> {
+        match self {
+            Self::Fretted4(_) => Some(4),
+            Self::Fretted5(_) => Some(5),
+            Self::Fretted6(_) => Some(6),
+            Self::Fretted7(_) => Some(7),
+            Self::Fretted8(_) => Some(8),
+            _ => None,
         }
     }
 }
@@ -159,6 +237,53 @@ impl ProtoEntry {
         }
         mark.clone()
     }
+    /// Fret number of the fretboard entries, without the frets under the capo.
+    pub fn fretboard_fret_num(&self) -> Option<usize> {
+        match self {
+            ProtoEntry::Fretted6(entry) => entry.as_fretboard().map(|x| x.fret_num()),
+            ProtoEntry::Fretted4(entry) => entry.as_fretboard().map(|x| x.fret_num()),
+            ProtoEntry::Fretted5(entry) => entry.as_fretboard().map(|x| x.fret_num()),
+            ProtoEntry::Fretted7(entry) => entry.as_fretboard().map(|x| x.fret_num()),
+            ProtoEntry::Fretted8(entry) => entry.as_fretboard().map(|x| x.fret_num()),
+            _ => None,
+        }
+    }
+    /// The meta is the one after transposing, tones are spelled in its key,
+    /// `fret_num` is of the transposed fretboard of the track.
+    pub fn transpose(
+        &self,
+        meta: &TabMeta,
+        semitones: i8,
+        fretted: FrettedTranspose,
+        fret_num: Option<usize>,
+    ) -> Option<Self> {
+        match self {
+            ProtoEntry::Meta(entry) => {
+                Some(entry.transpose(&meta.scale, Semitones(semitones)).into())
+            }
+            ProtoEntry::Core(entry) => Some(
+                entry
+                    .transpose(&meta.scale, &meta.key, Semitones(semitones))
+                    .into(),
+            ),
+            ProtoEntry::Fretted6(entry) => entry
+                .transpose(semitones, fretted, fret_num)
+                .map(Self::from),
+            ProtoEntry::Fretted4(entry) => entry
+                .transpose(semitones, fretted, fret_num)
+                .map(Self::from),
+            ProtoEntry::Fretted5(entry) => entry
+                .transpose(semitones, fretted, fret_num)
+                .map(Self::from),
+            ProtoEntry::Fretted7(entry) => entry
+                .transpose(semitones, fretted, fret_num)
+                .map(Self::from),
+            ProtoEntry::Fretted8(entry) => entry
+                .transpose(semitones, fretted, fret_num)
+                .map(Self::from),
+            _ => Some(self.clone()),
+        }
+    }
 }
 impl Entry for ProtoEntry {
     fn duration(&self) -> Duration {
@@ -234,3 +359,21 @@ impl From<FrettedEntry4> for ProtoEntry {
         ProtoEntry::Fretted4(v)
     }
 }
+
+impl From<FrettedEntry5> for ProtoEntry {
+    fn from(v: FrettedEntry5) -> Self {
+        ProtoEntry::Fretted5(v)
+    }
+}
+
+impl From<FrettedEntry7> for ProtoEntry {
+    fn from(v: FrettedEntry7) -> Self {
+        ProtoEntry::Fretted7(v)
+    }
+}
+
+impl From<FrettedEntry8> for ProtoEntry {
+    fn from(v: FrettedEntry8) -> Self {
+        ProtoEntry::Fretted8(v)
+    }
+}
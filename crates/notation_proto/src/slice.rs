@@ -45,6 +45,33 @@ impl Slice {
     pub fn in_round(&self, round: usize) -> bool {
         !self.not_in_round(round)
     }
+    /// Same slice in a track with `offset` more entries before the indexed ones.
+    pub fn with_index_offset(&self, offset: usize) -> Self {
+        let begin = match &self.begin {
+            SliceBegin::Index(index) => SliceBegin::Index(index + offset),
+            SliceBegin::Mark(mark) => SliceBegin::Mark(mark.clone()),
+        };
+        Self {
+            begin,
+            ..self.clone()
+        }
+    }
+    /// Same slice in a track with the marks renamed with the prefix.
+    pub fn with_mark_prefix(&self, prefix: &str) -> Self {
+        let begin = match &self.begin {
+            SliceBegin::Mark(mark) => SliceBegin::Mark(format!("{}{}", prefix, mark)),
+            SliceBegin::Index(index) => SliceBegin::Index(*index),
+        };
+        let end = match &self.end {
+            SliceEnd::Mark(mark) => SliceEnd::Mark(format!("{}{}", prefix, mark)),
+            SliceEnd::Count(count) => SliceEnd::Count(*count),
+        };
+        Self {
+            begin,
+            end,
+            rounds: self.rounds.clone(),
+        }
+    }
 }
 impl Display for SliceBegin {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
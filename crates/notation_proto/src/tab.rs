@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use std::collections::HashMap;
 use std::fmt::Display;
 
-use crate::prelude::{Form, Section, Track};
+use crate::prelude::{
+    BarLayer, FingeringSettings, Form, Fretboard6, FrettedTranspose, Section, Track,
+    VoicingSettings,
+};
 use notation_core::prelude::{
-    Key, Note, Pitch, Scale, Signature, Syllable, Tempo, Units, Octave,
+    Key, MetaEntry, Note, Pitch, Rational, Scale, Semitones, Signature, Syllable, Tempo, Units, Octave,
 };
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -19,6 +23,9 @@ impl TabMeta {
     pub fn bar_units(&self) -> Units {
         Units::from(self.signature)
     }
+    pub fn bar_length(&self) -> Rational {
+        Rational::from(self.signature)
+    }
     pub fn calc_syllable(&self, pitch: &Pitch) -> Syllable {
         self.scale.calc_syllable(&self.key, pitch)
     }
@@ -28,6 +35,26 @@ impl TabMeta {
     pub fn calc_note_from_syllable(&self, syllable: &Syllable, octave: &Octave) -> Note {
         self.scale.calc_note_from_syllable(&self.key, syllable, octave)
     }
+    /// Meta entries in bars change the meta from that bar onward.
+    pub fn apply(&self, entry: &MetaEntry) -> Self {
+        match entry {
+            MetaEntry::Signature(x) => Self { signature: *x, ..*self },
+            MetaEntry::Tempo(x) => Self { tempo: *x, ..*self },
+            MetaEntry::Scale(x) => Self { scale: *x, ..*self },
+            MetaEntry::Key(x) => Self { key: *x, ..*self },
+        }
+    }
+    /// Syllables of notes are calculated when parsing, need to follow the key
+    /// changes in bars.
+    pub fn rekey_note(&self, note: &Note) -> Note {
+        Note::new(note.octave, note.pitch, self.calc_syllable(&note.pitch))
+    }
+    pub fn transpose(&self, semitones: i8) -> Self {
+        Self {
+            key: self.scale.calc_key_from_semitones(Semitones::from(self.key) + Semitones(semitones)),
+            ..*self
+        }
+    }
 }
 impl Display for TabMeta {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -62,6 +89,8 @@ impl Default for TabMeta {
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct Tab {
     pub uuid: Uuid,
+    #[serde(default)]
+    pub title: Option<String>,
     pub meta: TabMeta,
     pub tracks: Vec<Track>,
     pub sections: Vec<Section>,
@@ -93,12 +122,162 @@ impl Tab {
         let uuid = Uuid::parse_str(uuid).unwrap();
         Self {
             uuid,
+            title: None,
             meta,
             tracks,
             sections,
             form,
         }
     }
+    pub fn with_title(self, title: Option<String>) -> Self {
+        Self { title, ..self }
+    }
+    /// Chords and lyrics are relative to the key, so only the keys and tones
+    /// are changed, tones are spelled in the key of their bars, returns `None`
+    /// if a fretted track has no fretboard, or if the capo or any fret would
+    /// be out of it.
+    pub fn transpose(&self, semitones: i8, fretted: FrettedTranspose) -> Option<Self> {
+        let tracks = self
+            .tracks
+            .iter()
+            .zip(self.calc_entry_metas().iter())
+            .map(|(track, metas)| {
+                let metas: Vec<TabMeta> = metas.iter().map(|x| x.transpose(semitones)).collect();
+                track.transpose(&metas, semitones, fretted)
+            })
+            .collect::<Option<Vec<Track>>>()?;
+        Some(Self {
+            meta: self.meta.transpose(semitones),
+            tracks,
+            ..self.clone()
+        })
+    }
+    /// Meta of the entries of each track, with the meta entries applied in
+    /// the order of the form, from the first bar using the entry, entries not
+    /// in any bar get the meta of the tab.
+    pub fn calc_entry_metas(&self) -> Vec<Vec<TabMeta>> {
+        let mut metas: Vec<Vec<Option<TabMeta>>> = self
+            .tracks
+            .iter()
+            .map(|x| vec![None; x.entries.len()])
+            .collect();
+        let mut section_rounds: HashMap<&String, usize> = HashMap::new();
+        let mut meta = self.meta;
+        for section_id in self.form.sections.iter() {
+            let section = match self.sections.iter().find(|x| &x.id == section_id) {
+                Some(x) => x,
+                None => continue,
+            };
+            let section_round = section_rounds.entry(section_id).or_insert(0);
+            *section_round += 1;
+            for bar in section.bars.iter() {
+                let mut ranges = vec![];
+                for layer in bar.layers.iter() {
+                    let track_index = match self.tracks.iter().position(|x| x.id == layer.track) {
+                        Some(x) => x,
+                        None => continue,
+                    };
+                    for slice in layer.slices.iter() {
+                        if slice.in_round(*section_round) {
+                            let range = self.tracks[track_index].calc_slice_range(slice);
+                            ranges.push((track_index, range));
+                        }
+                    }
+                }
+                for (track_index, range) in ranges.iter() {
+                    for entry in self.tracks[*track_index].entries[range.clone()].iter() {
+                        if let Some(meta_entry) = entry.as_meta() {
+                            meta = meta.apply(meta_entry);
+                        }
+                    }
+                }
+                for (track_index, range) in ranges {
+                    for index in range {
+                        metas[track_index][index].get_or_insert(meta);
+                    }
+                }
+            }
+        }
+        metas
+            .into_iter()
+            .map(|x| x.into_iter().map(|x| x.unwrap_or(self.meta)).collect())
+            .collect()
+    }
+    /// Adds a guitar track picking the tones of the source track, in the same
+    /// bars, returns `None` if the source is missing or the id is taken.
+    pub fn add_fingered_track(
+        &self,
+        source: &str,
+        id: &str,
+        fretboard: Fretboard6,
+        settings: &FingeringSettings,
+    ) -> Option<Self> {
+        if self.tracks.iter().any(|x| x.id == id) {
+            return None;
+        }
+        let track = self.tracks.iter().find(|x| x.id == source)?;
+        let mut tracks = self.tracks.clone();
+        tracks.push(track.new_fingered_track(id.to_string(), &self.meta, fretboard, settings));
+        let mut sections = self.sections.clone();
+        for bar in sections.iter_mut().flat_map(|x| x.bars.iter_mut()) {
+            let layers: Vec<BarLayer> = bar
+                .layers
+                .iter()
+                .filter(|x| x.track == source)
+                .map(|x| {
+                    // The fretboard is added as the first entry.
+                    let slices = x.slices.iter().map(|x| x.with_index_offset(1)).collect();
+                    BarLayer::new(id.to_string(), slices)
+                })
+                .collect();
+            bar.layers.extend(layers);
+        }
+        Some(Self {
+            tracks,
+            sections,
+            ..self.clone()
+        })
+    }
+    /// Adds a guitar track playing the chords of the source track, in the same
+    /// bars, returns `None` if the source is missing or the id is taken.
+    pub fn add_voiced_track(
+        &self,
+        source: &str,
+        id: &str,
+        fretboard: Fretboard6,
+        settings: &VoicingSettings,
+    ) -> Option<Self> {
+        if self.tracks.iter().any(|x| x.id == id) {
+            return None;
+        }
+        let track = self.tracks.iter().find(|x| x.id == source)?;
+        let mut tracks = self.tracks.clone();
+        tracks.push(track.new_voiced_track(id.to_string(), &self.meta, fretboard, settings));
+        // The fretboard is added as the first entry, then the shapes and the picks.
+        let pick_offset = 1 + track.entries.len();
+        let mut sections = self.sections.clone();
+        for bar in sections.iter_mut().flat_map(|x| x.bars.iter_mut()) {
+            let layers: Vec<BarLayer> = bar
+                .layers
+                .iter()
+                .filter(|x| x.track == source)
+                .map(|x| {
+                    let shapes = x.slices.iter().map(|x| x.with_index_offset(1));
+                    let picks = x.slices.iter().map(|x| {
+                        x.with_index_offset(pick_offset)
+                            .with_mark_prefix(Track::PICK_MARK_PREFIX)
+                    });
+                    BarLayer::new(id.to_string(), shapes.chain(picks).collect())
+                })
+                .collect();
+            bar.layers.extend(layers);
+        }
+        Some(Self {
+            tracks,
+            sections,
+            ..self.clone()
+        })
+    }
     pub fn new_empty() -> Self {
         Self::new(
             Self::new_uuid().as_str(),
@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
 
 use std::fmt::Display;
+use std::ops::Range;
 
-use crate::prelude::ProtoEntry;
+use crate::prelude::{
+    CoreEntry, FingeringSettings, Fretboard6, FrettedEntry6, FrettedTranspose, Pick, PickNote,
+    ProtoEntry, Slice, SliceBegin, SliceEnd, TabMeta, Tone, VoicingSettings,
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub enum TrackKind {
@@ -16,6 +20,8 @@ pub enum TrackKind {
     Piano,
     Drums,
     Bass,
+    Banjo,
+    Mandolin,
 }
 impl Display for TrackKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -23,6 +29,13 @@ impl Display for TrackKind {
     }
 }
 impl TrackKind {
+    /// Tracks using fretted entries, shown with strings and shapes.
+    pub fn is_fretted(&self) -> bool {
+        matches!(
+            self,
+            Self::Guitar | Self::Bass | Self::Banjo | Self::Mandolin
+        )
+    }
     pub fn from_ident(ident: &str) -> Self {
         match ident {
             "Meta" => Self::Meta,
@@ -34,6 +47,8 @@ impl TrackKind {
             "Piano" => Self::Piano,
             "Drums" => Self::Drums,
             "Bass" => Self::Bass,
+            "Banjo" => Self::Banjo,
+            "Mandolin" => Self::Mandolin,
             _ => {
                 println!("TrackKind::from_ident() Unsupported ident: {}", ident);
                 Self::Unsupported
@@ -52,6 +67,158 @@ impl Track {
     pub fn new(id: String, kind: TrackKind, entries: Vec<ProtoEntry>) -> Self {
         Self { kind, id, entries }
     }
+    pub fn index_of_mark(&self, begin: usize, mark: &String) -> Option<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .skip(begin)
+            .find(|x| x.1.is_mark_string(mark))
+            .map(|x| x.0)
+    }
+    /// Indexes of the entries in the slice, same as the ones of the model,
+    /// empty if a mark is missing.
+    pub fn calc_slice_range(&self, slice: &Slice) -> Range<usize> {
+        let (index, count) = match (&slice.begin, &slice.end) {
+            (SliceBegin::Mark(x), SliceEnd::Mark(y)) => match self.index_of_mark(0, x) {
+                Some(index) => match self.index_of_mark(index + 1, y) {
+                    Some(end) => (index + 1, end - index - 1),
+                    None => (index + 1, 0),
+                },
+                None => (0, 0),
+            },
+            (SliceBegin::Mark(x), SliceEnd::Count(y)) => match self.index_of_mark(0, x) {
+                Some(index) => (index + 1, *y),
+                None => (0, 0),
+            },
+            (SliceBegin::Index(x), SliceEnd::Mark(y)) => match self.index_of_mark(*x, y) {
+                Some(end) => (*x, end.saturating_sub(1 + *x)),
+                None => (*x, 0),
+            },
+            (SliceBegin::Index(x), SliceEnd::Count(y)) => (*x, *y),
+        };
+        let begin = index.min(self.entries.len());
+        begin..(index + count).min(self.entries.len())
+    }
+    /// `metas` are the transposed ones of the entries, fretted tracks need a
+    /// fretboard to be transposed, returns `None` if there is none, or if the
+    /// capo or any fret would be out of it.
+    pub fn transpose(
+        &self,
+        metas: &[TabMeta],
+        semitones: i8,
+        fretted: FrettedTranspose,
+    ) -> Option<Self> {
+        if self.kind.is_fretted()
+            && fretted == FrettedTranspose::Capo
+            && !self
+                .entries
+                .iter()
+                .any(|x| x.fretboard_fret_num().is_some())
+        {
+            return None;
+        }
+        let mut fret_num = None;
+        let mut entries = Vec::with_capacity(self.entries.len());
+        for (entry, meta) in self.entries.iter().zip(metas.iter()) {
+            let entry = entry.transpose(meta, semitones, fretted, fret_num)?;
+            if let Some(x) = entry.fretboard_fret_num() {
+                fret_num = Some(x);
+            }
+            entries.push(entry);
+        }
+        Some(Self::new(self.id.clone(), self.kind, entries))
+    }
+    /// Guitar track picking the tones of this one, e.g. of a vocal melody,
+    /// other entries are kept after the fretboard, tones can't be played on
+    /// the fretboard become rests.
+    pub fn new_fingered_track(
+        &self,
+        id: String,
+        meta: &TabMeta,
+        fretboard: Fretboard6,
+        settings: &FingeringSettings,
+    ) -> Self {
+        let tones: Vec<Tone> = self
+            .entries
+            .iter()
+            .filter_map(|x| match x.as_core() {
+                Some(CoreEntry::Tone(tone, _)) => Some(*tone),
+                _ => None,
+            })
+            .collect();
+        let mut picks = fretboard
+            .calc_fingering(&meta.scale, &meta.key, &tones, settings)
+            .into_iter();
+        let mut entries = vec![ProtoEntry::from(FrettedEntry6::from(fretboard))];
+        for entry in self.entries.iter() {
+            let entry = match entry.as_core() {
+                Some(CoreEntry::Tone(_, duration)) => {
+                    let notes = picks.next().unwrap_or_default();
+                    if notes.is_empty() {
+                        ProtoEntry::from(CoreEntry::Rest(*duration))
+                    } else {
+                        ProtoEntry::from(FrettedEntry6::Pick(Pick::from(notes), *duration))
+                    }
+                }
+                _ => entry.clone(),
+            };
+            entries.push(entry);
+        }
+        Self::new(id, TrackKind::Guitar, entries)
+    }
+    /// Marks of the picks in voiced tracks, to be told from the ones of the shapes.
+    pub const PICK_MARK_PREFIX: &'static str = "pick:";
+    /// Guitar track playing the chords of this one with the best voicings,
+    /// the fretboard is followed by the shapes, then the picks of all the
+    /// sounding strings, with the same durations, chords can't be played on
+    /// the fretboard become rests.
+    pub fn new_voiced_track(
+        &self,
+        id: String,
+        meta: &TabMeta,
+        fretboard: Fretboard6,
+        settings: &VoicingSettings,
+    ) -> Self {
+        let mut shapes = Vec::new();
+        let mut picks = Vec::new();
+        for entry in self.entries.iter() {
+            let (shape, pick) = match (entry, entry.as_core()) {
+                (_, Some(CoreEntry::Chord(chord, duration))) => {
+                    let voicing = fretboard
+                        .calc_voicings(&meta.scale, &meta.key, chord, settings)
+                        .first()
+                        .copied();
+                    match voicing {
+                        Some(shape) => {
+                            let notes: Vec<PickNote> = (1..=6)
+                                .filter(|x| shape.string_fret(*x).is_some())
+                                .map(PickNote::new_string)
+                                .collect();
+                            (
+                                ProtoEntry::from(FrettedEntry6::Shape(shape, *duration)),
+                                ProtoEntry::from(FrettedEntry6::Pick(Pick::from(notes), *duration)),
+                            )
+                        }
+                        None => (
+                            ProtoEntry::from(CoreEntry::Rest(*duration)),
+                            ProtoEntry::from(CoreEntry::Rest(*duration)),
+                        ),
+                    }
+                }
+                (ProtoEntry::Mark(mark), _) => (
+                    entry.clone(),
+                    ProtoEntry::Mark(format!("{}{}", Self::PICK_MARK_PREFIX, mark)),
+                ),
+                _ => (entry.clone(), entry.clone()),
+            };
+            shapes.push(shape);
+            picks.push(pick);
+        }
+        let mut entries = vec![ProtoEntry::from(FrettedEntry6::from(fretboard))];
+        entries.extend(shapes);
+        entries.extend(picks);
+        Self::new(id, TrackKind::Guitar, entries)
+    }
 }
 impl Display for Track {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
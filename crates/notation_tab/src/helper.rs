@@ -1,5 +1,6 @@
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 
 use ron::ser::{to_string_pretty, PrettyConfig};
 
@@ -17,9 +18,19 @@ pub fn print_tab(tab: &Tab) {
     println!("{}", s);
 }
 
-pub fn write_tab(tab: &Tab, path: &'static str) {
+pub fn write_tab<P: AsRef<Path>>(tab: &Tab, path: P) {
+    try_write_tab(tab, path).unwrap();
+}
+
+pub fn try_write_tab<P: AsRef<Path>>(tab: &Tab, path: P) -> std::io::Result<()> {
     let s = convert_tab(tab);
-    let mut file = File::create(path).unwrap();
-    file.write_all(s.as_bytes()).unwrap();
-    println!("Tab written to: `{}` [{}] - {}", path, s.len(), tab);
+    let mut file = File::create(path.as_ref())?;
+    file.write_all(s.as_bytes())?;
+    println!(
+        "Tab written to: `{}` [{}] - {}",
+        path.as_ref().display(),
+        s.len(),
+        tab
+    );
+    Ok(())
 }